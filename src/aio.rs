@@ -0,0 +1,743 @@
+/*!
+基于[tokio]的异步监听器实现，需开启`async` feature
+
+与[`listener::Listener`]共用同一套RESP、RDB以及命令的解析逻辑，区别在于:
+
+- 网络IO均为异步IO，处理AOF时不需要为每个Redis独占一个线程
+- 事件由[`AsyncEventHandler`]进行处理
+- 心跳(`REPLCONF ACK`)由tokio的定时器驱动
+
+RDB的解析依旧是同步进行的，这一阶段通过`block_in_place`在当前的工作线程中执行，处理完毕之后回到异步任务中继续接收AOF，
+所以只能在tokio的multi-thread运行时中使用。
+
+暂不支持TLS以及unix socket连接。
+
+# 示例
+
+```no_run
+use std::sync::Arc;
+use std::sync::atomic::AtomicBool;
+use redis_event::aio::{self, AsyncEventHandler};
+use redis_event::config::Config;
+use redis_event::Event;
+
+struct PrintHandler {}
+
+impl AsyncEventHandler for PrintHandler {
+    async fn handle(&mut self, event: Event<'_>) {
+        if let Event::AOF(cmd) = event {
+            println!("{:?}", cmd);
+        }
+    }
+}
+
+async fn listen(conf: Config) -> std::io::Result<()> {
+    let mut builder = aio::Builder::new();
+    builder.with_config(conf);
+    builder.with_control_flag(Arc::new(AtomicBool::new(true)));
+    builder.with_event_handler(PrintHandler {});
+    let mut listener = builder.build()?;
+    listener.start().await
+}
+```
+
+[tokio]: https://tokio.rs
+[`listener::Listener`]: ../listener/struct.Listener.html
+[`AsyncEventHandler`]: trait.AsyncEventHandler.html
+*/
+use std::future::Future;
+use std::io::{BufReader, Cursor, Error, ErrorKind, Read, Result, Write};
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, AtomicI64, Ordering};
+use std::time::Duration;
+
+use log::{error, info, warn};
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::TcpStream;
+use tokio::net::tcp::{OwnedReadHalf, OwnedWriteHalf};
+use tokio::runtime::Handle;
+use tokio::sync::Mutex;
+use tokio::task::JoinHandle;
+use tokio_util::io::SyncIoBridge;
+
+use crate::config::Config;
+use crate::filter::{Filter, FilteredHandler};
+use crate::listener::{
+    Mode, NextStep, RdbSize, check_hello, check_reply, hello_args, is_getack, psync_next_step, receive_rdb,
+};
+use crate::rdb::DefaultRDBParser;
+use crate::resp::{
+    BANG, COLON, COMMA, CR, DOLLAR, EQUAL, GREATER, HASH, LEFT_PAREN, LF, MINUS, PERCENT, PIPE, PLUS, Resp, RespDecode,
+    STAR, TILDE, UNDERSCORE,
+};
+use crate::{Event, EventHandler, ModuleParser, NoOpEventHandler, cmd, io, to_string};
+
+/// 异步的Redis事件处理器
+///
+/// 每一个事件返回的Future执行完毕之后，才会处理下一个事件
+pub trait AsyncEventHandler: Send {
+    fn handle(&mut self, event: Event<'_>) -> impl Future<Output = ()> + Send;
+}
+
+impl AsyncEventHandler for NoOpEventHandler {
+    async fn handle(&mut self, _: Event<'_>) {}
+}
+
+/// 用于异步监听单个Redis实例的事件
+pub struct AsyncListener<H: AsyncEventHandler + 'static> {
+    pub config: Config,
+    event_handler: Arc<Mutex<H>>,
+    module_parser: Option<Arc<std::sync::Mutex<dyn ModuleParser + Send>>>,
+    rdb_writer: Option<Arc<std::sync::Mutex<dyn Write + Send>>>,
+    running: Arc<AtomicBool>,
+    repl_offset: Arc<AtomicI64>,
+    heartbeat: Option<JoinHandle<()>>,
+    filter: Option<Filter>,
+}
+
+impl<H: AsyncEventHandler + 'static> AsyncListener<H> {
+    /// 程序运行的整体逻辑都在这个方法里面实现，与[`RedisListener::start`]一致
+    ///
+    /// [`RedisListener::start`]: ../trait.RedisListener.html#tymethod.start
+    pub async fn start(&mut self) -> Result<()> {
+        let mut conn = self.connect().await?;
+        self.auth(&mut conn).await?;
+        self.send_replica_info(&mut conn).await?;
+        let mut mode;
+        loop {
+            mode = self.start_sync(&mut conn).await?;
+            match mode {
+                Mode::Wait => {
+                    if self.is_running() {
+                        tokio::time::sleep(Duration::from_secs(5)).await;
+                    } else {
+                        return Ok(());
+                    }
+                }
+                _ => break,
+            }
+        }
+        if !self.config.is_aof {
+            Ok(())
+        } else {
+            self.start_heartbeat(&mode, &conn);
+            let result = self.receive_aof(&mode, &mut conn).await;
+            self.stop_heartbeat();
+            result
+        }
+    }
+
+    /// 连接Redis，创建TCP连接
+    async fn connect(&mut self) -> Result<Connection> {
+        #[cfg(any(feature = "tls", feature = "tls-rustls"))]
+        if self.config.is_tls_enabled {
+            return Err(Error::new(
+                ErrorKind::Unsupported,
+                "TLS is not supported by AsyncListener",
+            ));
+        }
+        if self.config.unix_socket_path.is_some() {
+            return Err(Error::new(
+                ErrorKind::Unsupported,
+                "unix socket is not supported by AsyncListener",
+            ));
+        }
+        let addr = format!("{}:{}", &self.config.host, self.config.port);
+        let stream = TcpStream::connect(&addr).await?;
+        info!("Connected to server {}", &addr);
+
+        let socket_addr = stream.local_addr()?;
+        let (input, output) = stream.into_split();
+        Ok(Connection {
+            reader: RespReader {
+                input,
+                buf: Vec::new(),
+                needed: 0,
+                read_timeout: self.config.read_timeout,
+            },
+            writer: Arc::new(Mutex::new(output)),
+            write_timeout: self.config.write_timeout,
+            local_ip: socket_addr.ip().to_string(),
+            local_port: socket_addr.port(),
+        })
+    }
+
+    /// 如果有设置密码，将尝试使用此密码进行认证
+    async fn auth(&mut self, conn: &mut Connection) -> Result<()> {
+        if self.config.is_resp3 {
+            info!("HELLO 3");
+            conn.send(b"HELLO", &hello_args(&self.config)).await?;
+            let (resp, _) = conn.reader.read_resp().await?;
+            return check_hello(resp);
+        }
+        if !self.config.password.is_empty() {
+            let mut args = Vec::with_capacity(2);
+            if !self.config.username.is_empty() {
+                args.push(self.config.username.as_bytes());
+            }
+            args.push(self.config.password.as_bytes());
+            conn.send(b"AUTH", &args).await?;
+            conn.reader.read_resp().await?;
+        }
+        Ok(())
+    }
+
+    /// 发送replica相关信息到redis，此端口展现在`info replication`中
+    async fn send_replica_info(&mut self, conn: &mut Connection) -> Result<()> {
+        let port = conn.local_port.to_string();
+        let ip = conn.local_ip.clone();
+
+        info!("PING");
+        conn.send(b"PING", &[]).await?;
+        conn.reply().await?;
+
+        info!("REPLCONF listening-port {}", port);
+        conn.send(b"REPLCONF", &[b"listening-port", port.as_bytes()]).await?;
+        conn.reply().await?;
+
+        info!("REPLCONF ip-address {}", ip);
+        conn.send(b"REPLCONF", &[b"ip-address", ip.as_bytes()]).await?;
+        conn.reply().await?;
+
+        info!("REPLCONF capa eof");
+        conn.send(b"REPLCONF", &[b"capa", b"eof"]).await?;
+        conn.reply().await?;
+
+        info!("REPLCONF capa psync2");
+        conn.send(b"REPLCONF", &[b"capa", b"psync2"]).await?;
+        conn.reply().await?;
+
+        // 与Listener一致，只需要RDB时告知Redis
+        if !self.config.is_aof {
+            info!("REPLCONF rdb-only 1");
+            conn.send(b"REPLCONF", &[b"rdb-only", b"1"]).await?;
+            conn.reply().await?;
+            if let Some(filter) = &self.config.rdb_filter_only {
+                info!("REPLCONF rdb-filter-only {}", filter);
+                conn.send(b"REPLCONF", &[b"rdb-filter-only", filter.as_bytes()]).await?;
+                conn.reply().await?;
+            }
+        }
+        Ok(())
+    }
+
+    /// 开启replication
+    /// 默认使用PSYNC命令，若不支持PSYNC则尝试使用SYNC命令
+    async fn start_sync(&mut self, conn: &mut Connection) -> Result<Mode> {
+        let (next_step, size) = self.psync(conn).await?;
+        match next_step {
+            NextStep::FullSync | NextStep::ChangeMode => {
+                let (mode, size) = if let NextStep::ChangeMode = next_step {
+                    info!("源Redis不支持PSYNC命令, 使用SYNC命令再次进行尝试");
+                    conn.send(b"SYNC", &[]).await?;
+                    let reply = conn.reader.read_bulk_len().await?;
+                    (Mode::Sync, RdbSize::parse(&reply)?)
+                } else {
+                    (Mode::PSync, size.expect("Expect RDB size"))
+                };
+                match &size {
+                    RdbSize::Length(length) => info!("Full Sync, size: {}bytes", length),
+                    RdbSize::EofMark(_) => info!("Disk-less replication."),
+                }
+                self.receive_rdb(conn, &size).await?;
+                Ok(mode)
+            }
+            NextStep::PartialResync => {
+                info!("PSYNC进度恢复");
+                Ok(Mode::PSync)
+            }
+            NextStep::Wait => Ok(Mode::Wait),
+        }
+    }
+
+    async fn psync(&mut self, conn: &mut Connection) -> Result<(NextStep, Option<RdbSize>)> {
+        let offset = self.config.repl_offset.to_string();
+        conn.send(b"PSYNC", &[self.config.repl_id.as_bytes(), offset.as_bytes()])
+            .await?;
+
+        let (response, _) = conn.reader.read_resp().await?;
+        let next_step = match &response {
+            Resp::String(resp) => {
+                info!("{}", resp);
+                psync_next_step(resp, &mut self.config)?
+            }
+            // 不支持PSYNC，或者Redis暂时无法进行同步(-LOADING、-NOMASTERLINK)
+            Resp::Error(err) => {
+                warn!("{}", err);
+                psync_next_step(err, &mut self.config)?
+            }
+            _ => None,
+        };
+        match next_step {
+            Some(NextStep::FullSync) => {
+                info!("等待Redis dump完成...");
+                let reply = conn.reader.read_bulk_len().await?;
+                Ok((NextStep::FullSync, Some(RdbSize::parse(&reply)?)))
+            }
+            Some(next_step) => Ok((next_step, None)),
+            None => Err(Error::new(
+                ErrorKind::InvalidData,
+                format!("Unexpected Response: {:?}", response),
+            )),
+        }
+    }
+
+    /// 同步地解析RDB，解析时多读取的数据会放回到`conn`的缓冲区中
+    async fn receive_rdb(&mut self, conn: &mut Connection, size: &RdbSize) -> Result<()> {
+        let config = self.config.clone();
+        let running = Arc::clone(&self.running);
+        let module_parser = self.module_parser.clone();
+        let rdb_writer = self.rdb_writer.clone();
+        let event_handler = Arc::clone(&self.event_handler);
+        let runtime = Handle::current();
+        let filter = self.filter.as_mut();
+        let buffered = Cursor::new(std::mem::take(&mut conn.reader.buf));
+        // 通过SyncIoBridge同步地读取conn中的数据
+        let input = &mut conn.reader.input;
+
+        let (result, rest) = tokio::task::block_in_place(|| {
+            let bridge = SyncIoBridge::new_with_handle(input, runtime.clone());
+            let mut input = BufReader::new(Read::chain(buffered, bridge));
+            let mut rdb_parser = DefaultRDBParser {
+                running,
+                module_parser,
+                metrics: None,
+            };
+            let mut event_handler = event_handler.blocking_lock();
+            let mut event_handler = BlockingHandler {
+                handler: &mut *event_handler,
+                runtime,
+            };
+            let mut filtered;
+            let event_handler: &mut dyn EventHandler = match filter {
+                Some(filter) => {
+                    filtered = FilteredHandler::new(&mut event_handler, filter);
+                    &mut filtered
+                }
+                None => &mut event_handler,
+            };
+            let result = receive_rdb(
+                &mut input,
+                size,
+                &config,
+                rdb_writer.as_ref(),
+                &mut rdb_parser,
+                event_handler,
+            );
+
+            let mut rest = input.buffer().to_vec();
+            let (buffered, _) = input.into_inner().into_inner();
+            let position = buffered.position() as usize;
+            rest.extend_from_slice(&buffered.get_ref()[position..]);
+            (result, rest)
+        });
+        conn.reader.buf = rest;
+        result
+    }
+
+    /// 开启心跳，由tokio的定时器每秒发送一次`REPLCONF ACK`
+    fn start_heartbeat(&mut self, mode: &Mode, conn: &Connection) {
+        if !self.is_running() {
+            return;
+        }
+        if let Mode::Sync = mode {
+            return;
+        }
+        // PSYNC之后offset可能已被更新，心跳需从最新的offset开始
+        self.repl_offset.store(self.config.repl_offset, Ordering::SeqCst);
+        info!("Start heartbeat");
+        let writer = Arc::clone(&conn.writer);
+        let write_timeout = conn.write_timeout;
+        let repl_offset = Arc::clone(&self.repl_offset);
+        let period = self.config.heartbeat_interval;
+        let handle = tokio::spawn(async move {
+            let mut interval = tokio::time::interval(period);
+            loop {
+                interval.tick().await;
+                let offset_str = repl_offset.load(Ordering::Relaxed).to_string();
+                let offset_bytes = offset_str.as_bytes();
+                if let Err(error) = send(&writer, write_timeout, b"REPLCONF", &[b"ACK", offset_bytes]).await {
+                    error!("heartbeat error: {}", error);
+                }
+            }
+        });
+        self.heartbeat = Some(handle);
+    }
+
+    fn stop_heartbeat(&mut self) {
+        if let Some(handle) = self.heartbeat.take() {
+            info!("Cancel heartbeat");
+            handle.abort();
+        }
+    }
+
+    async fn receive_aof(&mut self, mode: &Mode, conn: &mut Connection) -> Result<()> {
+        let mut handler = self.event_handler.lock().await;
+        let mut transaction = cmd::TransactionBuffer::default();
+        while self.running.load(Ordering::Relaxed) {
+            if let (Resp::Array(array), size) = conn.reader.read_resp().await? {
+                let mut vec = Vec::with_capacity(array.len());
+                for x in array {
+                    if let Resp::BulkBytes(bytes) = x {
+                        vec.push(bytes);
+                    } else {
+                        return Err(Error::new(ErrorKind::InvalidData, "Expected BulkString response"));
+                    }
+                }
+                let getack = is_getack(&vec);
+                if getack && let Mode::PSync = mode {
+                    let offset_str = self.config.repl_offset.to_string();
+                    conn.send(b"REPLCONF", &[b"ACK", offset_str.as_bytes()]).await?;
+                }
+                let size = if getack && !self.config.is_forward_getack {
+                    size
+                } else {
+                    let buffered = if self.config.is_group_transaction {
+                        transaction.push(vec, size)
+                    } else {
+                        cmd::Buffered::Command(vec, size)
+                    };
+                    match buffered {
+                        cmd::Buffered::Command(data, size) => {
+                            if let Some(cmd) = cmd::parse_command(&data) {
+                                handle(&mut *handler, self.filter.as_mut(), Event::AOF(cmd.as_command())).await;
+                            }
+                            size
+                        }
+                        cmd::Buffered::Transaction(commands, size) => {
+                            let parsed: Vec<_> = commands.iter().filter_map(|data| cmd::parse_command(data)).collect();
+                            let commands = parsed.iter().map(|cmd| cmd.as_command()).collect();
+                            handle(&mut *handler, self.filter.as_mut(), Event::Transaction(commands)).await;
+                            size
+                        }
+                        cmd::Buffered::Flushed(commands, size) => {
+                            for data in commands {
+                                if let Some(cmd) = cmd::parse_command(&data) {
+                                    handle(&mut *handler, self.filter.as_mut(), Event::AOF(cmd.as_command())).await;
+                                }
+                            }
+                            size
+                        }
+                        cmd::Buffered::Pending => 0,
+                    }
+                };
+                if let Mode::PSync = mode {
+                    self.config.repl_offset += size;
+                    self.repl_offset.store(self.config.repl_offset, Ordering::SeqCst);
+                }
+            } else {
+                return Err(Error::new(ErrorKind::InvalidData, "Expected array response"));
+            }
+        }
+        Ok(())
+    }
+
+    /// 获取当前运行的状态，若为false，程序将有序退出
+    fn is_running(&self) -> bool {
+        self.running.load(Ordering::Relaxed)
+    }
+}
+
+impl<H: AsyncEventHandler + 'static> Drop for AsyncListener<H> {
+    fn drop(&mut self) {
+        self.stop_heartbeat();
+    }
+}
+
+/// 若设置了`filter`，只将满足条件的事件交给`handler`
+async fn handle<H: AsyncEventHandler>(handler: &mut H, filter: Option<&mut Filter>, event: Event<'_>) {
+    let event = match filter {
+        Some(filter) => filter.apply(event),
+        None => Some(event),
+    };
+    if let Some(event) = event {
+        handler.handle(event).await;
+    }
+}
+
+/// 与Redis之间的连接，写入端由心跳任务共享
+struct Connection {
+    reader: RespReader,
+    writer: Arc<Mutex<OwnedWriteHalf>>,
+    write_timeout: Option<Duration>,
+    local_ip: String,
+    local_port: u16,
+}
+
+impl Connection {
+    async fn send(&self, command: &[u8], args: &[&[u8]]) -> Result<()> {
+        send(&self.writer, self.write_timeout, command, args).await
+    }
+
+    async fn reply(&mut self) -> Result<()> {
+        let (resp, _) = self.reader.read_resp().await?;
+        check_reply(resp)
+    }
+}
+
+async fn send(
+    writer: &Mutex<OwnedWriteHalf>, write_timeout: Option<Duration>, command: &[u8], args: &[&[u8]],
+) -> Result<()> {
+    let mut buf = Vec::new();
+    io::send(&mut buf, command, args)?;
+    let mut writer = writer.lock().await;
+    with_timeout(write_timeout, writer.write_all(&buf)).await
+}
+
+async fn with_timeout<T>(timeout: Option<Duration>, future: impl Future<Output = Result<T>>) -> Result<T> {
+    match timeout {
+        None => future.await,
+        Some(timeout) => match tokio::time::timeout(timeout, future).await {
+            Ok(result) => result,
+            Err(_) => Err(Error::new(ErrorKind::TimedOut, "operation timed out")),
+        },
+    }
+}
+
+/// 带缓冲的RESP读取，数据完整之后交由[`RespDecode`]解析
+struct RespReader {
+    input: OwnedReadHalf,
+    buf: Vec<u8>,
+    // 缓冲区中的数据达到此长度之后才进行解析
+    needed: usize,
+    read_timeout: Option<Duration>,
+}
+
+impl RespReader {
+    /// 读取一条完整的RESP数据，返回数据本身及其所占的字节数
+    async fn read_resp(&mut self) -> Result<(Resp, i64)> {
+        loop {
+            if !self.buf.is_empty() && self.buf.len() >= self.needed {
+                // 先根据类型及长度判断数据是否完整，不完整时不必解析，避免每次读取之后都重新解析整个缓冲区
+                self.needed = min_frame_len(&self.buf);
+                if self.buf.len() >= self.needed {
+                    let mut cursor = Cursor::new(self.buf.as_slice());
+                    match cursor.decode_resp() {
+                        Ok(resp) => {
+                            let size = cursor.position() as usize;
+                            self.buf.drain(..size);
+                            self.needed = 0;
+                            return Ok((resp, size as i64));
+                        }
+                        Err(err) if err.kind() == ErrorKind::UnexpectedEof => self.needed = self.buf.len() + 1,
+                        Err(err) => return Err(err),
+                    }
+                }
+            }
+            self.fill().await?;
+        }
+    }
+
+    /// 读取`$`之后的内容，即RDB的长度或者`EOF:<mark>`
+    ///
+    /// Redis在dump RDB期间会发送`\n`以保持连接，这部分数据会被忽略
+    async fn read_bulk_len(&mut self) -> Result<String> {
+        loop {
+            let start = self.buf.iter().position(|b| *b != LF).unwrap_or(self.buf.len());
+            self.buf.drain(..start);
+            if let Some(end) = self.buf.windows(2).position(|w| w == [CR, LF]) {
+                let line: Vec<u8> = self.buf.drain(..end + 2).take(end).collect();
+                return match line.split_first() {
+                    Some((b'$', len)) => Ok(to_string(len.to_vec())),
+                    _ => Err(Error::new(ErrorKind::InvalidData, "Expect BulkString response")),
+                };
+            }
+            self.fill().await?;
+        }
+    }
+
+    /// 从连接中读取更多数据，每次读取的大小随缓冲区增长，避免大数据时反复解析
+    async fn fill(&mut self) -> Result<()> {
+        self.buf.reserve(self.buf.len().max(BUF_SIZE));
+        let n = with_timeout(self.read_timeout, self.input.read_buf(&mut self.buf)).await?;
+        if n == 0 {
+            return Err(Error::new(ErrorKind::UnexpectedEof, "connection closed by server"));
+        }
+        Ok(())
+    }
+}
+
+const BUF_SIZE: usize = 8 * 1024;
+
+/// 一条完整的RESP数据至少需要的字节数，只检查类型及长度，不解析数据本身
+///
+/// 返回值不超过`buf.len()`时数据已完整或者格式有误，交由[`RespDecode`]解析即可；
+/// 否则在缓冲区达到此长度之前无需解析，避免大数据分多次到达时反复解析
+///
+/// [`RespDecode`]: ../resp/trait.RespDecode.html
+pub(crate) fn min_frame_len(buf: &[u8]) -> usize {
+    let mut pos = 0;
+    match skip_frame(buf, &mut pos) {
+        Ok(()) => pos,
+        Err(needed) => needed,
+    }
+}
+
+/// 跳过一条RESP数据，数据不完整时返回至少需要的字节数，格式有误时返回0
+fn skip_frame(buf: &[u8], pos: &mut usize) -> std::result::Result<(), usize> {
+    // 与decode_type一致，忽略类型之前的`\n`
+    while buf.get(*pos) == Some(&LF) {
+        *pos += 1;
+    }
+    let data_type = *buf.get(*pos).ok_or(*pos + 1)?;
+    *pos += 1;
+    match data_type {
+        PLUS | MINUS | COLON | UNDERSCORE | COMMA | HASH | LEFT_PAREN => skip_line(buf, pos).map(|_| ()),
+        DOLLAR | BANG | EQUAL => match skip_length(buf, pos)? {
+            Some(len) => {
+                *pos = pos.saturating_add(len).saturating_add(2);
+                if *pos > buf.len() { Err(*pos) } else { Ok(()) }
+            }
+            None => Ok(()),
+        },
+        STAR | TILDE | GREATER => {
+            let len = skip_length(buf, pos)?.unwrap_or(0);
+            skip_frames(buf, pos, len)
+        }
+        PERCENT => {
+            let len = skip_length(buf, pos)?.unwrap_or(0);
+            skip_frames(buf, pos, len.saturating_mul(2))
+        }
+        PIPE => {
+            let len = skip_length(buf, pos)?.unwrap_or(0);
+            skip_frames(buf, pos, len.saturating_mul(2))?;
+            skip_frame(buf, pos)
+        }
+        _ => Err(0),
+    }
+}
+
+fn skip_frames(buf: &[u8], pos: &mut usize, count: usize) -> std::result::Result<(), usize> {
+    for _ in 0..count {
+        skip_frame(buf, pos)?;
+    }
+    Ok(())
+}
+
+/// 跳过以`\r\n`结尾的一行，返回不含`\r\n`的内容
+fn skip_line<'a>(buf: &'a [u8], pos: &mut usize) -> std::result::Result<&'a [u8], usize> {
+    match buf[*pos..].iter().position(|b| *b == CR) {
+        Some(i) => {
+            let line = &buf[*pos..*pos + i];
+            *pos += i + 2;
+            if *pos > buf.len() { Err(*pos) } else { Ok(line) }
+        }
+        None => Err(buf.len() + 1),
+    }
+}
+
+/// 跳过长度，-1表示Null
+fn skip_length(buf: &[u8], pos: &mut usize) -> std::result::Result<Option<usize>, usize> {
+    let line = skip_line(buf, pos)?;
+    match std::str::from_utf8(line).ok().and_then(|s| s.parse::<i64>().ok()) {
+        Some(-1) => Ok(None),
+        Some(len) if len >= 0 => Ok(Some(len as usize)),
+        _ => Err(0),
+    }
+}
+
+/// 在blocking线程中等待[`AsyncEventHandler`]处理完事件
+struct BlockingHandler<'a, H: AsyncEventHandler> {
+    handler: &'a mut H,
+    runtime: Handle,
+}
+
+impl<H: AsyncEventHandler> EventHandler for BlockingHandler<'_, H> {
+    fn handle(&mut self, event: Event) {
+        self.runtime.block_on(self.handler.handle(event));
+    }
+}
+
+pub struct Builder<H: AsyncEventHandler + 'static> {
+    pub config: Option<Config>,
+    pub event_handler: Option<H>,
+    pub module_parser: Option<Arc<std::sync::Mutex<dyn ModuleParser + Send>>>,
+    pub control_flag: Option<Arc<AtomicBool>>,
+    pub rdb_writer: Option<Arc<std::sync::Mutex<dyn Write + Send>>>,
+    pub filter: Option<Filter>,
+}
+
+impl<H: AsyncEventHandler + 'static> Builder<H> {
+    pub fn new() -> Builder<H> {
+        Builder {
+            config: None,
+            event_handler: None,
+            module_parser: None,
+            control_flag: None,
+            rdb_writer: None,
+            filter: None,
+        }
+    }
+
+    pub fn with_config(&mut self, config: Config) {
+        self.config = Some(config);
+    }
+
+    pub fn with_event_handler(&mut self, handler: H) {
+        self.event_handler = Some(handler);
+    }
+
+    pub fn with_module_parser(&mut self, parser: Arc<std::sync::Mutex<dyn ModuleParser + Send>>) {
+        self.module_parser = Some(parser);
+    }
+
+    pub fn with_control_flag(&mut self, flag: Arc<AtomicBool>) {
+        self.control_flag = Some(flag);
+    }
+
+    /// 全量同步时，将接收到的RDB原样写入`writer`
+    pub fn with_rdb_writer(&mut self, writer: Arc<std::sync::Mutex<dyn Write + Send>>) {
+        self.rdb_writer = Some(writer);
+    }
+
+    /// 只将满足`filter`条件的事件交给`AsyncEventHandler`
+    pub fn with_filter(&mut self, filter: Filter) {
+        self.filter = Some(filter);
+    }
+
+    /// 创建`AsyncListener`，缺少必需的参数或`Config`无效时返回错误
+    pub fn build(&mut self) -> Result<AsyncListener<H>> {
+        let config = match &self.config {
+            Some(c) => c,
+            None => return Err(Error::new(ErrorKind::InvalidInput, "Parameter Config is required")),
+        };
+        config.validate()?;
+
+        let running = match &self.control_flag {
+            None => {
+                return Err(Error::new(
+                    ErrorKind::InvalidInput,
+                    "Parameter Control_flag is required",
+                ));
+            }
+            Some(flag) => flag.clone(),
+        };
+
+        let event_handler = match self.event_handler.take() {
+            None => {
+                return Err(Error::new(
+                    ErrorKind::InvalidInput,
+                    "Parameter Event_handler is required",
+                ));
+            }
+            Some(handler) => handler,
+        };
+
+        Ok(AsyncListener {
+            config: config.clone(),
+            event_handler: Arc::new(Mutex::new(event_handler)),
+            module_parser: self.module_parser.clone(),
+            rdb_writer: self.rdb_writer.clone(),
+            running,
+            repl_offset: Arc::new(AtomicI64::from(config.repl_offset)),
+            heartbeat: None,
+            filter: self.filter.clone(),
+        })
+    }
+}
+
+impl<H: AsyncEventHandler + 'static> Default for Builder<H> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -0,0 +1,219 @@
+/*!
+Redis 7的multi-part AOF
+
+Redis 7将AOF保存在`appendonlydir`目录下，由以下几部分组成:
+- manifest: 记录各个文件的名字、序号及类型，每行的格式为`file <name> seq <seq> type <b|h|i>`
+- base: 重写AOF时生成的文件，可以是RDB格式，也可以是AOF格式
+- incr: base之后的增量AOF文件，按照seq的顺序依次加载
+- history: 已过期的文件，等待Redis删除，不需要加载
+*/
+use std::fs;
+use std::io::{Error, ErrorKind, Result};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+
+use log::info;
+
+use crate::aof::{AofReader, ReadResult};
+use crate::{EventHandler, ModuleParser};
+
+/// manifest中文件的类型
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FileType {
+    /// base文件
+    Base,
+    /// 已过期的文件
+    History,
+    /// 增量文件
+    Incr,
+}
+
+/// manifest中记录的文件
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ManifestEntry {
+    pub name: String,
+    pub seq: u64,
+    pub file_type: FileType,
+}
+
+/// AOF的manifest
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Manifest {
+    pub entries: Vec<ManifestEntry>,
+}
+
+impl Manifest {
+    /// 读取并解析manifest文件
+    pub fn open<P: AsRef<Path>>(path: P) -> Result<Manifest> {
+        Manifest::parse(&fs::read_to_string(path)?)
+    }
+
+    /// 解析manifest的内容
+    pub fn parse(content: &str) -> Result<Manifest> {
+        let mut entries = Vec::new();
+        for line in content.lines() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let args = split_args(line)?;
+            if args.len() % 2 != 0 {
+                return Err(invalid_manifest(line));
+            }
+            let (mut name, mut seq, mut file_type) = (None, None, None);
+            for pair in args.chunks(2) {
+                match pair[0].as_str() {
+                    "file" => name = Some(pair[1].clone()),
+                    "seq" => seq = pair[1].parse::<u64>().ok(),
+                    "type" => {
+                        file_type = match pair[1].as_str() {
+                            "b" => Some(FileType::Base),
+                            "h" => Some(FileType::History),
+                            "i" => Some(FileType::Incr),
+                            _ => None,
+                        }
+                    }
+                    // 忽略新版本中增加的字段，如startoffset、endoffset
+                    _ => {}
+                }
+            }
+            match (name, seq, file_type) {
+                (Some(name), Some(seq), Some(file_type)) => entries.push(ManifestEntry { name, seq, file_type }),
+                _ => return Err(invalid_manifest(line)),
+            }
+        }
+        Ok(Manifest { entries })
+    }
+
+    /// base文件
+    pub fn base(&self) -> Option<&ManifestEntry> {
+        self.entries.iter().find(|entry| entry.file_type == FileType::Base)
+    }
+
+    /// 按seq排序的增量文件
+    pub fn incrs(&self) -> Vec<&ManifestEntry> {
+        let mut incrs: Vec<&ManifestEntry> = self
+            .entries
+            .iter()
+            .filter(|entry| entry.file_type == FileType::Incr)
+            .collect();
+        incrs.sort_by_key(|entry| entry.seq);
+        incrs
+    }
+}
+
+/// 读取`appendonlydir`中的multi-part AOF，依次处理base及incr文件
+pub struct MultiPartReader {
+    dir: PathBuf,
+    manifest: Manifest,
+    module_parser: Option<Arc<Mutex<dyn ModuleParser + Send>>>,
+}
+
+impl MultiPartReader {
+    /// 打开`appendonlydir`，目录中需有且仅有一个`.manifest`文件
+    ///
+    /// Redis重写AOF期间产生的`temp-`开头的manifest会被忽略
+    pub fn open<P: AsRef<Path>>(dir: P) -> Result<MultiPartReader> {
+        let dir = dir.as_ref();
+        let mut manifests = Vec::new();
+        for entry in fs::read_dir(dir)? {
+            let path = entry?.path();
+            let is_temp = path
+                .file_name()
+                .is_some_and(|name| name.to_string_lossy().starts_with("temp-"));
+            if path.extension().is_some_and(|ext| ext == "manifest") && !is_temp {
+                manifests.push(path);
+            }
+        }
+        match manifests.as_slice() {
+            [manifest] => Ok(MultiPartReader {
+                dir: dir.to_path_buf(),
+                manifest: Manifest::open(manifest)?,
+                module_parser: None,
+            }),
+            [] => Err(Error::new(ErrorKind::NotFound, "manifest not found")),
+            _ => Err(Error::new(ErrorKind::InvalidInput, "found more than one manifest")),
+        }
+    }
+
+    pub fn manifest(&self) -> &Manifest {
+        &self.manifest
+    }
+
+    /// 解析base中RDB的Module时所使用的ModuleParser
+    pub fn with_module_parser(&mut self, parser: Arc<Mutex<dyn ModuleParser + Send>>) {
+        self.module_parser = Some(parser);
+    }
+
+    /// 依次读取base及incr文件，并交给`event_handler`处理
+    ///
+    /// 与Redis一致，只有最后一个文件允许末尾不完整，返回结果中的`commands`为所有文件的命令总数，
+    /// `valid_len`及`truncated`为最后一个文件的情况
+    pub fn read(&mut self, event_handler: &mut dyn EventHandler) -> Result<ReadResult> {
+        let mut files: Vec<&ManifestEntry> = self.manifest.base().into_iter().collect();
+        files.extend(self.manifest.incrs());
+        let mut result = ReadResult {
+            commands: 0,
+            valid_len: 0,
+            truncated: false,
+        };
+        for (i, entry) in files.iter().enumerate() {
+            let path = self.dir.join(&entry.name);
+            info!("读取AOF文件: {}", path.display());
+            let mut reader = AofReader::open(&path)?;
+            if let Some(parser) = &self.module_parser {
+                reader.with_module_parser(parser.clone());
+            }
+            let file_result = reader.read(event_handler)?;
+            if file_result.truncated && i + 1 < files.len() {
+                return Err(Error::new(
+                    ErrorKind::InvalidData,
+                    format!("{} is truncated, only the last file can be truncated", path.display()),
+                ));
+            }
+            result.commands += file_result.commands;
+            result.valid_len = file_result.valid_len;
+            result.truncated = file_result.truncated;
+        }
+        Ok(result)
+    }
+}
+
+fn invalid_manifest(line: &str) -> Error {
+    Error::new(ErrorKind::InvalidData, format!("Invalid manifest line: {}", line))
+}
+
+/// 按空白分隔参数，文件名中带有空白等字符时，Redis会用双引号括起来
+fn split_args(line: &str) -> Result<Vec<String>> {
+    let mut args = Vec::new();
+    let mut chars = line.chars().peekable();
+    loop {
+        while chars.next_if(|c| c.is_whitespace()).is_some() {}
+        let Some(first) = chars.next() else {
+            return Ok(args);
+        };
+        let mut arg = String::new();
+        if first == '"' {
+            loop {
+                match chars.next() {
+                    Some('"') => break,
+                    Some('\\') => match chars.next() {
+                        Some('n') => arg.push('\n'),
+                        Some('r') => arg.push('\r'),
+                        Some('t') => arg.push('\t'),
+                        Some(c) => arg.push(c),
+                        None => return Err(invalid_manifest(line)),
+                    },
+                    Some(c) => arg.push(c),
+                    None => return Err(invalid_manifest(line)),
+                }
+            }
+        } else {
+            arg.push(first);
+            while let Some(c) = chars.next_if(|c| !c.is_whitespace()) {
+                arg.push(c);
+            }
+        }
+        args.push(arg);
+    }
+}
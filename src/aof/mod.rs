@@ -0,0 +1,19 @@
+/*!
+AOF文件的读写
+
+此模块包括:
+- 将从Redis复制过来的命令原样记录到AOF文件中，支持按大小或时间滚动文件，见于[AofWriter]
+- 读取Redis的AOF文件，将其中的命令作为`Event::AOF`进行处理，见于[AofReader]
+- 读取Redis 7的multi-part AOF(`appendonlydir`)，见于[MultiPartReader]
+
+[AofWriter]: struct.AofWriter.html
+[AofReader]: struct.AofReader.html
+[MultiPartReader]: struct.MultiPartReader.html
+*/
+pub use crate::aof::manifest::{FileType, Manifest, ManifestEntry, MultiPartReader};
+pub use crate::aof::reader::{AofReader, ReadResult};
+pub use crate::aof::writer::{AofWriter, FsyncPolicy, WriterConfig};
+
+mod manifest;
+mod reader;
+mod writer;
@@ -0,0 +1,180 @@
+/*!
+读取Redis的AOF文件，将其中的命令作为`Event::AOF`交给EventHandler处理
+
+与Redis加载AOF时的行为一致:
+- 以`REDIS`开头的AOF带有RDB前导(`aof-use-rdb-preamble yes`)，RDB部分作为`Event::RDB`进行处理
+- 以`#`开头的行为注释(annotation)，直接跳过
+- 文件末尾不完整的命令会被忽略，与`aof-load-truncated yes`相同
+- `MULTI`之后的命令在读取到`EXEC`之后才会被处理，文件末尾未完成的事务会被整体忽略
+*/
+use std::fs::File;
+use std::io::{BufRead, BufReader, Error, ErrorKind, Read, Result};
+use std::path::Path;
+use std::sync::atomic::AtomicBool;
+use std::sync::{Arc, Mutex};
+
+use crate::rdb::DefaultRDBParser;
+use crate::resp::{Resp, RespDecode};
+use crate::{EventHandler, ModuleParser, RDBParser, cmd, io};
+
+/// AOF的读取结果
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ReadResult {
+    /// 已处理的命令数量
+    pub commands: u64,
+    /// 有效部分的长度，即最后一条完整的命令(或事务)结束的位置，截断文件时可使用此长度
+    pub valid_len: u64,
+    /// 文件末尾是否存在不完整的命令或事务
+    pub truncated: bool,
+}
+
+/// AOF文件读取器
+pub struct AofReader<R> {
+    input: BufReader<R>,
+    pos: u64,
+    db: i32,
+    module_parser: Option<Arc<Mutex<dyn ModuleParser + Send>>>,
+}
+
+impl AofReader<File> {
+    /// 打开指定路径的AOF文件
+    pub fn open<P: AsRef<Path>>(path: P) -> Result<AofReader<File>> {
+        Ok(AofReader::new(File::open(path)?))
+    }
+}
+
+impl<R: Read> AofReader<R> {
+    pub fn new(input: R) -> AofReader<R> {
+        AofReader {
+            input: BufReader::new(input),
+            pos: 0,
+            db: 0,
+            module_parser: None,
+        }
+    }
+
+    /// 解析RDB前导中的Module时所使用的ModuleParser
+    pub fn with_module_parser(&mut self, parser: Arc<Mutex<dyn ModuleParser + Send>>) {
+        self.module_parser = Some(parser);
+    }
+
+    /// 当前所在的db，即最后一个`SELECT`所选择的db
+    pub fn db(&self) -> i32 {
+        self.db
+    }
+
+    /// 读取所有命令并交给`event_handler`处理
+    pub fn read(&mut self, event_handler: &mut dyn EventHandler) -> Result<ReadResult> {
+        if self.pos == 0 && self.input.fill_buf()?.starts_with(b"REDIS") {
+            self.read_preamble(event_handler)?;
+        }
+        let mut result = ReadResult {
+            commands: 0,
+            valid_len: self.pos,
+            truncated: false,
+        };
+        // MULTI之后的命令，读取到EXEC之后再一并处理
+        let mut transaction: Option<Vec<Vec<Vec<u8>>>> = None;
+        loop {
+            let buf = self.input.fill_buf()?;
+            if buf.is_empty() {
+                break;
+            }
+            match buf[0] {
+                b'#' => {
+                    let mut line = Vec::new();
+                    self.pos += self.input.read_until(b'\n', &mut line)? as u64;
+                    if !line.ends_with(b"\n") {
+                        result.truncated = true;
+                        break;
+                    }
+                }
+                b'*' => {
+                    let data = match self.next_command() {
+                        Ok(data) => data,
+                        Err(err) if err.kind() == ErrorKind::UnexpectedEof => {
+                            result.truncated = true;
+                            break;
+                        }
+                        Err(err) => return Err(err),
+                    };
+                    let name = data.first().map(|name| name.to_ascii_uppercase());
+                    match (name.as_deref(), transaction.as_mut()) {
+                        (Some(b"MULTI"), _) => transaction = Some(vec![data]),
+                        (Some(b"EXEC"), Some(_)) => {
+                            let mut commands = transaction.take().unwrap();
+                            commands.push(data);
+                            for data in commands {
+                                self.handle(data, event_handler);
+                                result.commands += 1;
+                            }
+                        }
+                        (_, Some(commands)) => commands.push(data),
+                        (_, None) => {
+                            self.handle(data, event_handler);
+                            result.commands += 1;
+                        }
+                    }
+                }
+                b => {
+                    return Err(Error::new(
+                        ErrorKind::InvalidData,
+                        format!("Bad file format at {}, unexpected byte: {}", self.pos, b),
+                    ));
+                }
+            }
+            if transaction.is_none() {
+                result.valid_len = self.pos;
+            }
+        }
+        if transaction.is_some() {
+            result.truncated = true;
+        }
+        Ok(result)
+    }
+
+    /// 读取RDB前导
+    fn read_preamble(&mut self, event_handler: &mut dyn EventHandler) -> Result<()> {
+        let mut rdb_parser = DefaultRDBParser {
+            running: Arc::new(AtomicBool::new(true)),
+            module_parser: self.module_parser.clone(),
+            metrics: None,
+        };
+        let mut sink = std::io::sink();
+        let mut input = io::TeeReader::new(&mut self.input, &mut sink);
+        let result = rdb_parser.parse(&mut input, -1, event_handler);
+        self.pos += input.count();
+        result
+    }
+
+    /// 读取一条命令
+    fn next_command(&mut self) -> Result<Vec<Vec<u8>>> {
+        let mut sink = std::io::sink();
+        let mut input = io::TeeReader::new(&mut self.input, &mut sink);
+        let resp = input.decode_resp();
+        self.pos += input.count();
+        if let Resp::Array(array) = resp? {
+            let mut data = Vec::with_capacity(array.len());
+            for x in array {
+                if let Resp::BulkBytes(bytes) = x {
+                    data.push(bytes);
+                } else {
+                    return Err(Error::new(ErrorKind::InvalidData, "Expected BulkString"));
+                }
+            }
+            Ok(data)
+        } else {
+            Err(Error::new(ErrorKind::InvalidData, "Expected Array"))
+        }
+    }
+
+    fn handle(&mut self, data: Vec<Vec<u8>>, event_handler: &mut dyn EventHandler) {
+        if data.len() == 2
+            && data[0].eq_ignore_ascii_case(b"SELECT")
+            && let Ok(db) = String::from_utf8_lossy(&data[1]).parse::<i32>()
+        {
+            self.db = db;
+        }
+        cmd::parse(data, event_handler);
+    }
+}
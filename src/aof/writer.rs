@@ -0,0 +1,230 @@
+/*!
+将复制流中的命令原样写入AOF文件
+
+文件中的数据与Redis发送的复制流完全一致(包括`PING`、`REPLCONF GETACK`等)，
+所以两个offset标记之间的字节数即为offset的增量。
+
+offset标记以注释(annotation)的形式写入，格式为`#OFFSET:<repl_id> <offset>\r\n`，
+表示紧随其后的命令在复制流中的offset，Redis 7.0及以上的版本加载AOF时会跳过以`#`开头的行。
+以下情况会写入offset标记:
+- 每个文件的开头
+- Replication ID变化或者offset不连续(如重新进行了全量同步)，SYNC模式下offset为-1，不做此判断
+- 距离上一次标记已超过1秒
+
+每条命令写入后都会flush到操作系统，`FsyncPolicy::EverySec`的fsync则由[`AofWriter::tick`]完成，
+需每秒调用一次(`Listener`会在后台定时调用)，不依赖于下一条命令的写入。
+
+[`AofWriter::tick`]: struct.AofWriter.html#method.tick
+*/
+use std::fs::{self, File, OpenOptions};
+use std::io::{BufWriter, Result, Write};
+use std::path::PathBuf;
+use std::time::{Duration, Instant};
+
+use log::info;
+
+const MARKER_INTERVAL: Duration = Duration::from_secs(1);
+
+/// fsync策略，与Redis的`appendfsync`一致
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FsyncPolicy {
+    /// 每写入一条命令都进行fsync
+    Always,
+    /// 每秒fsync一次，由[`AofWriter::tick`]触发
+    ///
+    /// [`AofWriter::tick`]: struct.AofWriter.html#method.tick
+    EverySec,
+    /// 不主动fsync，交由操作系统处理
+    No,
+}
+
+/// AofWriter的配置信息
+#[derive(Debug, Clone)]
+pub struct WriterConfig {
+    /// AOF文件所在的目录
+    pub dir: String,
+    /// 文件名的前缀，文件名为`<file_name>.<seq>.aof`，seq从1开始递增
+    pub file_name: String,
+    /// 单个文件的最大字节数，超过之后写入新的文件，None表示不按大小滚动
+    pub max_file_size: Option<u64>,
+    /// 单个文件的最长写入时间，超过之后写入新的文件，None表示不按时间滚动
+    pub max_file_age: Option<Duration>,
+    /// fsync策略
+    pub fsync: FsyncPolicy,
+}
+
+/// 将复制流中的命令写入AOF文件
+pub struct AofWriter {
+    config: WriterConfig,
+    file: Option<BufWriter<File>>,
+    seq: u64,
+    size: u64,
+    opened_at: Instant,
+    marked_at: Instant,
+    // 上一次fsync之后是否写入过数据
+    dirty: bool,
+    repl_id: String,
+    next_offset: i64,
+    // 为true时暂不滚动文件
+    rotation_deferred: bool,
+}
+
+impl AofWriter {
+    /// 创建AofWriter，`dir`中已存在的文件不会被覆盖，seq从已有文件的最大值之后开始
+    pub fn new(config: WriterConfig) -> Result<AofWriter> {
+        fs::create_dir_all(&config.dir)?;
+        let mut seq = 0;
+        for entry in fs::read_dir(&config.dir)? {
+            let name = entry?.file_name();
+            if let Some(n) = parse_seq(&name.to_string_lossy(), &config.file_name) {
+                seq = seq.max(n);
+            }
+        }
+        let now = Instant::now();
+        Ok(AofWriter {
+            config,
+            file: None,
+            seq,
+            size: 0,
+            opened_at: now,
+            marked_at: now,
+            dirty: false,
+            repl_id: String::new(),
+            next_offset: -1,
+            rotation_deferred: false,
+        })
+    }
+
+    /// 当前正在写入的文件
+    pub fn current_path(&self) -> Option<PathBuf> {
+        self.file.as_ref().map(|_| self.path(self.seq))
+    }
+
+    /// 为true时暂不滚动文件，直到再次设为false之后的下一次写入
+    ///
+    /// `Listener`在`MULTI`与`EXEC`之间设置为true，保证同一个事务写入同一个文件
+    pub fn defer_rotation(&mut self, deferred: bool) {
+        self.rotation_deferred = deferred;
+    }
+
+    /// 写入一条命令
+    ///
+    /// 方法参数:
+    ///
+    /// * `repl_id`: Replication ID
+    /// * `offset`: 此命令在复制流中的offset
+    /// * `frame`: 命令的原始数据(RESP格式)
+    pub fn append(&mut self, repl_id: &str, offset: i64, frame: &[u8]) -> Result<()> {
+        if self.should_rotate() {
+            self.rotate()?;
+        }
+        let now = Instant::now();
+        if self.size == 0
+            || (offset >= 0 && offset != self.next_offset)
+            || repl_id != self.repl_id
+            || now.duration_since(self.marked_at) >= MARKER_INTERVAL
+        {
+            let marker = format!("#OFFSET:{} {}\r\n", repl_id, offset);
+            self.write(marker.as_bytes())?;
+            self.repl_id = repl_id.to_string();
+            self.marked_at = now;
+        }
+        self.write(frame)?;
+        self.next_offset = offset + frame.len() as i64;
+
+        match self.config.fsync {
+            FsyncPolicy::Always => self.sync(),
+            _ => self.flush(),
+        }
+    }
+
+    /// `FsyncPolicy::EverySec`时，若上一次fsync之后写入过数据则进行fsync，其余策略下不做任何操作
+    ///
+    /// 需每秒调用一次，以保证Redis长时间没有写入时，最后写入的命令也能在1秒内落盘
+    pub fn tick(&mut self) -> Result<()> {
+        if self.config.fsync == FsyncPolicy::EverySec && self.dirty {
+            self.sync()
+        } else {
+            Ok(())
+        }
+    }
+
+    /// 将缓冲的数据写入文件并fsync
+    pub fn sync(&mut self) -> Result<()> {
+        self.flush()?;
+        if let Some(file) = &self.file
+            && self.config.fsync != FsyncPolicy::No
+        {
+            file.get_ref().sync_data()?;
+        }
+        self.dirty = false;
+        Ok(())
+    }
+
+    /// 将缓冲的数据写入操作系统
+    fn flush(&mut self) -> Result<()> {
+        if let Some(file) = &mut self.file {
+            file.flush()?;
+        }
+        Ok(())
+    }
+
+    fn write(&mut self, data: &[u8]) -> Result<()> {
+        if self.file.is_none() {
+            self.rotate()?;
+        }
+        self.file.as_mut().unwrap().write_all(data)?;
+        self.size += data.len() as u64;
+        self.dirty = true;
+        Ok(())
+    }
+
+    fn should_rotate(&self) -> bool {
+        if self.file.is_none() || self.size == 0 || self.rotation_deferred {
+            return false;
+        }
+        if let Some(max_size) = self.config.max_file_size
+            && self.size >= max_size
+        {
+            return true;
+        }
+        if let Some(max_age) = self.config.max_file_age
+            && self.opened_at.elapsed() >= max_age
+        {
+            return true;
+        }
+        false
+    }
+
+    /// 关闭当前文件，并打开下一个文件
+    fn rotate(&mut self) -> Result<()> {
+        self.sync()?;
+        self.seq += 1;
+        let path = self.path(self.seq);
+        info!("写入AOF文件: {}", path.display());
+        let file = OpenOptions::new().create_new(true).write(true).open(&path)?;
+        self.file = Some(BufWriter::new(file));
+        self.size = 0;
+        self.opened_at = Instant::now();
+        Ok(())
+    }
+
+    fn path(&self, seq: u64) -> PathBuf {
+        PathBuf::from(&self.config.dir).join(format!("{}.{}.aof", self.config.file_name, seq))
+    }
+}
+
+impl Drop for AofWriter {
+    fn drop(&mut self) {
+        let _ = self.sync();
+    }
+}
+
+/// 从`<file_name>.<seq>.aof`中解析出seq
+fn parse_seq(name: &str, file_name: &str) -> Option<u64> {
+    name.strip_prefix(file_name)?
+        .strip_prefix('.')?
+        .strip_suffix(".aof")?
+        .parse()
+        .ok()
+}
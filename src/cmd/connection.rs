@@ -13,10 +13,10 @@ pub struct SELECT {
     pub db: i32,
 }
 
-pub(crate) fn parse_select(mut iter: Iter<Vec<u8>>) -> SELECT {
-    let db = String::from_utf8_lossy(iter.next().unwrap());
-    let db = db.parse::<i32>().unwrap();
-    SELECT { db }
+pub(crate) fn parse_select(mut iter: Iter<Vec<u8>>) -> Option<SELECT> {
+    let db = String::from_utf8_lossy(iter.next()?);
+    let db = db.parse::<i32>().ok()?;
+    Some(SELECT { db })
 }
 
 #[derive(Debug)]
@@ -25,8 +25,8 @@ pub struct SWAPDB<'a> {
     pub index2: &'a [u8],
 }
 
-pub(crate) fn parse_swapdb(mut iter: Iter<Vec<u8>>) -> SWAPDB {
-    let index1 = iter.next().unwrap();
-    let index2 = iter.next().unwrap();
-    SWAPDB { index1, index2 }
+pub(crate) fn parse_swapdb(mut iter: Iter<Vec<u8>>) -> Option<SWAPDB> {
+    let index1 = iter.next()?;
+    let index2 = iter.next()?;
+    Some(SWAPDB { index1, index2 })
 }
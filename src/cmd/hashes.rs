@@ -14,13 +14,13 @@ pub struct HDEL<'a> {
     pub fields: Vec<&'a [u8]>,
 }
 
-pub(crate) fn parse_hdel(mut iter: Iter<Vec<u8>>) -> HDEL {
-    let key = iter.next().unwrap();
+pub(crate) fn parse_hdel(mut iter: Iter<Vec<u8>>) -> Option<HDEL> {
+    let key = iter.next()?;
     let mut fields = Vec::new();
     while let Some(field) = iter.next() {
         fields.push(field.as_slice());
     }
-    HDEL { key, fields }
+    Some(HDEL { key, fields })
 }
 
 #[derive(Debug)]
@@ -30,11 +30,11 @@ pub struct HINCRBY<'a> {
     pub increment: &'a [u8],
 }
 
-pub(crate) fn parse_hincrby(mut iter: Iter<Vec<u8>>) -> HINCRBY {
-    let key = iter.next().unwrap();
-    let field = iter.next().unwrap();
-    let increment = iter.next().unwrap();
-    HINCRBY { key, field, increment }
+pub(crate) fn parse_hincrby(mut iter: Iter<Vec<u8>>) -> Option<HINCRBY> {
+    let key = iter.next()?;
+    let field = iter.next()?;
+    let increment = iter.next()?;
+    Some(HINCRBY { key, field, increment })
 }
 
 #[derive(Debug)]
@@ -55,8 +55,8 @@ pub struct Field<'a> {
     pub value: &'a [u8],
 }
 
-pub(crate) fn parse_hmset(mut iter: Iter<Vec<u8>>) -> HMSET {
-    let key = iter.next().unwrap();
+pub(crate) fn parse_hmset(mut iter: Iter<Vec<u8>>) -> Option<HMSET> {
+    let key = iter.next()?;
     let mut fields = Vec::new();
     loop {
         if let Some(field) = iter.next() {
@@ -64,17 +64,17 @@ pub(crate) fn parse_hmset(mut iter: Iter<Vec<u8>>) -> HMSET {
                 let field = Field { name: field, value };
                 fields.push(field);
             } else {
-                panic!("HMSET缺失field value");
+                return None;
             }
         } else {
             break;
         }
     }
-    HMSET { key, fields }
+    Some(HMSET { key, fields })
 }
 
-pub(crate) fn parse_hset(mut iter: Iter<Vec<u8>>) -> HSET {
-    let key = iter.next().unwrap();
+pub(crate) fn parse_hset(mut iter: Iter<Vec<u8>>) -> Option<HSET> {
+    let key = iter.next()?;
     let mut fields = Vec::new();
     loop {
         if let Some(field) = iter.next() {
@@ -82,13 +82,13 @@ pub(crate) fn parse_hset(mut iter: Iter<Vec<u8>>) -> HSET {
                 let field = Field { name: field, value };
                 fields.push(field);
             } else {
-                panic!("HSET缺失field value");
+                return None;
             }
         } else {
             break;
         }
     }
-    HSET { key, fields }
+    Some(HSET { key, fields })
 }
 
 #[derive(Debug)]
@@ -98,9 +98,9 @@ pub struct HSETNX<'a> {
     pub value: &'a [u8],
 }
 
-pub(crate) fn parse_hsetnx(mut iter: Iter<Vec<u8>>) -> HSETNX {
-    let key = iter.next().unwrap();
-    let field = iter.next().unwrap();
-    let value = iter.next().unwrap();
-    HSETNX { key, field, value }
+pub(crate) fn parse_hsetnx(mut iter: Iter<Vec<u8>>) -> Option<HSETNX> {
+    let key = iter.next()?;
+    let field = iter.next()?;
+    let value = iter.next()?;
+    Some(HSETNX { key, field, value })
 }
@@ -14,13 +14,13 @@ pub struct PFADD<'a> {
     pub elements: Vec<&'a [u8]>,
 }
 
-pub(crate) fn parse_pfadd(mut iter: Iter<Vec<u8>>) -> PFADD {
-    let key = iter.next().unwrap();
+pub(crate) fn parse_pfadd(mut iter: Iter<Vec<u8>>) -> Option<PFADD> {
+    let key = iter.next()?;
     let mut elements = Vec::new();
     while let Some(element) = iter.next() {
         elements.push(element.as_slice());
     }
-    PFADD { key, elements }
+    Some(PFADD { key, elements })
 }
 
 #[derive(Debug)]
@@ -28,12 +28,12 @@ pub struct PFCOUNT<'a> {
     pub keys: Vec<&'a [u8]>,
 }
 
-pub(crate) fn parse_pfcount(mut iter: Iter<Vec<u8>>) -> PFCOUNT {
+pub(crate) fn parse_pfcount(mut iter: Iter<Vec<u8>>) -> Option<PFCOUNT> {
     let mut keys = Vec::new();
     while let Some(key) = iter.next() {
         keys.push(key.as_slice());
     }
-    PFCOUNT { keys }
+    Some(PFCOUNT { keys })
 }
 
 #[derive(Debug)]
@@ -42,11 +42,11 @@ pub struct PFMERGE<'a> {
     pub source_keys: Vec<&'a [u8]>,
 }
 
-pub(crate) fn parse_pfmerge(mut iter: Iter<Vec<u8>>) -> PFMERGE {
-    let dest_key = iter.next().unwrap();
+pub(crate) fn parse_pfmerge(mut iter: Iter<Vec<u8>>) -> Option<PFMERGE> {
+    let dest_key = iter.next()?;
     let mut source_keys = Vec::new();
     while let Some(source) = iter.next() {
         source_keys.push(source.as_slice());
     }
-    PFMERGE { dest_key, source_keys }
+    Some(PFMERGE { dest_key, source_keys })
 }
@@ -15,12 +15,12 @@ pub struct DEL<'a> {
     pub keys: Vec<&'a Vec<u8>>,
 }
 
-pub(crate) fn parse_del(iter: Iter<Vec<u8>>) -> DEL {
+pub(crate) fn parse_del(iter: Iter<Vec<u8>>) -> Option<DEL> {
     let mut keys = Vec::new();
     for next_key in iter {
         keys.push(next_key);
     }
-    DEL { keys }
+    Some(DEL { keys })
 }
 
 #[derive(Debug)]
@@ -28,9 +28,9 @@ pub struct PERSIST<'a> {
     pub key: &'a [u8],
 }
 
-pub(crate) fn parse_persist(mut iter: Iter<Vec<u8>>) -> PERSIST {
-    let key = iter.next().unwrap();
-    PERSIST { key }
+pub(crate) fn parse_persist(mut iter: Iter<Vec<u8>>) -> Option<PERSIST> {
+    let key = iter.next()?;
+    Some(PERSIST { key })
 }
 
 #[derive(Debug)]
@@ -39,10 +39,10 @@ pub struct EXPIRE<'a> {
     pub seconds: &'a [u8],
 }
 
-pub(crate) fn parse_expire(mut iter: Iter<Vec<u8>>) -> EXPIRE {
-    let key = iter.next().unwrap();
-    let seconds = iter.next().unwrap();
-    EXPIRE { key, seconds }
+pub(crate) fn parse_expire(mut iter: Iter<Vec<u8>>) -> Option<EXPIRE> {
+    let key = iter.next()?;
+    let seconds = iter.next()?;
+    Some(EXPIRE { key, seconds })
 }
 
 #[derive(Debug)]
@@ -51,10 +51,10 @@ pub struct PEXPIRE<'a> {
     pub milliseconds: &'a [u8],
 }
 
-pub(crate) fn parse_pexpire(mut iter: Iter<Vec<u8>>) -> PEXPIRE {
-    let key = iter.next().unwrap();
-    let milliseconds = iter.next().unwrap();
-    PEXPIRE { key, milliseconds }
+pub(crate) fn parse_pexpire(mut iter: Iter<Vec<u8>>) -> Option<PEXPIRE> {
+    let key = iter.next()?;
+    let milliseconds = iter.next()?;
+    Some(PEXPIRE { key, milliseconds })
 }
 
 #[derive(Debug)]
@@ -63,10 +63,10 @@ pub struct EXPIREAT<'a> {
     pub timestamp: &'a [u8],
 }
 
-pub(crate) fn parse_expireat(mut iter: Iter<Vec<u8>>) -> EXPIREAT {
-    let key = iter.next().unwrap();
-    let timestamp = iter.next().unwrap();
-    EXPIREAT { key, timestamp }
+pub(crate) fn parse_expireat(mut iter: Iter<Vec<u8>>) -> Option<EXPIREAT> {
+    let key = iter.next()?;
+    let timestamp = iter.next()?;
+    Some(EXPIREAT { key, timestamp })
 }
 
 #[derive(Debug)]
@@ -75,10 +75,10 @@ pub struct PEXPIREAT<'a> {
     pub mill_timestamp: &'a [u8],
 }
 
-pub(crate) fn parse_pexpireat(mut iter: Iter<Vec<u8>>) -> PEXPIREAT {
-    let key = iter.next().unwrap();
-    let mill_timestamp = iter.next().unwrap();
-    PEXPIREAT { key, mill_timestamp }
+pub(crate) fn parse_pexpireat(mut iter: Iter<Vec<u8>>) -> Option<PEXPIREAT> {
+    let key = iter.next()?;
+    let mill_timestamp = iter.next()?;
+    Some(PEXPIREAT { key, mill_timestamp })
 }
 
 #[derive(Debug)]
@@ -87,10 +87,10 @@ pub struct MOVE<'a> {
     pub db: &'a [u8],
 }
 
-pub(crate) fn parse_move(mut iter: Iter<Vec<u8>>) -> MOVE {
-    let key = iter.next().unwrap();
-    let db = iter.next().unwrap();
-    MOVE { key, db }
+pub(crate) fn parse_move(mut iter: Iter<Vec<u8>>) -> Option<MOVE> {
+    let key = iter.next()?;
+    let db = iter.next()?;
+    Some(MOVE { key, db })
 }
 
 #[derive(Debug)]
@@ -99,10 +99,10 @@ pub struct RENAME<'a> {
     pub new_key: &'a [u8],
 }
 
-pub(crate) fn parse_rename(mut iter: Iter<Vec<u8>>) -> RENAME {
-    let key = iter.next().unwrap();
-    let new_key = iter.next().unwrap();
-    RENAME { key, new_key }
+pub(crate) fn parse_rename(mut iter: Iter<Vec<u8>>) -> Option<RENAME> {
+    let key = iter.next()?;
+    let new_key = iter.next()?;
+    Some(RENAME { key, new_key })
 }
 
 #[derive(Debug)]
@@ -111,10 +111,10 @@ pub struct RENAMENX<'a> {
     pub new_key: &'a [u8],
 }
 
-pub(crate) fn parse_renamenx(mut iter: Iter<Vec<u8>>) -> RENAMENX {
-    let key = iter.next().unwrap();
-    let new_key = iter.next().unwrap();
-    RENAMENX { key, new_key }
+pub(crate) fn parse_renamenx(mut iter: Iter<Vec<u8>>) -> Option<RENAMENX> {
+    let key = iter.next()?;
+    let new_key = iter.next()?;
+    Some(RENAMENX { key, new_key })
 }
 
 #[derive(Debug)]
@@ -128,10 +128,10 @@ pub struct RESTORE<'a> {
     pub freq: Option<&'a [u8]>,
 }
 
-pub(crate) fn parse_restore(mut iter: Iter<Vec<u8>>) -> RESTORE {
-    let key = iter.next().unwrap();
-    let ttl = iter.next().unwrap();
-    let value = iter.next().unwrap();
+pub(crate) fn parse_restore(mut iter: Iter<Vec<u8>>) -> Option<RESTORE> {
+    let key = iter.next()?;
+    let ttl = iter.next()?;
+    let value = iter.next()?;
     let mut replace = None;
     let mut abs_ttl = None;
     let mut idle_time = None;
@@ -143,12 +143,12 @@ pub(crate) fn parse_restore(mut iter: Iter<Vec<u8>>) -> RESTORE {
         } else if &arg == "ABSTTL" {
             abs_ttl = Some(true);
         } else if &arg == "IDLETIME" {
-            idle_time = Some(iter.next().unwrap().as_slice());
+            idle_time = Some(iter.next()?.as_slice());
         } else if &arg == "FREQ" {
-            freq = Some(iter.next().unwrap().as_slice());
+            freq = Some(iter.next()?.as_slice());
         }
     }
-    RESTORE {
+    Some(RESTORE {
         key,
         ttl,
         value,
@@ -156,7 +156,7 @@ pub(crate) fn parse_restore(mut iter: Iter<Vec<u8>>) -> RESTORE {
         abs_ttl,
         idle_time,
         freq,
-    }
+    })
 }
 
 #[derive(Debug)]
@@ -182,8 +182,8 @@ pub enum ORDER {
     DESC,
 }
 
-pub(crate) fn parse_sort(mut iter: Iter<Vec<u8>>) -> SORT {
-    let key = iter.next().unwrap();
+pub(crate) fn parse_sort(mut iter: Iter<Vec<u8>>) -> Option<SORT> {
+    let key = iter.next()?;
     let mut order = None;
     let mut alpha = None;
     let mut limit = None;
@@ -201,24 +201,24 @@ pub(crate) fn parse_sort(mut iter: Iter<Vec<u8>>) -> SORT {
         } else if &arg_upper == "ALPHA" {
             alpha = Some(true);
         } else if &arg_upper == "LIMIT" {
-            let offset = iter.next().unwrap();
-            let count = iter.next().unwrap();
+            let offset = iter.next()?;
+            let count = iter.next()?;
             limit = Some(LIMIT { offset, count });
         } else if &arg_upper == "STORE" {
-            let store = iter.next().unwrap();
+            let store = iter.next()?;
             destination = Some(store.as_slice());
         } else if &arg_upper == "BY" {
-            let pattern = iter.next().unwrap();
+            let pattern = iter.next()?;
             by_pattern = Some(pattern.as_slice());
         } else if &arg_upper == "GET" {
-            let next_pattern = iter.next().unwrap();
+            let next_pattern = iter.next()?;
             patterns.push(next_pattern.as_slice());
         }
     }
     if !patterns.is_empty() {
         get_patterns = Some(patterns);
     }
-    SORT {
+    Some(SORT {
         key,
         by_pattern,
         limit,
@@ -226,7 +226,7 @@ pub(crate) fn parse_sort(mut iter: Iter<Vec<u8>>) -> SORT {
         order,
         alpha,
         destination,
-    }
+    })
 }
 
 #[derive(Debug)]
@@ -234,10 +234,10 @@ pub struct UNLINK<'a> {
     pub keys: Vec<&'a [u8]>,
 }
 
-pub(crate) fn parse_unlink(mut iter: Iter<Vec<u8>>) -> UNLINK {
+pub(crate) fn parse_unlink(mut iter: Iter<Vec<u8>>) -> Option<UNLINK> {
     let mut keys = Vec::new();
     while let Some(next_key) = iter.next() {
         keys.push(next_key.as_slice());
     }
-    UNLINK { keys }
+    Some(UNLINK { keys })
 }
@@ -17,15 +17,15 @@ pub struct BRPOPLPUSH<'a> {
     pub timeout: &'a [u8],
 }
 
-pub(crate) fn parse_brpoplpush(mut iter: Iter<Vec<u8>>) -> BRPOPLPUSH {
-    let source = iter.next().unwrap();
-    let destination = iter.next().unwrap();
-    let timeout = iter.next().unwrap();
-    BRPOPLPUSH {
+pub(crate) fn parse_brpoplpush(mut iter: Iter<Vec<u8>>) -> Option<BRPOPLPUSH> {
+    let source = iter.next()?;
+    let destination = iter.next()?;
+    let timeout = iter.next()?;
+    Some(BRPOPLPUSH {
         source,
         destination,
         timeout,
-    }
+    })
 }
 
 #[derive(Debug)]
@@ -42,9 +42,9 @@ pub enum POSITION {
     AFTER,
 }
 
-pub(crate) fn parse_linsert(mut iter: Iter<Vec<u8>>) -> LINSERT {
-    let key = iter.next().unwrap();
-    let next_arg = iter.next().unwrap();
+pub(crate) fn parse_linsert(mut iter: Iter<Vec<u8>>) -> Option<LINSERT> {
+    let key = iter.next()?;
+    let next_arg = iter.next()?;
     let position;
     let arg_upper = String::from_utf8_lossy(next_arg).to_uppercase();
     if &arg_upper == "BEFORE" {
@@ -52,14 +52,14 @@ pub(crate) fn parse_linsert(mut iter: Iter<Vec<u8>>) -> LINSERT {
     } else {
         position = AFTER;
     }
-    let pivot = iter.next().unwrap();
-    let element = iter.next().unwrap();
-    LINSERT {
+    let pivot = iter.next()?;
+    let element = iter.next()?;
+    Some(LINSERT {
         key,
         position,
         pivot,
         element,
-    }
+    })
 }
 
 #[derive(Debug)]
@@ -67,9 +67,9 @@ pub struct LPOP<'a> {
     pub key: &'a [u8],
 }
 
-pub(crate) fn parse_lpop(mut iter: Iter<Vec<u8>>) -> LPOP {
-    let key = iter.next().unwrap();
-    LPOP { key }
+pub(crate) fn parse_lpop(mut iter: Iter<Vec<u8>>) -> Option<LPOP> {
+    let key = iter.next()?;
+    Some(LPOP { key })
 }
 
 #[derive(Debug)]
@@ -78,13 +78,13 @@ pub struct LPUSH<'a> {
     pub elements: Vec<&'a [u8]>,
 }
 
-pub(crate) fn parse_lpush(mut iter: Iter<Vec<u8>>) -> LPUSH {
-    let key = iter.next().unwrap();
+pub(crate) fn parse_lpush(mut iter: Iter<Vec<u8>>) -> Option<LPUSH> {
+    let key = iter.next()?;
     let mut elements = Vec::new();
     while let Some(ele) = iter.next() {
         elements.push(ele.as_slice());
     }
-    LPUSH { key, elements }
+    Some(LPUSH { key, elements })
 }
 
 #[derive(Debug)]
@@ -93,13 +93,13 @@ pub struct LPUSHX<'a> {
     pub elements: Vec<&'a [u8]>,
 }
 
-pub(crate) fn parse_lpushx(mut iter: Iter<Vec<u8>>) -> LPUSHX {
-    let key = iter.next().unwrap();
+pub(crate) fn parse_lpushx(mut iter: Iter<Vec<u8>>) -> Option<LPUSHX> {
+    let key = iter.next()?;
     let mut elements = Vec::new();
     while let Some(ele) = iter.next() {
         elements.push(ele.as_slice());
     }
-    LPUSHX { key, elements }
+    Some(LPUSHX { key, elements })
 }
 
 #[derive(Debug)]
@@ -109,11 +109,11 @@ pub struct LREM<'a> {
     pub element: &'a [u8],
 }
 
-pub(crate) fn parse_lrem(mut iter: Iter<Vec<u8>>) -> LREM {
-    let key = iter.next().unwrap();
-    let count = iter.next().unwrap();
-    let element = iter.next().unwrap();
-    LREM { key, count, element }
+pub(crate) fn parse_lrem(mut iter: Iter<Vec<u8>>) -> Option<LREM> {
+    let key = iter.next()?;
+    let count = iter.next()?;
+    let element = iter.next()?;
+    Some(LREM { key, count, element })
 }
 
 #[derive(Debug)]
@@ -123,11 +123,11 @@ pub struct LSET<'a> {
     pub element: &'a [u8],
 }
 
-pub(crate) fn parse_lset(mut iter: Iter<Vec<u8>>) -> LSET {
-    let key = iter.next().unwrap();
-    let index = iter.next().unwrap();
-    let element = iter.next().unwrap();
-    LSET { key, index, element }
+pub(crate) fn parse_lset(mut iter: Iter<Vec<u8>>) -> Option<LSET> {
+    let key = iter.next()?;
+    let index = iter.next()?;
+    let element = iter.next()?;
+    Some(LSET { key, index, element })
 }
 
 #[derive(Debug)]
@@ -137,11 +137,11 @@ pub struct LTRIM<'a> {
     pub stop: &'a [u8],
 }
 
-pub(crate) fn parse_ltrim(mut iter: Iter<Vec<u8>>) -> LTRIM {
-    let key = iter.next().unwrap();
-    let start = iter.next().unwrap();
-    let stop = iter.next().unwrap();
-    LTRIM { key, start, stop }
+pub(crate) fn parse_ltrim(mut iter: Iter<Vec<u8>>) -> Option<LTRIM> {
+    let key = iter.next()?;
+    let start = iter.next()?;
+    let stop = iter.next()?;
+    Some(LTRIM { key, start, stop })
 }
 
 #[derive(Debug)]
@@ -149,9 +149,9 @@ pub struct RPOP<'a> {
     pub key: &'a [u8],
 }
 
-pub(crate) fn parse_rpop(mut iter: Iter<Vec<u8>>) -> RPOP {
-    let key = iter.next().unwrap();
-    RPOP { key }
+pub(crate) fn parse_rpop(mut iter: Iter<Vec<u8>>) -> Option<RPOP> {
+    let key = iter.next()?;
+    Some(RPOP { key })
 }
 
 #[derive(Debug)]
@@ -160,10 +160,10 @@ pub struct RPOPLPUSH<'a> {
     pub destination: &'a [u8],
 }
 
-pub(crate) fn parse_rpoplpush(mut iter: Iter<Vec<u8>>) -> RPOPLPUSH {
-    let source = iter.next().unwrap();
-    let destination = iter.next().unwrap();
-    RPOPLPUSH { source, destination }
+pub(crate) fn parse_rpoplpush(mut iter: Iter<Vec<u8>>) -> Option<RPOPLPUSH> {
+    let source = iter.next()?;
+    let destination = iter.next()?;
+    Some(RPOPLPUSH { source, destination })
 }
 
 #[derive(Debug)]
@@ -172,13 +172,13 @@ pub struct RPUSH<'a> {
     pub elements: Vec<&'a [u8]>,
 }
 
-pub(crate) fn parse_rpush(mut iter: Iter<Vec<u8>>) -> RPUSH {
-    let key = iter.next().unwrap();
+pub(crate) fn parse_rpush(mut iter: Iter<Vec<u8>>) -> Option<RPUSH> {
+    let key = iter.next()?;
     let mut elements = Vec::new();
     while let Some(ele) = iter.next() {
         elements.push(ele.as_slice());
     }
-    RPUSH { key, elements }
+    Some(RPUSH { key, elements })
 }
 
 #[derive(Debug)]
@@ -187,11 +187,11 @@ pub struct RPUSHX<'a> {
     pub elements: Vec<&'a [u8]>,
 }
 
-pub(crate) fn parse_rpushx(mut iter: Iter<Vec<u8>>) -> RPUSHX {
-    let key = iter.next().unwrap();
+pub(crate) fn parse_rpushx(mut iter: Iter<Vec<u8>>) -> Option<RPUSHX> {
+    let key = iter.next()?;
     let mut elements = Vec::new();
     while let Some(ele) = iter.next() {
         elements.push(ele.as_slice());
     }
-    RPUSHX { key, elements }
+    Some(RPUSHX { key, elements })
 }
@@ -42,7 +42,7 @@ pub mod strings;
 /// 所有支持的Redis命令
 ///
 /// 不在此枚举中的Redis命令均不支持
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub enum Command<'a> {
     APPEND(&'a APPEND<'a>),
     BITFIELD(&'a BITFIELD<'a>),
@@ -128,352 +128,589 @@ pub enum Command<'a> {
     Other(RawCommand),
 }
 
-#[derive(Debug)]
+impl Command<'_> {
+    /// 命令的名称(大写)，如`SET`，[`Command::Other`]返回`RawCommand::name`
+    pub fn name(&self) -> &str {
+        match self {
+            Command::APPEND(_) => "APPEND",
+            Command::BITFIELD(_) => "BITFIELD",
+            Command::BITOP(_) => "BITOP",
+            Command::BRPOPLPUSH(_) => "BRPOPLPUSH",
+            Command::DECR(_) => "DECR",
+            Command::DECRBY(_) => "DECRBY",
+            Command::DEL(_) => "DEL",
+            Command::EVAL(_) => "EVAL",
+            Command::EVALSHA(_) => "EVALSHA",
+            Command::EXPIRE(_) => "EXPIRE",
+            Command::EXPIREAT(_) => "EXPIREAT",
+            Command::EXEC => "EXEC",
+            Command::FLUSHALL(_) => "FLUSHALL",
+            Command::FLUSHDB(_) => "FLUSHDB",
+            Command::GETSET(_) => "GETSET",
+            Command::HDEL(_) => "HDEL",
+            Command::HINCRBY(_) => "HINCRBY",
+            Command::HMSET(_) => "HMSET",
+            Command::HSET(_) => "HSET",
+            Command::HSETNX(_) => "HSETNX",
+            Command::INCR(_) => "INCR",
+            Command::INCRBY(_) => "INCRBY",
+            Command::LINSERT(_) => "LINSERT",
+            Command::LPOP(_) => "LPOP",
+            Command::LPUSH(_) => "LPUSH",
+            Command::LPUSHX(_) => "LPUSHX",
+            Command::LREM(_) => "LREM",
+            Command::LSET(_) => "LSET",
+            Command::LTRIM(_) => "LTRIM",
+            Command::MOVE(_) => "MOVE",
+            Command::MSET(_) => "MSET",
+            Command::MSETNX(_) => "MSETNX",
+            Command::MULTI => "MULTI",
+            Command::PERSIST(_) => "PERSIST",
+            Command::PEXPIRE(_) => "PEXPIRE",
+            Command::PEXPIREAT(_) => "PEXPIREAT",
+            Command::PFADD(_) => "PFADD",
+            Command::PFCOUNT(_) => "PFCOUNT",
+            Command::PFMERGE(_) => "PFMERGE",
+            Command::PSETEX(_) => "PSETEX",
+            Command::PUBLISH(_) => "PUBLISH",
+            Command::RENAME(_) => "RENAME",
+            Command::RENAMENX(_) => "RENAMENX",
+            Command::RESTORE(_) => "RESTORE",
+            Command::RPOP(_) => "RPOP",
+            Command::RPOPLPUSH(_) => "RPOPLPUSH",
+            Command::RPUSH(_) => "RPUSH",
+            Command::RPUSHX(_) => "RPUSHX",
+            Command::SADD(_) => "SADD",
+            Command::SCRIPTFLUSH => "SCRIPT FLUSH",
+            Command::SCRIPTLOAD(_) => "SCRIPT LOAD",
+            Command::SDIFFSTORE(_) => "SDIFFSTORE",
+            Command::SET(_) => "SET",
+            Command::SETBIT(_) => "SETBIT",
+            Command::SETEX(_) => "SETEX",
+            Command::SETNX(_) => "SETNX",
+            Command::SELECT(_) => "SELECT",
+            Command::SETRANGE(_) => "SETRANGE",
+            Command::SINTERSTORE(_) => "SINTERSTORE",
+            Command::SMOVE(_) => "SMOVE",
+            Command::SORT(_) => "SORT",
+            Command::SREM(_) => "SREM",
+            Command::SUNIONSTORE(_) => "SUNIONSTORE",
+            Command::SWAPDB(_) => "SWAPDB",
+            Command::UNLINK(_) => "UNLINK",
+            Command::ZADD(_) => "ZADD",
+            Command::ZINCRBY(_) => "ZINCRBY",
+            Command::ZINTERSTORE(_) => "ZINTERSTORE",
+            Command::ZPOPMAX(_) => "ZPOPMAX",
+            Command::ZPOPMIN(_) => "ZPOPMIN",
+            Command::ZREM(_) => "ZREM",
+            Command::ZREMRANGEBYLEX(_) => "ZREMRANGEBYLEX",
+            Command::ZREMRANGEBYRANK(_) => "ZREMRANGEBYRANK",
+            Command::ZREMRANGEBYSCORE(_) => "ZREMRANGEBYSCORE",
+            Command::ZUNIONSTORE(_) => "ZUNIONSTORE",
+            Command::XACK(_) => "XACK",
+            Command::XADD(_) => "XADD",
+            Command::XCLAIM(_) => "XCLAIM",
+            Command::XDEL(_) => "XDEL",
+            Command::XGROUP(_) => "XGROUP",
+            Command::XTRIM(_) => "XTRIM",
+            Command::Other(cmd) => &cmd.name,
+        }
+    }
+
+    /// 命令所操作的key，不涉及key的命令(如`SELECT`)以及[`Command::Other`]返回空
+    pub fn keys(&self) -> Vec<&[u8]> {
+        match self {
+            Command::APPEND(cmd) => vec![cmd.key],
+            Command::BITFIELD(cmd) => vec![cmd.key],
+            Command::BITOP(cmd) => {
+                let mut keys = vec![cmd.dest_key];
+                keys.extend(cmd.keys.iter().map(|key| key.as_slice()));
+                keys
+            }
+            Command::BRPOPLPUSH(cmd) => vec![cmd.source, cmd.destination],
+            Command::DECR(cmd) => vec![cmd.key],
+            Command::DECRBY(cmd) => vec![cmd.key],
+            Command::DEL(cmd) => cmd.keys.iter().map(|key| key.as_slice()).collect(),
+            Command::EVAL(cmd) => cmd.keys.clone(),
+            Command::EVALSHA(cmd) => cmd.keys.clone(),
+            Command::EXPIRE(cmd) => vec![cmd.key],
+            Command::EXPIREAT(cmd) => vec![cmd.key],
+            Command::GETSET(cmd) => vec![cmd.key],
+            Command::HDEL(cmd) => vec![cmd.key],
+            Command::HINCRBY(cmd) => vec![cmd.key],
+            Command::HMSET(cmd) => vec![cmd.key],
+            Command::HSET(cmd) => vec![cmd.key],
+            Command::HSETNX(cmd) => vec![cmd.key],
+            Command::INCR(cmd) => vec![cmd.key],
+            Command::INCRBY(cmd) => vec![cmd.key],
+            Command::LINSERT(cmd) => vec![cmd.key],
+            Command::LPOP(cmd) => vec![cmd.key],
+            Command::LPUSH(cmd) => vec![cmd.key],
+            Command::LPUSHX(cmd) => vec![cmd.key],
+            Command::LREM(cmd) => vec![cmd.key],
+            Command::LSET(cmd) => vec![cmd.key],
+            Command::LTRIM(cmd) => vec![cmd.key],
+            Command::MOVE(cmd) => vec![cmd.key],
+            Command::MSET(cmd) => cmd.key_values.iter().map(|kv| kv.key).collect(),
+            Command::MSETNX(cmd) => cmd.key_values.iter().map(|kv| kv.key).collect(),
+            Command::PERSIST(cmd) => vec![cmd.key],
+            Command::PEXPIRE(cmd) => vec![cmd.key],
+            Command::PEXPIREAT(cmd) => vec![cmd.key],
+            Command::PFADD(cmd) => vec![cmd.key],
+            Command::PFCOUNT(cmd) => cmd.keys.clone(),
+            Command::PFMERGE(cmd) => {
+                let mut keys = vec![cmd.dest_key];
+                keys.extend_from_slice(&cmd.source_keys);
+                keys
+            }
+            Command::PSETEX(cmd) => vec![cmd.key],
+            Command::RENAME(cmd) => vec![cmd.key, cmd.new_key],
+            Command::RENAMENX(cmd) => vec![cmd.key, cmd.new_key],
+            Command::RESTORE(cmd) => vec![cmd.key],
+            Command::RPOP(cmd) => vec![cmd.key],
+            Command::RPOPLPUSH(cmd) => vec![cmd.source, cmd.destination],
+            Command::RPUSH(cmd) => vec![cmd.key],
+            Command::RPUSHX(cmd) => vec![cmd.key],
+            Command::SADD(cmd) => vec![cmd.key],
+            Command::SDIFFSTORE(cmd) => {
+                let mut keys = vec![cmd.destination];
+                keys.extend_from_slice(&cmd.keys);
+                keys
+            }
+            Command::SET(cmd) => vec![cmd.key],
+            Command::SETBIT(cmd) => vec![cmd.key],
+            Command::SETEX(cmd) => vec![cmd.key],
+            Command::SETNX(cmd) => vec![cmd.key],
+            Command::SETRANGE(cmd) => vec![cmd.key],
+            Command::SINTERSTORE(cmd) => {
+                let mut keys = vec![cmd.destination];
+                keys.extend_from_slice(&cmd.keys);
+                keys
+            }
+            Command::SMOVE(cmd) => vec![cmd.source, cmd.destination],
+            Command::SORT(cmd) => {
+                let mut keys = vec![cmd.key];
+                keys.extend(cmd.destination);
+                keys
+            }
+            Command::SREM(cmd) => vec![cmd.key],
+            Command::SUNIONSTORE(cmd) => {
+                let mut keys = vec![cmd.destination];
+                keys.extend_from_slice(&cmd.keys);
+                keys
+            }
+            Command::UNLINK(cmd) => cmd.keys.clone(),
+            Command::ZADD(cmd) => vec![cmd.key],
+            Command::ZINCRBY(cmd) => vec![cmd.key],
+            Command::ZINTERSTORE(cmd) => {
+                let mut keys = vec![cmd.destination];
+                keys.extend_from_slice(&cmd.keys);
+                keys
+            }
+            Command::ZPOPMAX(cmd) => vec![cmd.key],
+            Command::ZPOPMIN(cmd) => vec![cmd.key],
+            Command::ZREM(cmd) => vec![cmd.key],
+            Command::ZREMRANGEBYLEX(cmd) => vec![cmd.key],
+            Command::ZREMRANGEBYRANK(cmd) => vec![cmd.key],
+            Command::ZREMRANGEBYSCORE(cmd) => vec![cmd.key],
+            Command::ZUNIONSTORE(cmd) => {
+                let mut keys = vec![cmd.destination];
+                keys.extend_from_slice(&cmd.keys);
+                keys
+            }
+            Command::XACK(cmd) => vec![cmd.key],
+            Command::XADD(cmd) => vec![cmd.key],
+            Command::XCLAIM(cmd) => vec![cmd.key],
+            Command::XDEL(cmd) => vec![cmd.key],
+            Command::XGROUP(cmd) => {
+                let mut keys = Vec::new();
+                keys.extend(cmd.create.as_ref().map(|create| create.key));
+                keys.extend(cmd.set_id.as_ref().map(|set_id| set_id.key));
+                keys.extend(cmd.destroy.as_ref().map(|destroy| destroy.key));
+                keys.extend(cmd.del_consumer.as_ref().map(|del_consumer| del_consumer.key));
+                keys
+            }
+            Command::XTRIM(cmd) => vec![cmd.key],
+            Command::EXEC
+            | Command::FLUSHALL(_)
+            | Command::FLUSHDB(_)
+            | Command::MULTI
+            | Command::PUBLISH(_)
+            | Command::SCRIPTFLUSH
+            | Command::SCRIPTLOAD(_)
+            | Command::SELECT(_)
+            | Command::SWAPDB(_)
+            | Command::Other(_) => Vec::new(),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
 pub struct RawCommand {
     pub name: String,
     pub args: Vec<Vec<u8>>,
 }
 
+/// 解析得到的命令，持有各个命令结构体，通过[`ParsedCommand::as_command`]借出对应的[`Command`]
+#[allow(clippy::upper_case_acronyms)]
+pub(crate) enum ParsedCommand<'a> {
+    APPEND(APPEND<'a>),
+    BITFIELD(BITFIELD<'a>),
+    BITOP(BITOP<'a>),
+    BRPOPLPUSH(BRPOPLPUSH<'a>),
+    DECR(DECR<'a>),
+    DECRBY(DECRBY<'a>),
+    DEL(DEL<'a>),
+    EVAL(EVAL<'a>),
+    EVALSHA(EVALSHA<'a>),
+    EXPIRE(EXPIRE<'a>),
+    EXPIREAT(EXPIREAT<'a>),
+    EXEC,
+    FLUSHALL(FLUSHALL),
+    FLUSHDB(FLUSHDB),
+    GETSET(GETSET<'a>),
+    HDEL(HDEL<'a>),
+    HINCRBY(HINCRBY<'a>),
+    HMSET(HMSET<'a>),
+    HSET(HSET<'a>),
+    HSETNX(HSETNX<'a>),
+    INCR(INCR<'a>),
+    INCRBY(INCRBY<'a>),
+    LINSERT(LINSERT<'a>),
+    LPOP(LPOP<'a>),
+    LPUSH(LPUSH<'a>),
+    LPUSHX(LPUSHX<'a>),
+    LREM(LREM<'a>),
+    LSET(LSET<'a>),
+    LTRIM(LTRIM<'a>),
+    MOVE(MOVE<'a>),
+    MSET(MSET<'a>),
+    MSETNX(MSETNX<'a>),
+    MULTI,
+    PERSIST(PERSIST<'a>),
+    PEXPIRE(PEXPIRE<'a>),
+    PEXPIREAT(PEXPIREAT<'a>),
+    PFADD(PFADD<'a>),
+    PFCOUNT(PFCOUNT<'a>),
+    PFMERGE(PFMERGE<'a>),
+    PSETEX(PSETEX<'a>),
+    PUBLISH(PUBLISH<'a>),
+    RENAME(RENAME<'a>),
+    RENAMENX(RENAMENX<'a>),
+    RESTORE(RESTORE<'a>),
+    RPOP(RPOP<'a>),
+    RPOPLPUSH(RPOPLPUSH<'a>),
+    RPUSH(RPUSH<'a>),
+    RPUSHX(RPUSHX<'a>),
+    SADD(SADD<'a>),
+    SCRIPTFLUSH,
+    SCRIPTLOAD(SCRIPTLOAD<'a>),
+    SDIFFSTORE(SDIFFSTORE<'a>),
+    SET(SET<'a>),
+    SETBIT(SETBIT<'a>),
+    SETEX(SETEX<'a>),
+    SETNX(SETNX<'a>),
+    SELECT(SELECT),
+    SETRANGE(SETRANGE<'a>),
+    SINTERSTORE(SINTERSTORE<'a>),
+    SMOVE(SMOVE<'a>),
+    SORT(SORT<'a>),
+    SREM(SREM<'a>),
+    SUNIONSTORE(SUNIONSTORE<'a>),
+    SWAPDB(SWAPDB<'a>),
+    UNLINK(UNLINK<'a>),
+    ZADD(ZADD<'a>),
+    ZINCRBY(ZINCRBY<'a>),
+    ZINTERSTORE(ZINTERSTORE<'a>),
+    ZPOPMAX(ZPOPMAX<'a>),
+    ZPOPMIN(ZPOPMIN<'a>),
+    ZREM(ZREM<'a>),
+    ZREMRANGEBYLEX(ZREMRANGEBYLEX<'a>),
+    ZREMRANGEBYRANK(ZREMRANGEBYRANK<'a>),
+    ZREMRANGEBYSCORE(ZREMRANGEBYSCORE<'a>),
+    ZUNIONSTORE(ZUNIONSTORE<'a>),
+    XACK(XACK<'a>),
+    XADD(XADD<'a>),
+    XCLAIM(XCLAIM<'a>),
+    XDEL(XDEL<'a>),
+    XGROUP(XGROUP<'a>),
+    XTRIM(XTRIM<'a>),
+    Other(RawCommand),
+}
+
+impl ParsedCommand<'_> {
+    pub(crate) fn as_command(&self) -> Command<'_> {
+        match self {
+            ParsedCommand::APPEND(cmd) => Command::APPEND(cmd),
+            ParsedCommand::BITFIELD(cmd) => Command::BITFIELD(cmd),
+            ParsedCommand::BITOP(cmd) => Command::BITOP(cmd),
+            ParsedCommand::BRPOPLPUSH(cmd) => Command::BRPOPLPUSH(cmd),
+            ParsedCommand::DECR(cmd) => Command::DECR(cmd),
+            ParsedCommand::DECRBY(cmd) => Command::DECRBY(cmd),
+            ParsedCommand::DEL(cmd) => Command::DEL(cmd),
+            ParsedCommand::EVAL(cmd) => Command::EVAL(cmd),
+            ParsedCommand::EVALSHA(cmd) => Command::EVALSHA(cmd),
+            ParsedCommand::EXPIRE(cmd) => Command::EXPIRE(cmd),
+            ParsedCommand::EXPIREAT(cmd) => Command::EXPIREAT(cmd),
+            ParsedCommand::EXEC => Command::EXEC,
+            ParsedCommand::FLUSHALL(cmd) => Command::FLUSHALL(cmd),
+            ParsedCommand::FLUSHDB(cmd) => Command::FLUSHDB(cmd),
+            ParsedCommand::GETSET(cmd) => Command::GETSET(cmd),
+            ParsedCommand::HDEL(cmd) => Command::HDEL(cmd),
+            ParsedCommand::HINCRBY(cmd) => Command::HINCRBY(cmd),
+            ParsedCommand::HMSET(cmd) => Command::HMSET(cmd),
+            ParsedCommand::HSET(cmd) => Command::HSET(cmd),
+            ParsedCommand::HSETNX(cmd) => Command::HSETNX(cmd),
+            ParsedCommand::INCR(cmd) => Command::INCR(cmd),
+            ParsedCommand::INCRBY(cmd) => Command::INCRBY(cmd),
+            ParsedCommand::LINSERT(cmd) => Command::LINSERT(cmd),
+            ParsedCommand::LPOP(cmd) => Command::LPOP(cmd),
+            ParsedCommand::LPUSH(cmd) => Command::LPUSH(cmd),
+            ParsedCommand::LPUSHX(cmd) => Command::LPUSHX(cmd),
+            ParsedCommand::LREM(cmd) => Command::LREM(cmd),
+            ParsedCommand::LSET(cmd) => Command::LSET(cmd),
+            ParsedCommand::LTRIM(cmd) => Command::LTRIM(cmd),
+            ParsedCommand::MOVE(cmd) => Command::MOVE(cmd),
+            ParsedCommand::MSET(cmd) => Command::MSET(cmd),
+            ParsedCommand::MSETNX(cmd) => Command::MSETNX(cmd),
+            ParsedCommand::MULTI => Command::MULTI,
+            ParsedCommand::PERSIST(cmd) => Command::PERSIST(cmd),
+            ParsedCommand::PEXPIRE(cmd) => Command::PEXPIRE(cmd),
+            ParsedCommand::PEXPIREAT(cmd) => Command::PEXPIREAT(cmd),
+            ParsedCommand::PFADD(cmd) => Command::PFADD(cmd),
+            ParsedCommand::PFCOUNT(cmd) => Command::PFCOUNT(cmd),
+            ParsedCommand::PFMERGE(cmd) => Command::PFMERGE(cmd),
+            ParsedCommand::PSETEX(cmd) => Command::PSETEX(cmd),
+            ParsedCommand::PUBLISH(cmd) => Command::PUBLISH(cmd),
+            ParsedCommand::RENAME(cmd) => Command::RENAME(cmd),
+            ParsedCommand::RENAMENX(cmd) => Command::RENAMENX(cmd),
+            ParsedCommand::RESTORE(cmd) => Command::RESTORE(cmd),
+            ParsedCommand::RPOP(cmd) => Command::RPOP(cmd),
+            ParsedCommand::RPOPLPUSH(cmd) => Command::RPOPLPUSH(cmd),
+            ParsedCommand::RPUSH(cmd) => Command::RPUSH(cmd),
+            ParsedCommand::RPUSHX(cmd) => Command::RPUSHX(cmd),
+            ParsedCommand::SADD(cmd) => Command::SADD(cmd),
+            ParsedCommand::SCRIPTFLUSH => Command::SCRIPTFLUSH,
+            ParsedCommand::SCRIPTLOAD(cmd) => Command::SCRIPTLOAD(cmd),
+            ParsedCommand::SDIFFSTORE(cmd) => Command::SDIFFSTORE(cmd),
+            ParsedCommand::SET(cmd) => Command::SET(cmd),
+            ParsedCommand::SETBIT(cmd) => Command::SETBIT(cmd),
+            ParsedCommand::SETEX(cmd) => Command::SETEX(cmd),
+            ParsedCommand::SETNX(cmd) => Command::SETNX(cmd),
+            ParsedCommand::SELECT(cmd) => Command::SELECT(cmd),
+            ParsedCommand::SETRANGE(cmd) => Command::SETRANGE(cmd),
+            ParsedCommand::SINTERSTORE(cmd) => Command::SINTERSTORE(cmd),
+            ParsedCommand::SMOVE(cmd) => Command::SMOVE(cmd),
+            ParsedCommand::SORT(cmd) => Command::SORT(cmd),
+            ParsedCommand::SREM(cmd) => Command::SREM(cmd),
+            ParsedCommand::SUNIONSTORE(cmd) => Command::SUNIONSTORE(cmd),
+            ParsedCommand::SWAPDB(cmd) => Command::SWAPDB(cmd),
+            ParsedCommand::UNLINK(cmd) => Command::UNLINK(cmd),
+            ParsedCommand::ZADD(cmd) => Command::ZADD(cmd),
+            ParsedCommand::ZINCRBY(cmd) => Command::ZINCRBY(cmd),
+            ParsedCommand::ZINTERSTORE(cmd) => Command::ZINTERSTORE(cmd),
+            ParsedCommand::ZPOPMAX(cmd) => Command::ZPOPMAX(cmd),
+            ParsedCommand::ZPOPMIN(cmd) => Command::ZPOPMIN(cmd),
+            ParsedCommand::ZREM(cmd) => Command::ZREM(cmd),
+            ParsedCommand::ZREMRANGEBYLEX(cmd) => Command::ZREMRANGEBYLEX(cmd),
+            ParsedCommand::ZREMRANGEBYRANK(cmd) => Command::ZREMRANGEBYRANK(cmd),
+            ParsedCommand::ZREMRANGEBYSCORE(cmd) => Command::ZREMRANGEBYSCORE(cmd),
+            ParsedCommand::ZUNIONSTORE(cmd) => Command::ZUNIONSTORE(cmd),
+            ParsedCommand::XACK(cmd) => Command::XACK(cmd),
+            ParsedCommand::XADD(cmd) => Command::XADD(cmd),
+            ParsedCommand::XCLAIM(cmd) => Command::XCLAIM(cmd),
+            ParsedCommand::XDEL(cmd) => Command::XDEL(cmd),
+            ParsedCommand::XGROUP(cmd) => Command::XGROUP(cmd),
+            ParsedCommand::XTRIM(cmd) => Command::XTRIM(cmd),
+            ParsedCommand::Other(cmd) => Command::Other(cmd.clone()),
+        }
+    }
+}
+
 pub(crate) fn parse(data: Vec<Vec<u8>>, cmd_handler: &mut dyn EventHandler) {
-    let mut iter = data.iter();
-    if let Some(cmd_name) = iter.next() {
-        let cmd_name = String::from_utf8_lossy(cmd_name).to_uppercase();
-        match cmd_name.as_str() {
-            "APPEND" => {
-                let cmd = strings::parse_append(iter);
-                cmd_handler.handle(Event::AOF(Command::APPEND(&cmd)));
-            }
-            "BITFIELD" => {
-                let cmd = strings::parse_bitfield(iter);
-                cmd_handler.handle(Event::AOF(Command::BITFIELD(&cmd)));
-            }
-            "BITOP" => {
-                let cmd = strings::parse_bitop(iter);
-                cmd_handler.handle(Event::AOF(Command::BITOP(&cmd)));
-            }
-            "BRPOPLPUSH" => {
-                let cmd = lists::parse_brpoplpush(iter);
-                cmd_handler.handle(Event::AOF(Command::BRPOPLPUSH(&cmd)));
-            }
-            "DEL" => {
-                let cmd = keys::parse_del(iter);
-                cmd_handler.handle(Event::AOF(Command::DEL(&cmd)));
-            }
-            "DECR" => {
-                let cmd = strings::parse_decr(iter);
-                cmd_handler.handle(Event::AOF(Command::DECR(&cmd)));
-            }
-            "DECRBY" => {
-                let cmd = strings::parse_decrby(iter);
-                cmd_handler.handle(Event::AOF(Command::DECRBY(&cmd)));
-            }
-            "EVAL" => {
-                let cmd = scripting::parse_eval(iter);
-                cmd_handler.handle(Event::AOF(Command::EVAL(&cmd)));
-            }
-            "EVALSHA" => {
-                let cmd = scripting::parse_evalsha(iter);
-                cmd_handler.handle(Event::AOF(Command::EVALSHA(&cmd)));
-            }
-            "EXPIRE" => {
-                let cmd = keys::parse_expire(iter);
-                cmd_handler.handle(Event::AOF(Command::EXPIRE(&cmd)));
-            }
-            "EXPIREAT" => {
-                let cmd = keys::parse_expireat(iter);
-                cmd_handler.handle(Event::AOF(Command::EXPIREAT(&cmd)));
-            }
-            "EXEC" => {
-                cmd_handler.handle(Event::AOF(Command::EXEC));
-            }
-            "FLUSHALL" => {
-                let cmd = server::parse_flushall(iter);
-                cmd_handler.handle(Event::AOF(Command::FLUSHALL(&cmd)));
-            }
-            "FLUSHDB" => {
-                let cmd = server::parse_flushdb(iter);
-                cmd_handler.handle(Event::AOF(Command::FLUSHDB(&cmd)));
-            }
-            "GETSET" => {
-                let cmd = strings::parse_getset(iter);
-                cmd_handler.handle(Event::AOF(Command::GETSET(&cmd)));
-            }
-            "HDEL" => {
-                let cmd = hashes::parse_hdel(iter);
-                cmd_handler.handle(Event::AOF(Command::HDEL(&cmd)));
-            }
-            "HINCRBY" => {
-                let cmd = hashes::parse_hincrby(iter);
-                cmd_handler.handle(Event::AOF(Command::HINCRBY(&cmd)));
-            }
-            "HMSET" => {
-                let cmd = hashes::parse_hmset(iter);
-                cmd_handler.handle(Event::AOF(Command::HMSET(&cmd)));
-            }
-            "HSET" => {
-                let cmd = hashes::parse_hset(iter);
-                cmd_handler.handle(Event::AOF(Command::HSET(&cmd)));
-            }
-            "HSETNX" => {
-                let cmd = hashes::parse_hsetnx(iter);
-                cmd_handler.handle(Event::AOF(Command::HSETNX(&cmd)));
-            }
-            "INCR" => {
-                let cmd = strings::parse_incr(iter);
-                cmd_handler.handle(Event::AOF(Command::INCR(&cmd)));
-            }
-            "INCRBY" => {
-                let cmd = strings::parse_incrby(iter);
-                cmd_handler.handle(Event::AOF(Command::INCRBY(&cmd)));
-            }
-            "LINSERT" => {
-                let cmd = lists::parse_linsert(iter);
-                cmd_handler.handle(Event::AOF(Command::LINSERT(&cmd)));
-            }
-            "LPOP" => {
-                let cmd = lists::parse_lpop(iter);
-                cmd_handler.handle(Event::AOF(Command::LPOP(&cmd)));
-            }
-            "LPUSH" => {
-                let cmd = lists::parse_lpush(iter);
-                cmd_handler.handle(Event::AOF(Command::LPUSH(&cmd)));
-            }
-            "LPUSHX" => {
-                let cmd = lists::parse_lpushx(iter);
-                cmd_handler.handle(Event::AOF(Command::LPUSHX(&cmd)));
-            }
-            "LREM" => {
-                let cmd = lists::parse_lrem(iter);
-                cmd_handler.handle(Event::AOF(Command::LREM(&cmd)));
-            }
-            "LSET" => {
-                let cmd = lists::parse_lset(iter);
-                cmd_handler.handle(Event::AOF(Command::LSET(&cmd)));
-            }
-            "LTRIM" => {
-                let cmd = lists::parse_ltrim(iter);
-                cmd_handler.handle(Event::AOF(Command::LTRIM(&cmd)));
-            }
-            "RENAME" => {
-                let cmd = keys::parse_rename(iter);
-                cmd_handler.handle(Event::AOF(Command::RENAME(&cmd)));
-            }
-            "RENAMENX" => {
-                let cmd = keys::parse_renamenx(iter);
-                cmd_handler.handle(Event::AOF(Command::RENAMENX(&cmd)));
-            }
-            "RESTORE" => {
-                let cmd = keys::parse_restore(iter);
-                cmd_handler.handle(Event::AOF(Command::RESTORE(&cmd)));
-            }
-            "RPOP" => {
-                let cmd = lists::parse_rpop(iter);
-                cmd_handler.handle(Event::AOF(Command::RPOP(&cmd)));
-            }
-            "RPOPLPUSH" => {
-                let cmd = lists::parse_rpoplpush(iter);
-                cmd_handler.handle(Event::AOF(Command::RPOPLPUSH(&cmd)));
-            }
-            "RPUSH" => {
-                let cmd = lists::parse_rpush(iter);
-                cmd_handler.handle(Event::AOF(Command::RPUSH(&cmd)));
-            }
-            "RPUSHX" => {
-                let cmd = lists::parse_rpushx(iter);
-                cmd_handler.handle(Event::AOF(Command::RPUSHX(&cmd)));
-            }
-            "SADD" => {
-                let cmd = sets::parse_sadd(iter);
-                cmd_handler.handle(Event::AOF(Command::SADD(&cmd)));
-            }
-            "SCRIPT" => {
-                let cmd = iter.next().unwrap();
-                let cmd = String::from_utf8_lossy(cmd).to_uppercase();
-                if &cmd == "LOAD" {
-                    let cmd = scripting::parse_script_load(iter);
-                    cmd_handler.handle(Event::AOF(Command::SCRIPTLOAD(&cmd)));
-                } else if &cmd == "FLUSH" {
-                    cmd_handler.handle(Event::AOF(Command::SCRIPTFLUSH));
-                }
-            }
-            "SDIFFSTORE" => {
-                let cmd = sets::parse_sdiffstore(iter);
-                cmd_handler.handle(Event::AOF(Command::SDIFFSTORE(&cmd)));
-            }
-            "SMOVE" => {
-                let cmd = sets::parse_smove(iter);
-                cmd_handler.handle(Event::AOF(Command::SMOVE(&cmd)));
-            }
-            "SET" => {
-                let cmd = strings::parse_set(iter);
-                cmd_handler.handle(Event::AOF(Command::SET(&cmd)));
-            }
-            "SELECT" => {
-                let cmd = connection::parse_select(iter);
-                cmd_handler.handle(Event::AOF(Command::SELECT(&cmd)));
-            }
-            "SORT" => {
-                let cmd = keys::parse_sort(iter);
-                cmd_handler.handle(Event::AOF(Command::SORT(&cmd)));
-            }
-            "SREM" => {
-                let cmd = sets::parse_srem(iter);
-                cmd_handler.handle(Event::AOF(Command::SREM(&cmd)));
-            }
-            "SUNIONSTORE" => {
-                let cmd = sets::parse_sunionstore(iter);
-                cmd_handler.handle(Event::AOF(Command::SUNIONSTORE(&cmd)));
-            }
-            "SWAPDB" => {
-                let cmd = connection::parse_swapdb(iter);
-                cmd_handler.handle(Event::AOF(Command::SWAPDB(&cmd)));
-            }
-            "UNLINK" => {
-                let cmd = keys::parse_unlink(iter);
-                cmd_handler.handle(Event::AOF(Command::UNLINK(&cmd)));
-            }
-            "MOVE" => {
-                let cmd = keys::parse_move(iter);
-                cmd_handler.handle(Event::AOF(Command::MOVE(&cmd)));
-            }
-            "MSET" => {
-                let cmd = strings::parse_mset(iter);
-                cmd_handler.handle(Event::AOF(Command::MSET(&cmd)));
-            }
-            "MSETNX" => {
-                let cmd = strings::parse_msetnx(iter);
-                cmd_handler.handle(Event::AOF(Command::MSETNX(&cmd)));
-            }
-            "MULTI" => {
-                cmd_handler.handle(Event::AOF(Command::MULTI));
-            }
-            "PFADD" => {
-                let cmd = hyperloglog::parse_pfadd(iter);
-                cmd_handler.handle(Event::AOF(Command::PFADD(&cmd)));
-            }
-            "PFCOUNT" => {
-                let cmd = hyperloglog::parse_pfcount(iter);
-                cmd_handler.handle(Event::AOF(Command::PFCOUNT(&cmd)));
-            }
-            "PFMERGE" => {
-                let cmd = hyperloglog::parse_pfmerge(iter);
-                cmd_handler.handle(Event::AOF(Command::PFMERGE(&cmd)));
-            }
-            "SETEX" => {
-                let cmd = strings::parse_setex(iter);
-                cmd_handler.handle(Event::AOF(Command::SETEX(&cmd)));
-            }
-            "SETNX" => {
-                let cmd = strings::parse_setnx(iter);
-                cmd_handler.handle(Event::AOF(Command::SETNX(&cmd)));
-            }
-            "PSETEX" => {
-                let cmd = strings::parse_psetex(iter);
-                cmd_handler.handle(Event::AOF(Command::PSETEX(&cmd)));
-            }
-            "PUBLISH" => {
-                let cmd = pub_sub::parse_publish(iter);
-                cmd_handler.handle(Event::AOF(Command::PUBLISH(&cmd)));
-            }
-            "PEXPIRE" => {
-                let cmd = keys::parse_pexpire(iter);
-                cmd_handler.handle(Event::AOF(Command::PEXPIRE(&cmd)));
-            }
-            "PEXPIREAT" => {
-                let cmd = keys::parse_pexpireat(iter);
-                cmd_handler.handle(Event::AOF(Command::PEXPIREAT(&cmd)));
-            }
-            "PERSIST" => {
-                let cmd = keys::parse_persist(iter);
-                cmd_handler.handle(Event::AOF(Command::PERSIST(&cmd)));
-            }
-            "SETRANGE" => {
-                let cmd = strings::parse_setrange(iter);
-                cmd_handler.handle(Event::AOF(Command::SETRANGE(&cmd)));
-            }
-            "SETBIT" => {
-                let cmd = strings::parse_setbit(iter);
-                cmd_handler.handle(Event::AOF(Command::SETBIT(&cmd)));
-            }
-            "SINTERSTORE" => {
-                let cmd = sets::parse_sinterstore(iter);
-                cmd_handler.handle(Event::AOF(Command::SINTERSTORE(&cmd)));
-            }
-            "ZADD" => {
-                let cmd = sorted_sets::parse_zadd(iter);
-                cmd_handler.handle(Event::AOF(Command::ZADD(&cmd)));
-            }
-            "ZINCRBY" => {
-                let cmd = sorted_sets::parse_zincrby(iter);
-                cmd_handler.handle(Event::AOF(Command::ZINCRBY(&cmd)));
-            }
-            "ZINTERSTORE" => {
-                let cmd = sorted_sets::parse_zinterstore(iter);
-                cmd_handler.handle(Event::AOF(Command::ZINTERSTORE(&cmd)));
-            }
-            "ZPOPMAX" => {
-                let cmd = sorted_sets::parse_zpopmax(iter);
-                cmd_handler.handle(Event::AOF(Command::ZPOPMAX(&cmd)));
-            }
-            "ZPOPMIN" => {
-                let cmd = sorted_sets::parse_zpopmin(iter);
-                cmd_handler.handle(Event::AOF(Command::ZPOPMIN(&cmd)));
-            }
-            "ZREM" => {
-                let cmd = sorted_sets::parse_zrem(iter);
-                cmd_handler.handle(Event::AOF(Command::ZREM(&cmd)));
-            }
-            "ZREMRANGEBYLEX" => {
-                let cmd = sorted_sets::parse_zremrangebylex(iter);
-                cmd_handler.handle(Event::AOF(Command::ZREMRANGEBYLEX(&cmd)));
-            }
-            "ZREMRANGEBYRANK" => {
-                let cmd = sorted_sets::parse_zremrangebyrank(iter);
-                cmd_handler.handle(Event::AOF(Command::ZREMRANGEBYRANK(&cmd)));
-            }
-            "ZREMRANGEBYSCORE" => {
-                let cmd = sorted_sets::parse_zremrangebyscore(iter);
-                cmd_handler.handle(Event::AOF(Command::ZREMRANGEBYSCORE(&cmd)));
-            }
-            "ZUNIONSTORE" => {
-                let cmd = sorted_sets::parse_zunionstore(iter);
-                cmd_handler.handle(Event::AOF(Command::ZUNIONSTORE(&cmd)));
-            }
-            "XACK" => {
-                let cmd = streams::parse_xack(iter);
-                cmd_handler.handle(Event::AOF(Command::XACK(&cmd)));
-            }
-            "XADD" => {
-                let cmd = streams::parse_xadd(iter);
-                cmd_handler.handle(Event::AOF(Command::XADD(&cmd)));
-            }
-            "XCLAIM" => {
-                let cmd = streams::parse_xclaim(iter);
-                cmd_handler.handle(Event::AOF(Command::XCLAIM(&cmd)));
-            }
-            "XDEL" => {
-                let cmd = streams::parse_xdel(iter);
-                cmd_handler.handle(Event::AOF(Command::XDEL(&cmd)));
-            }
-            "XGROUP" => {
-                let cmd = streams::parse_xgroup(iter);
-                cmd_handler.handle(Event::AOF(Command::XGROUP(&cmd)));
-            }
-            "XTRIM" => {
-                let cmd = streams::parse_xtrim(iter);
-                cmd_handler.handle(Event::AOF(Command::XTRIM(&cmd)));
-            }
-            "PING" => {
-                // PING命令是由Redis master主动发送过来，判断下游节点是否活跃，不需要处理
-            }
-            _ => {
-                let mut args = Vec::new();
-                while let Some(arg) = iter.next() {
-                    args.push(arg.clone());
-                }
-                let cmd = RawCommand { name: cmd_name, args };
-                cmd_handler.handle(Event::AOF(Command::Other(cmd)))
-            }
-        };
+    if let Some(cmd) = parse_command(&data) {
+        cmd_handler.handle(Event::AOF(cmd.as_command()));
     }
 }
+
+/// 将事务中的命令解析为`Event::Transaction`交给`cmd_handler`
+pub(crate) fn parse_transaction(commands: &[Vec<Vec<u8>>], cmd_handler: &mut dyn EventHandler) {
+    let parsed: Vec<ParsedCommand> = commands.iter().filter_map(|data| parse_command(data)).collect();
+    cmd_handler.handle(Event::Transaction(parsed.iter().map(|cmd| cmd.as_command()).collect()));
+}
+
+/// 缓冲`MULTI`与`EXEC`之间的命令，直到`EXEC`之后再一并处理
+#[derive(Default)]
+pub(crate) struct TransactionBuffer {
+    commands: Option<Vec<Vec<Vec<u8>>>>,
+    size: i64,
+}
+
+pub(crate) enum Buffered {
+    /// 不在事务中的命令，及其大小
+    Command(Vec<Vec<u8>>, i64),
+    /// 完整的事务(不含`MULTI`及`EXEC`)，及整个事务的大小
+    Transaction(Vec<Vec<Vec<u8>>>, i64),
+    /// 未等到`EXEC`又收到了`MULTI`，之前缓冲的命令需逐条处理，及其大小(含之前的`MULTI`)
+    Flushed(Vec<Vec<Vec<u8>>>, i64),
+    /// 命令已被缓冲
+    Pending,
+}
+
+impl TransactionBuffer {
+    /// 放入一条命令，`size`为命令在复制流中所占的字节数
+    pub(crate) fn push(&mut self, data: Vec<Vec<u8>>, size: i64) -> Buffered {
+        let name = data.first().map(|name| name.to_ascii_uppercase());
+        match (name.as_deref(), self.commands.as_mut()) {
+            (Some(b"MULTI"), Some(_)) => {
+                let flushed = self.commands.replace(Vec::new()).unwrap();
+                let flushed_size = std::mem::replace(&mut self.size, size);
+                Buffered::Flushed(flushed, flushed_size)
+            }
+            (Some(b"MULTI"), None) => {
+                self.commands = Some(Vec::new());
+                self.size = size;
+                Buffered::Pending
+            }
+            (Some(b"EXEC"), Some(_)) => {
+                let size = self.size + size;
+                self.size = 0;
+                Buffered::Transaction(self.commands.take().unwrap(), size)
+            }
+            (_, Some(commands)) => {
+                commands.push(data);
+                self.size += size;
+                Buffered::Pending
+            }
+            (_, None) => Buffered::Command(data, size),
+        }
+    }
+
+    /// 不合并事务时使用，只记录是否处于`MULTI`与`EXEC`之间，不缓冲命令
+    pub(crate) fn track(&mut self, data: &[Vec<u8>]) {
+        match data.first().map(|name| name.to_ascii_uppercase()).as_deref() {
+            Some(b"MULTI") => self.commands = Some(Vec::new()),
+            Some(b"EXEC") => self.commands = None,
+            _ => {}
+        }
+    }
+
+    /// 是否处于`MULTI`与`EXEC`之间
+    pub(crate) fn is_open(&self) -> bool {
+        self.commands.is_some()
+    }
+
+    /// 已缓冲但尚未处理的字节数
+    pub(crate) fn pending_size(&self) -> i64 {
+        self.size
+    }
+}
+
+/// 将命令及其参数解析为对应的命令结构体，不需要处理的命令返回None
+///
+/// 未知的命令以及参数不完整、无法解析的命令作为[`ParsedCommand::Other`]原样返回，不会panic
+pub(crate) fn parse_command(data: &[Vec<u8>]) -> Option<ParsedCommand<'_>> {
+    let mut iter = data.iter();
+    let cmd_name = iter.next()?;
+    let cmd_name = String::from_utf8_lossy(cmd_name).to_uppercase();
+    let parsed = match cmd_name.as_str() {
+        "APPEND" => strings::parse_append(iter).map(ParsedCommand::APPEND),
+        "BITFIELD" => strings::parse_bitfield(iter).map(ParsedCommand::BITFIELD),
+        "BITOP" => strings::parse_bitop(iter).map(ParsedCommand::BITOP),
+        "BRPOPLPUSH" => lists::parse_brpoplpush(iter).map(ParsedCommand::BRPOPLPUSH),
+        "DEL" => keys::parse_del(iter).map(ParsedCommand::DEL),
+        "DECR" => strings::parse_decr(iter).map(ParsedCommand::DECR),
+        "DECRBY" => strings::parse_decrby(iter).map(ParsedCommand::DECRBY),
+        "EVAL" => scripting::parse_eval(iter).map(ParsedCommand::EVAL),
+        "EVALSHA" => scripting::parse_evalsha(iter).map(ParsedCommand::EVALSHA),
+        "EXPIRE" => keys::parse_expire(iter).map(ParsedCommand::EXPIRE),
+        "EXPIREAT" => keys::parse_expireat(iter).map(ParsedCommand::EXPIREAT),
+        "EXEC" => Some(ParsedCommand::EXEC),
+        "FLUSHALL" => server::parse_flushall(iter).map(ParsedCommand::FLUSHALL),
+        "FLUSHDB" => server::parse_flushdb(iter).map(ParsedCommand::FLUSHDB),
+        "GETSET" => strings::parse_getset(iter).map(ParsedCommand::GETSET),
+        "HDEL" => hashes::parse_hdel(iter).map(ParsedCommand::HDEL),
+        "HINCRBY" => hashes::parse_hincrby(iter).map(ParsedCommand::HINCRBY),
+        "HMSET" => hashes::parse_hmset(iter).map(ParsedCommand::HMSET),
+        "HSET" => hashes::parse_hset(iter).map(ParsedCommand::HSET),
+        "HSETNX" => hashes::parse_hsetnx(iter).map(ParsedCommand::HSETNX),
+        "INCR" => strings::parse_incr(iter).map(ParsedCommand::INCR),
+        "INCRBY" => strings::parse_incrby(iter).map(ParsedCommand::INCRBY),
+        "LINSERT" => lists::parse_linsert(iter).map(ParsedCommand::LINSERT),
+        "LPOP" => lists::parse_lpop(iter).map(ParsedCommand::LPOP),
+        "LPUSH" => lists::parse_lpush(iter).map(ParsedCommand::LPUSH),
+        "LPUSHX" => lists::parse_lpushx(iter).map(ParsedCommand::LPUSHX),
+        "LREM" => lists::parse_lrem(iter).map(ParsedCommand::LREM),
+        "LSET" => lists::parse_lset(iter).map(ParsedCommand::LSET),
+        "LTRIM" => lists::parse_ltrim(iter).map(ParsedCommand::LTRIM),
+        "RENAME" => keys::parse_rename(iter).map(ParsedCommand::RENAME),
+        "RENAMENX" => keys::parse_renamenx(iter).map(ParsedCommand::RENAMENX),
+        "RESTORE" => keys::parse_restore(iter).map(ParsedCommand::RESTORE),
+        "RPOP" => lists::parse_rpop(iter).map(ParsedCommand::RPOP),
+        "RPOPLPUSH" => lists::parse_rpoplpush(iter).map(ParsedCommand::RPOPLPUSH),
+        "RPUSH" => lists::parse_rpush(iter).map(ParsedCommand::RPUSH),
+        "RPUSHX" => lists::parse_rpushx(iter).map(ParsedCommand::RPUSHX),
+        "SADD" => sets::parse_sadd(iter).map(ParsedCommand::SADD),
+        "SCRIPT" => {
+            let cmd = iter.next().map(|cmd| String::from_utf8_lossy(cmd).to_uppercase());
+            match cmd.as_deref() {
+                Some("LOAD") => scripting::parse_script_load(iter).map(ParsedCommand::SCRIPTLOAD),
+                Some("FLUSH") => Some(ParsedCommand::SCRIPTFLUSH),
+                // 其他子命令不会改变数据，不需要处理
+                Some(_) => return None,
+                None => None,
+            }
+        }
+        "SDIFFSTORE" => sets::parse_sdiffstore(iter).map(ParsedCommand::SDIFFSTORE),
+        "SMOVE" => sets::parse_smove(iter).map(ParsedCommand::SMOVE),
+        "SET" => strings::parse_set(iter).map(ParsedCommand::SET),
+        "SELECT" => connection::parse_select(iter).map(ParsedCommand::SELECT),
+        "SORT" => keys::parse_sort(iter).map(ParsedCommand::SORT),
+        "SREM" => sets::parse_srem(iter).map(ParsedCommand::SREM),
+        "SUNIONSTORE" => sets::parse_sunionstore(iter).map(ParsedCommand::SUNIONSTORE),
+        "SWAPDB" => connection::parse_swapdb(iter).map(ParsedCommand::SWAPDB),
+        "UNLINK" => keys::parse_unlink(iter).map(ParsedCommand::UNLINK),
+        "MOVE" => keys::parse_move(iter).map(ParsedCommand::MOVE),
+        "MSET" => strings::parse_mset(iter).map(ParsedCommand::MSET),
+        "MSETNX" => strings::parse_msetnx(iter).map(ParsedCommand::MSETNX),
+        "MULTI" => Some(ParsedCommand::MULTI),
+        "PFADD" => hyperloglog::parse_pfadd(iter).map(ParsedCommand::PFADD),
+        "PFCOUNT" => hyperloglog::parse_pfcount(iter).map(ParsedCommand::PFCOUNT),
+        "PFMERGE" => hyperloglog::parse_pfmerge(iter).map(ParsedCommand::PFMERGE),
+        "SETEX" => strings::parse_setex(iter).map(ParsedCommand::SETEX),
+        "SETNX" => strings::parse_setnx(iter).map(ParsedCommand::SETNX),
+        "PSETEX" => strings::parse_psetex(iter).map(ParsedCommand::PSETEX),
+        "PUBLISH" => pub_sub::parse_publish(iter).map(ParsedCommand::PUBLISH),
+        "PEXPIRE" => keys::parse_pexpire(iter).map(ParsedCommand::PEXPIRE),
+        "PEXPIREAT" => keys::parse_pexpireat(iter).map(ParsedCommand::PEXPIREAT),
+        "PERSIST" => keys::parse_persist(iter).map(ParsedCommand::PERSIST),
+        "SETRANGE" => strings::parse_setrange(iter).map(ParsedCommand::SETRANGE),
+        "SETBIT" => strings::parse_setbit(iter).map(ParsedCommand::SETBIT),
+        "SINTERSTORE" => sets::parse_sinterstore(iter).map(ParsedCommand::SINTERSTORE),
+        "ZADD" => sorted_sets::parse_zadd(iter).map(ParsedCommand::ZADD),
+        "ZINCRBY" => sorted_sets::parse_zincrby(iter).map(ParsedCommand::ZINCRBY),
+        "ZINTERSTORE" => sorted_sets::parse_zinterstore(iter).map(ParsedCommand::ZINTERSTORE),
+        "ZPOPMAX" => sorted_sets::parse_zpopmax(iter).map(ParsedCommand::ZPOPMAX),
+        "ZPOPMIN" => sorted_sets::parse_zpopmin(iter).map(ParsedCommand::ZPOPMIN),
+        "ZREM" => sorted_sets::parse_zrem(iter).map(ParsedCommand::ZREM),
+        "ZREMRANGEBYLEX" => sorted_sets::parse_zremrangebylex(iter).map(ParsedCommand::ZREMRANGEBYLEX),
+        "ZREMRANGEBYRANK" => sorted_sets::parse_zremrangebyrank(iter).map(ParsedCommand::ZREMRANGEBYRANK),
+        "ZREMRANGEBYSCORE" => sorted_sets::parse_zremrangebyscore(iter).map(ParsedCommand::ZREMRANGEBYSCORE),
+        "ZUNIONSTORE" => sorted_sets::parse_zunionstore(iter).map(ParsedCommand::ZUNIONSTORE),
+        "XACK" => streams::parse_xack(iter).map(ParsedCommand::XACK),
+        "XADD" => streams::parse_xadd(iter).map(ParsedCommand::XADD),
+        "XCLAIM" => streams::parse_xclaim(iter).map(ParsedCommand::XCLAIM),
+        "XDEL" => streams::parse_xdel(iter).map(ParsedCommand::XDEL),
+        "XGROUP" => streams::parse_xgroup(iter).map(ParsedCommand::XGROUP),
+        "XTRIM" => streams::parse_xtrim(iter).map(ParsedCommand::XTRIM),
+        "PING" => {
+            // PING命令是由Redis master主动发送过来，判断下游节点是否活跃，不需要处理
+            return None;
+        }
+        _ => None,
+    };
+    Some(parsed.unwrap_or_else(|| {
+        let args = data[1..].to_vec();
+        ParsedCommand::Other(RawCommand { name: cmd_name, args })
+    }))
+}
@@ -14,8 +14,8 @@ pub struct PUBLISH<'a> {
     pub message: &'a [u8],
 }
 
-pub(crate) fn parse_publish(mut iter: Iter<Vec<u8>>) -> PUBLISH {
-    let channel = iter.next().unwrap();
-    let message = iter.next().unwrap();
-    PUBLISH { channel, message }
+pub(crate) fn parse_publish(mut iter: Iter<Vec<u8>>) -> Option<PUBLISH> {
+    let channel = iter.next()?;
+    let message = iter.next()?;
+    Some(PUBLISH { channel, message })
 }
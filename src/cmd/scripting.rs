@@ -16,25 +16,25 @@ pub struct EVAL<'a> {
     pub args: Vec<&'a [u8]>,
 }
 
-pub(crate) fn parse_eval(mut iter: Iter<Vec<u8>>) -> EVAL {
-    let script = iter.next().unwrap();
-    let num_keys = iter.next().unwrap();
-    let num_keys = String::from_utf8_lossy(num_keys).parse::<i32>().unwrap();
+pub(crate) fn parse_eval(mut iter: Iter<Vec<u8>>) -> Option<EVAL> {
+    let script = iter.next()?;
+    let num_keys = iter.next()?;
+    let num_keys = String::from_utf8_lossy(num_keys).parse::<i32>().ok()?;
     let mut keys = Vec::with_capacity(num_keys as usize);
     for _ in 0..num_keys {
-        let key = iter.next().unwrap();
+        let key = iter.next()?;
         keys.push(key.as_slice());
     }
     let mut args = Vec::new();
     while let Some(arg) = iter.next() {
         args.push(arg.as_slice());
     }
-    EVAL {
+    Some(EVAL {
         script,
         num_keys,
         keys,
         args,
-    }
+    })
 }
 
 #[derive(Debug)]
@@ -45,25 +45,25 @@ pub struct EVALSHA<'a> {
     pub args: Vec<&'a [u8]>,
 }
 
-pub(crate) fn parse_evalsha(mut iter: Iter<Vec<u8>>) -> EVALSHA {
-    let sha1 = iter.next().unwrap();
-    let num_keys = iter.next().unwrap();
-    let num_keys = String::from_utf8_lossy(num_keys).parse::<i32>().unwrap();
+pub(crate) fn parse_evalsha(mut iter: Iter<Vec<u8>>) -> Option<EVALSHA> {
+    let sha1 = iter.next()?;
+    let num_keys = iter.next()?;
+    let num_keys = String::from_utf8_lossy(num_keys).parse::<i32>().ok()?;
     let mut keys = Vec::with_capacity(num_keys as usize);
     for _ in 0..num_keys {
-        let key = iter.next().unwrap();
+        let key = iter.next()?;
         keys.push(key.as_slice());
     }
     let mut args = Vec::new();
     while let Some(arg) = iter.next() {
         args.push(arg.as_slice());
     }
-    EVALSHA {
+    Some(EVALSHA {
         sha1,
         num_keys,
         keys,
         args,
-    }
+    })
 }
 
 #[derive(Debug)]
@@ -71,7 +71,7 @@ pub struct SCRIPTLOAD<'a> {
     pub script: &'a [u8],
 }
 
-pub(crate) fn parse_script_load(mut iter: Iter<Vec<u8>>) -> SCRIPTLOAD {
-    let script = iter.next().unwrap();
-    SCRIPTLOAD { script }
+pub(crate) fn parse_script_load(mut iter: Iter<Vec<u8>>) -> Option<SCRIPTLOAD> {
+    let script = iter.next()?;
+    Some(SCRIPTLOAD { script })
 }
@@ -13,17 +13,17 @@ pub struct FLUSHDB {
     pub _async: Option<bool>,
 }
 
-pub(crate) fn parse_flushdb(mut iter: Iter<Vec<u8>>) -> FLUSHDB {
+pub(crate) fn parse_flushdb(mut iter: Iter<Vec<u8>>) -> Option<FLUSHDB> {
     let mut _async = None;
     if let Some(next_arg) = iter.next() {
         let arg_upper = String::from_utf8_lossy(next_arg).to_uppercase();
         if &arg_upper == "ASYNC" {
             _async = Some(true);
         } else {
-            panic!("Invalid argument")
+            return None;
         }
     }
-    FLUSHDB { _async }
+    Some(FLUSHDB { _async })
 }
 
 #[derive(Debug)]
@@ -31,15 +31,15 @@ pub struct FLUSHALL {
     pub _async: Option<bool>,
 }
 
-pub(crate) fn parse_flushall(mut iter: Iter<Vec<u8>>) -> FLUSHALL {
+pub(crate) fn parse_flushall(mut iter: Iter<Vec<u8>>) -> Option<FLUSHALL> {
     let mut _async = None;
     if let Some(next_arg) = iter.next() {
         let arg_upper = String::from_utf8_lossy(next_arg).to_uppercase();
         if &arg_upper == "ASYNC" {
             _async = Some(true);
         } else {
-            panic!("Invalid argument")
+            return None;
         }
     }
-    FLUSHALL { _async }
+    Some(FLUSHALL { _async })
 }
@@ -14,13 +14,13 @@ pub struct SINTERSTORE<'a> {
     pub keys: Vec<&'a [u8]>,
 }
 
-pub(crate) fn parse_sinterstore(mut iter: Iter<Vec<u8>>) -> SINTERSTORE {
-    let destination = iter.next().unwrap();
+pub(crate) fn parse_sinterstore(mut iter: Iter<Vec<u8>>) -> Option<SINTERSTORE> {
+    let destination = iter.next()?;
     let mut keys = Vec::new();
     for next_arg in iter {
         keys.push(next_arg.as_slice());
     }
-    SINTERSTORE { destination, keys }
+    Some(SINTERSTORE { destination, keys })
 }
 
 #[derive(Debug)]
@@ -29,13 +29,13 @@ pub struct SADD<'a> {
     pub members: Vec<&'a [u8]>,
 }
 
-pub(crate) fn parse_sadd(mut iter: Iter<Vec<u8>>) -> SADD {
-    let key = iter.next().unwrap();
+pub(crate) fn parse_sadd(mut iter: Iter<Vec<u8>>) -> Option<SADD> {
+    let key = iter.next()?;
     let mut members = Vec::new();
     while let Some(member) = iter.next() {
         members.push(member.as_slice());
     }
-    SADD { key, members }
+    Some(SADD { key, members })
 }
 
 #[derive(Debug)]
@@ -44,13 +44,13 @@ pub struct SDIFFSTORE<'a> {
     pub keys: Vec<&'a [u8]>,
 }
 
-pub(crate) fn parse_sdiffstore(mut iter: Iter<Vec<u8>>) -> SDIFFSTORE {
-    let destination = iter.next().unwrap();
+pub(crate) fn parse_sdiffstore(mut iter: Iter<Vec<u8>>) -> Option<SDIFFSTORE> {
+    let destination = iter.next()?;
     let mut keys = Vec::new();
     while let Some(key) = iter.next() {
         keys.push(key.as_slice());
     }
-    SDIFFSTORE { destination, keys }
+    Some(SDIFFSTORE { destination, keys })
 }
 
 #[derive(Debug)]
@@ -60,15 +60,15 @@ pub struct SMOVE<'a> {
     pub member: &'a [u8],
 }
 
-pub(crate) fn parse_smove(mut iter: Iter<Vec<u8>>) -> SMOVE {
-    let source = iter.next().unwrap();
-    let destination = iter.next().unwrap();
-    let member = iter.next().unwrap();
-    SMOVE {
+pub(crate) fn parse_smove(mut iter: Iter<Vec<u8>>) -> Option<SMOVE> {
+    let source = iter.next()?;
+    let destination = iter.next()?;
+    let member = iter.next()?;
+    Some(SMOVE {
         source,
         destination,
         member,
-    }
+    })
 }
 
 #[derive(Debug)]
@@ -77,13 +77,13 @@ pub struct SREM<'a> {
     pub members: Vec<&'a [u8]>,
 }
 
-pub(crate) fn parse_srem(mut iter: Iter<Vec<u8>>) -> SREM {
-    let key = iter.next().unwrap();
+pub(crate) fn parse_srem(mut iter: Iter<Vec<u8>>) -> Option<SREM> {
+    let key = iter.next()?;
     let mut members = Vec::new();
     while let Some(member) = iter.next() {
         members.push(member.as_slice());
     }
-    SREM { key, members }
+    Some(SREM { key, members })
 }
 
 #[derive(Debug)]
@@ -92,11 +92,11 @@ pub struct SUNIONSTORE<'a> {
     pub keys: Vec<&'a [u8]>,
 }
 
-pub(crate) fn parse_sunionstore(mut iter: Iter<Vec<u8>>) -> SUNIONSTORE {
-    let destination = iter.next().unwrap();
+pub(crate) fn parse_sunionstore(mut iter: Iter<Vec<u8>>) -> Option<SUNIONSTORE> {
+    let destination = iter.next()?;
     let mut keys = Vec::new();
     for next_arg in iter {
         keys.push(next_arg.as_slice());
     }
-    SUNIONSTORE { destination, keys }
+    Some(SUNIONSTORE { destination, keys })
 }
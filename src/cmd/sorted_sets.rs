@@ -29,8 +29,8 @@ pub struct Item<'a> {
     pub member: &'a [u8],
 }
 
-pub(crate) fn parse_zadd(mut iter: Iter<Vec<u8>>) -> ZADD {
-    let key = iter.next().unwrap();
+pub(crate) fn parse_zadd(mut iter: Iter<Vec<u8>>) -> Option<ZADD> {
+    let key = iter.next()?;
     let mut exist_type = None;
     let mut ch = None;
     let mut incr = None;
@@ -47,20 +47,20 @@ pub(crate) fn parse_zadd(mut iter: Iter<Vec<u8>>) -> ZADD {
             incr = Some(true);
         } else {
             // score在前，element在后
-            let member = iter.next().unwrap();
+            let member = iter.next()?;
             items.push(Item {
                 score: next_arg,
                 member,
             });
         }
     }
-    ZADD {
+    Some(ZADD {
         key,
         exist_type,
         ch,
         incr,
         items,
-    }
+    })
 }
 
 #[derive(Debug)]
@@ -70,11 +70,11 @@ pub struct ZINCRBY<'a> {
     pub member: &'a [u8],
 }
 
-pub(crate) fn parse_zincrby(mut iter: Iter<Vec<u8>>) -> ZINCRBY {
-    let key = iter.next().unwrap();
-    let increment = iter.next().unwrap();
-    let member = iter.next().unwrap();
-    ZINCRBY { key, increment, member }
+pub(crate) fn parse_zincrby(mut iter: Iter<Vec<u8>>) -> Option<ZINCRBY> {
+    let key = iter.next()?;
+    let increment = iter.next()?;
+    let member = iter.next()?;
+    Some(ZINCRBY { key, increment, member })
 }
 
 #[derive(Debug)]
@@ -93,13 +93,13 @@ pub enum AGGREGATE {
     MAX,
 }
 
-pub(crate) fn parse_zinterstore(mut iter: Iter<Vec<u8>>) -> ZINTERSTORE {
-    let destination = iter.next().unwrap();
-    let num_keys = String::from_utf8_lossy(iter.next().unwrap());
-    let num_keys = num_keys.parse::<i32>().unwrap();
+pub(crate) fn parse_zinterstore(mut iter: Iter<Vec<u8>>) -> Option<ZINTERSTORE> {
+    let destination = iter.next()?;
+    let num_keys = String::from_utf8_lossy(iter.next()?);
+    let num_keys = num_keys.parse::<i32>().ok()?;
     let mut keys = Vec::new();
     for _ in 0..num_keys {
-        let next_key = iter.next().unwrap();
+        let next_key = iter.next()?;
         keys.push(next_key.as_slice());
     }
     let mut _weights = Vec::new();
@@ -124,13 +124,13 @@ pub(crate) fn parse_zinterstore(mut iter: Iter<Vec<u8>>) -> ZINTERSTORE {
     } else {
         weights = Some(_weights);
     }
-    ZINTERSTORE {
+    Some(ZINTERSTORE {
         destination,
         num_keys,
         keys,
         weights,
         aggregate,
-    }
+    })
 }
 
 #[derive(Debug)]
@@ -139,13 +139,13 @@ pub struct ZPOPMAX<'a> {
     pub count: Option<&'a [u8]>,
 }
 
-pub(crate) fn parse_zpopmax(mut iter: Iter<Vec<u8>>) -> ZPOPMAX {
-    let key = iter.next().unwrap();
+pub(crate) fn parse_zpopmax(mut iter: Iter<Vec<u8>>) -> Option<ZPOPMAX> {
+    let key = iter.next()?;
     let mut count = None;
     if let Some(next_arg) = iter.next() {
         count = Some(next_arg.as_slice());
     }
-    ZPOPMAX { key, count }
+    Some(ZPOPMAX { key, count })
 }
 
 #[derive(Debug)]
@@ -154,13 +154,13 @@ pub struct ZPOPMIN<'a> {
     pub count: Option<&'a [u8]>,
 }
 
-pub(crate) fn parse_zpopmin(mut iter: Iter<Vec<u8>>) -> ZPOPMIN {
-    let key = iter.next().unwrap();
+pub(crate) fn parse_zpopmin(mut iter: Iter<Vec<u8>>) -> Option<ZPOPMIN> {
+    let key = iter.next()?;
     let mut count = None;
     if let Some(next_arg) = iter.next() {
         count = Some(next_arg.as_slice());
     }
-    ZPOPMIN { key, count }
+    Some(ZPOPMIN { key, count })
 }
 
 #[derive(Debug)]
@@ -169,13 +169,13 @@ pub struct ZREM<'a> {
     pub members: Vec<&'a [u8]>,
 }
 
-pub(crate) fn parse_zrem(mut iter: Iter<Vec<u8>>) -> ZREM {
-    let key = iter.next().unwrap();
+pub(crate) fn parse_zrem(mut iter: Iter<Vec<u8>>) -> Option<ZREM> {
+    let key = iter.next()?;
     let mut members = Vec::new();
     while let Some(next_arg) = iter.next() {
         members.push(next_arg.as_slice());
     }
-    ZREM { key, members }
+    Some(ZREM { key, members })
 }
 
 #[derive(Debug)]
@@ -185,11 +185,11 @@ pub struct ZREMRANGEBYLEX<'a> {
     pub max: &'a [u8],
 }
 
-pub(crate) fn parse_zremrangebylex(mut iter: Iter<Vec<u8>>) -> ZREMRANGEBYLEX {
-    let key = iter.next().unwrap();
-    let min = iter.next().unwrap();
-    let max = iter.next().unwrap();
-    ZREMRANGEBYLEX { key, min, max }
+pub(crate) fn parse_zremrangebylex(mut iter: Iter<Vec<u8>>) -> Option<ZREMRANGEBYLEX> {
+    let key = iter.next()?;
+    let min = iter.next()?;
+    let max = iter.next()?;
+    Some(ZREMRANGEBYLEX { key, min, max })
 }
 
 #[derive(Debug)]
@@ -199,11 +199,11 @@ pub struct ZREMRANGEBYRANK<'a> {
     pub stop: &'a [u8],
 }
 
-pub(crate) fn parse_zremrangebyrank(mut iter: Iter<Vec<u8>>) -> ZREMRANGEBYRANK {
-    let key = iter.next().unwrap();
-    let start = iter.next().unwrap();
-    let stop = iter.next().unwrap();
-    ZREMRANGEBYRANK { key, start, stop }
+pub(crate) fn parse_zremrangebyrank(mut iter: Iter<Vec<u8>>) -> Option<ZREMRANGEBYRANK> {
+    let key = iter.next()?;
+    let start = iter.next()?;
+    let stop = iter.next()?;
+    Some(ZREMRANGEBYRANK { key, start, stop })
 }
 
 #[derive(Debug)]
@@ -213,11 +213,11 @@ pub struct ZREMRANGEBYSCORE<'a> {
     pub max: &'a [u8],
 }
 
-pub(crate) fn parse_zremrangebyscore(mut iter: Iter<Vec<u8>>) -> ZREMRANGEBYSCORE {
-    let key = iter.next().unwrap();
-    let min = iter.next().unwrap();
-    let max = iter.next().unwrap();
-    ZREMRANGEBYSCORE { key, min, max }
+pub(crate) fn parse_zremrangebyscore(mut iter: Iter<Vec<u8>>) -> Option<ZREMRANGEBYSCORE> {
+    let key = iter.next()?;
+    let min = iter.next()?;
+    let max = iter.next()?;
+    Some(ZREMRANGEBYSCORE { key, min, max })
 }
 
 #[derive(Debug)]
@@ -229,13 +229,13 @@ pub struct ZUNIONSTORE<'a> {
     pub aggregate: Option<AGGREGATE>,
 }
 
-pub(crate) fn parse_zunionstore(mut iter: Iter<Vec<u8>>) -> ZUNIONSTORE {
-    let destination = iter.next().unwrap();
-    let num_keys = String::from_utf8_lossy(iter.next().unwrap());
-    let num_keys = num_keys.parse::<i32>().unwrap();
+pub(crate) fn parse_zunionstore(mut iter: Iter<Vec<u8>>) -> Option<ZUNIONSTORE> {
+    let destination = iter.next()?;
+    let num_keys = String::from_utf8_lossy(iter.next()?);
+    let num_keys = num_keys.parse::<i32>().ok()?;
     let mut keys = Vec::new();
     for _ in 0..num_keys {
-        let next_key = iter.next().unwrap();
+        let next_key = iter.next()?;
         keys.push(next_key.as_slice());
     }
     let mut _weights = Vec::new();
@@ -260,11 +260,11 @@ pub(crate) fn parse_zunionstore(mut iter: Iter<Vec<u8>>) -> ZUNIONSTORE {
     } else {
         weights = Some(_weights);
     }
-    ZUNIONSTORE {
+    Some(ZUNIONSTORE {
         destination,
         num_keys,
         keys,
         weights,
         aggregate,
-    }
+    })
 }
@@ -17,14 +17,14 @@ pub struct XACK<'a> {
     pub ids: Vec<&'a Vec<u8>>,
 }
 
-pub(crate) fn parse_xack(mut iter: Iter<Vec<u8>>) -> XACK {
-    let key = iter.next().unwrap();
-    let group = iter.next().unwrap();
+pub(crate) fn parse_xack(mut iter: Iter<Vec<u8>>) -> Option<XACK> {
+    let key = iter.next()?;
+    let group = iter.next()?;
     let mut ids = Vec::new();
     for id in iter {
         ids.push(id);
     }
-    XACK { key, group, ids }
+    Some(XACK { key, group, ids })
 }
 
 #[derive(Debug)]
@@ -34,9 +34,9 @@ pub struct XADD<'a> {
     pub fields: Vec<Field<'a>>,
 }
 
-pub(crate) fn parse_xadd(mut iter: Iter<Vec<u8>>) -> XADD {
-    let key = iter.next().unwrap();
-    let id = iter.next().unwrap();
+pub(crate) fn parse_xadd(mut iter: Iter<Vec<u8>>) -> Option<XADD> {
+    let key = iter.next()?;
+    let id = iter.next()?;
     let mut fields = Vec::new();
     loop {
         if let Some(field) = iter.next() {
@@ -44,13 +44,13 @@ pub(crate) fn parse_xadd(mut iter: Iter<Vec<u8>>) -> XADD {
                 let field = Field { name: field, value };
                 fields.push(field);
             } else {
-                panic!("XADD缺失field value");
+                return None;
             }
         } else {
             break;
         }
     }
-    XADD { key, id, fields }
+    Some(XADD { key, id, fields })
 }
 
 #[derive(Debug)]
@@ -67,13 +67,13 @@ pub struct XCLAIM<'a> {
     pub just_id: Option<bool>,
 }
 
-pub(crate) fn parse_xclaim(mut iter: Iter<Vec<u8>>) -> XCLAIM {
-    let key = iter.next().unwrap();
-    let group = iter.next().unwrap();
-    let consumer = iter.next().unwrap();
-    let min_idle_time = iter.next().unwrap();
+pub(crate) fn parse_xclaim(mut iter: Iter<Vec<u8>>) -> Option<XCLAIM> {
+    let key = iter.next()?;
+    let group = iter.next()?;
+    let consumer = iter.next()?;
+    let min_idle_time = iter.next()?;
     let mut ids = Vec::new();
-    let id = iter.next().unwrap();
+    let id = iter.next()?;
     ids.push(id);
     let mut idle = None;
     let mut time = None;
@@ -84,13 +84,13 @@ pub(crate) fn parse_xclaim(mut iter: Iter<Vec<u8>>) -> XCLAIM {
         let arg_string = String::from_utf8_lossy(arg);
         let p_arg = &arg_string.to_uppercase();
         if p_arg == "IDLE" {
-            let _idle = iter.next().unwrap();
+            let _idle = iter.next()?;
             idle = Some(_idle);
         } else if p_arg == "TIME" {
-            let _time = iter.next().unwrap();
+            let _time = iter.next()?;
             time = Some(_time);
         } else if p_arg == "RETRYCOUNT" {
-            let _retry_count = iter.next().unwrap();
+            let _retry_count = iter.next()?;
             retry_count = Some(_retry_count);
         } else if p_arg == "FORCE" {
             force = Some(true);
@@ -100,7 +100,7 @@ pub(crate) fn parse_xclaim(mut iter: Iter<Vec<u8>>) -> XCLAIM {
             ids.push(arg);
         }
     }
-    XCLAIM {
+    Some(XCLAIM {
         key,
         group,
         consumer,
@@ -111,7 +111,7 @@ pub(crate) fn parse_xclaim(mut iter: Iter<Vec<u8>>) -> XCLAIM {
         retry_count,
         force,
         just_id,
-    }
+    })
 }
 
 #[derive(Debug)]
@@ -120,13 +120,13 @@ pub struct XDEL<'a> {
     pub ids: Vec<&'a Vec<u8>>,
 }
 
-pub(crate) fn parse_xdel(mut iter: Iter<Vec<u8>>) -> XDEL {
-    let key = iter.next().unwrap();
+pub(crate) fn parse_xdel(mut iter: Iter<Vec<u8>>) -> Option<XDEL> {
+    let key = iter.next()?;
     let mut ids = Vec::new();
     for id in iter {
         ids.push(id);
     }
-    XDEL { key, ids }
+    Some(XDEL { key, ids })
 }
 
 #[derive(Debug)]
@@ -164,7 +164,7 @@ pub struct DelConsumer<'a> {
     pub consumer_name: &'a [u8],
 }
 
-pub(crate) fn parse_xgroup(mut iter: Iter<Vec<u8>>) -> XGROUP {
+pub(crate) fn parse_xgroup(mut iter: Iter<Vec<u8>>) -> Option<XGROUP> {
     let mut create = None;
     let mut set_id = None;
     let mut destroy = None;
@@ -173,23 +173,23 @@ pub(crate) fn parse_xgroup(mut iter: Iter<Vec<u8>>) -> XGROUP {
         let arg_string = String::from_utf8_lossy(arg);
         let p_arg = &arg_string.to_uppercase();
         if p_arg == "CREATE" {
-            let key = iter.next().unwrap();
-            let group_name = iter.next().unwrap();
-            let id = iter.next().unwrap();
+            let key = iter.next()?;
+            let group_name = iter.next()?;
+            let id = iter.next()?;
             create = Some(Create { key, group_name, id })
         } else if p_arg == "SETID" {
-            let key = iter.next().unwrap();
-            let group_name = iter.next().unwrap();
-            let id = iter.next().unwrap();
+            let key = iter.next()?;
+            let group_name = iter.next()?;
+            let id = iter.next()?;
             set_id = Some(SetID { key, group_name, id })
         } else if p_arg == "DESTROY" {
-            let key = iter.next().unwrap();
-            let group_name = iter.next().unwrap();
+            let key = iter.next()?;
+            let group_name = iter.next()?;
             destroy = Some(Destroy { key, group_name })
         } else if p_arg == "DELCONSUMER" {
-            let key = iter.next().unwrap();
-            let group_name = iter.next().unwrap();
-            let consumer_name = iter.next().unwrap();
+            let key = iter.next()?;
+            let group_name = iter.next()?;
+            let consumer_name = iter.next()?;
             del_consumer = Some(DelConsumer {
                 key,
                 group_name,
@@ -197,12 +197,12 @@ pub(crate) fn parse_xgroup(mut iter: Iter<Vec<u8>>) -> XGROUP {
             })
         }
     }
-    XGROUP {
+    Some(XGROUP {
         create,
         set_id,
         destroy,
         del_consumer,
-    }
+    })
 }
 
 #[derive(Debug)]
@@ -212,24 +212,24 @@ pub struct XTRIM<'a> {
     pub count: u64,
 }
 
-pub(crate) fn parse_xtrim(mut iter: Iter<Vec<u8>>) -> XTRIM {
-    let key = iter.next().unwrap();
-    iter.next().unwrap();
-    let third = iter.next().unwrap();
+pub(crate) fn parse_xtrim(mut iter: Iter<Vec<u8>>) -> Option<XTRIM> {
+    let key = iter.next()?;
+    iter.next()?;
+    let third = iter.next()?;
     let third = String::from_utf8_lossy(third);
     let approximation;
     let count;
     if "~" == third {
         approximation = true;
-        let arg = String::from_utf8_lossy(iter.next().unwrap());
-        count = arg.parse::<u64>().unwrap();
+        let arg = String::from_utf8_lossy(iter.next()?);
+        count = arg.parse::<u64>().ok()?;
     } else {
         approximation = false;
-        count = third.parse::<u64>().unwrap();
+        count = third.parse::<u64>().ok()?;
     }
-    XTRIM {
+    Some(XTRIM {
         key,
         approximation,
         count,
-    }
+    })
 }
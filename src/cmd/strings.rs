@@ -16,10 +16,10 @@ pub struct APPEND<'a> {
     pub value: &'a [u8],
 }
 
-pub(crate) fn parse_append(mut iter: Iter<Vec<u8>>) -> APPEND {
-    let key = iter.next().unwrap();
-    let value = iter.next().unwrap();
-    APPEND { key, value }
+pub(crate) fn parse_append(mut iter: Iter<Vec<u8>>) -> Option<APPEND> {
+    let key = iter.next()?;
+    let value = iter.next()?;
+    Some(APPEND { key, value })
 }
 
 #[derive(Debug)]
@@ -63,33 +63,33 @@ pub enum Overflow {
     FAIL,
 }
 
-pub(crate) fn parse_bitfield(mut iter: Iter<Vec<u8>>) -> BITFIELD {
-    let key = iter.next().unwrap();
+pub(crate) fn parse_bitfield(mut iter: Iter<Vec<u8>>) -> Option<BITFIELD> {
+    let key = iter.next()?;
 
     let mut statements = Vec::new();
     let mut overflows = Vec::new();
     while let Some(next_arg) = iter.next() {
         let arg_upper = &String::from_utf8_lossy(next_arg).to_uppercase();
         if arg_upper == "GET" {
-            let _type = iter.next().expect("bitfield 缺失get type");
-            let offset = iter.next().expect("bitfield 缺失get offset");
+            let _type = iter.next()?;
+            let offset = iter.next()?;
             statements.push(Operation::GET(Get { _type, offset }));
         } else if arg_upper == "SET" {
-            let _type = iter.next().unwrap();
-            let offset = iter.next().expect("bitfield 缺失SET offset");
-            let value = iter.next().expect("bitfield 缺失SET offset");
+            let _type = iter.next()?;
+            let offset = iter.next()?;
+            let value = iter.next()?;
             statements.push(Operation::SET(Set { _type, offset, value }));
         } else if arg_upper == "INCRBY" {
-            let _type = iter.next().expect("bitfield 缺失INCR type");
-            let offset = iter.next().expect("bitfield 缺失INCR offset");
-            let increment = iter.next().expect("bitfield 缺失INCR offset");
+            let _type = iter.next()?;
+            let offset = iter.next()?;
+            let increment = iter.next()?;
             statements.push(Operation::INCRBY(IncrBy {
                 _type,
                 offset,
                 increment,
             }));
         } else if arg_upper == "OVERFLOW" {
-            let _type = String::from_utf8_lossy(iter.next().expect("bitfield 缺失OVERFLOW type"));
+            let _type = String::from_utf8_lossy(iter.next()?);
             let type_upper = &_type.to_uppercase();
             if type_upper == "FAIL" {
                 overflows.push(Overflow::FAIL);
@@ -113,11 +113,11 @@ pub(crate) fn parse_bitfield(mut iter: Iter<Vec<u8>>) -> BITFIELD {
     } else {
         _overflows = Some(overflows);
     }
-    BITFIELD {
+    Some(BITFIELD {
         key,
         statements: _statements,
         overflows: _overflows,
-    }
+    })
 }
 
 #[derive(Debug)]
@@ -135,9 +135,9 @@ pub enum Op {
     NOT,
 }
 
-pub(crate) fn parse_bitop(mut iter: Iter<Vec<u8>>) -> BITOP {
+pub(crate) fn parse_bitop(mut iter: Iter<Vec<u8>>) -> Option<BITOP> {
     let operation;
-    let op = String::from_utf8_lossy(iter.next().unwrap()).to_uppercase();
+    let op = String::from_utf8_lossy(iter.next()?).to_uppercase();
     if &op == "AND" {
         operation = AND;
     } else if &op == "OR" {
@@ -147,22 +147,22 @@ pub(crate) fn parse_bitop(mut iter: Iter<Vec<u8>>) -> BITOP {
     } else if &op == "NOT" {
         operation = NOT;
     } else {
-        panic!("bitop命令缺失operation")
+        return None;
     }
-    let dest_key = iter.next().unwrap();
+    let dest_key = iter.next()?;
 
     let mut keys = Vec::new();
     while let Some(next_arg) = iter.next() {
         keys.push(next_arg);
     }
     if keys.is_empty() {
-        panic!("bitop命令缺失input key")
+        return None;
     }
-    BITOP {
+    Some(BITOP {
         operation,
         dest_key,
         keys,
-    }
+    })
 }
 
 #[derive(Debug)]
@@ -190,10 +190,10 @@ pub enum ExistType {
     XX,
 }
 
-pub(crate) fn parse_set(mut iter: Iter<Vec<u8>>) -> SET {
-    let key = iter.next().unwrap();
+pub(crate) fn parse_set(mut iter: Iter<Vec<u8>>) -> Option<SET> {
+    let key = iter.next()?;
 
-    let value = iter.next().unwrap();
+    let value = iter.next()?;
 
     let mut expire_time = None;
     let mut expire_type = None;
@@ -222,13 +222,13 @@ pub(crate) fn parse_set(mut iter: Iter<Vec<u8>>) -> SET {
     if expire_type.is_some() && expire_time.is_some() {
         expire = Some((expire_type.unwrap(), expire_time.unwrap()));
     }
-    SET {
+    Some(SET {
         key,
         value,
         exist_type,
         expire,
         keep_ttl,
-    }
+    })
 }
 
 #[derive(Debug)]
@@ -238,11 +238,11 @@ pub struct SETEX<'a> {
     pub value: &'a [u8],
 }
 
-pub(crate) fn parse_setex(mut iter: Iter<Vec<u8>>) -> SETEX {
-    let key = iter.next().unwrap();
-    let seconds = iter.next().unwrap();
-    let value = iter.next().unwrap();
-    SETEX { key, seconds, value }
+pub(crate) fn parse_setex(mut iter: Iter<Vec<u8>>) -> Option<SETEX> {
+    let key = iter.next()?;
+    let seconds = iter.next()?;
+    let value = iter.next()?;
+    Some(SETEX { key, seconds, value })
 }
 
 #[derive(Debug)]
@@ -251,10 +251,10 @@ pub struct SETNX<'a> {
     pub value: &'a [u8],
 }
 
-pub(crate) fn parse_setnx(mut iter: Iter<Vec<u8>>) -> SETNX {
-    let key = iter.next().unwrap();
-    let value = iter.next().unwrap();
-    SETNX { key, value }
+pub(crate) fn parse_setnx(mut iter: Iter<Vec<u8>>) -> Option<SETNX> {
+    let key = iter.next()?;
+    let value = iter.next()?;
+    Some(SETNX { key, value })
 }
 
 #[derive(Debug)]
@@ -264,15 +264,15 @@ pub struct PSETEX<'a> {
     pub value: &'a [u8],
 }
 
-pub(crate) fn parse_psetex(mut iter: Iter<Vec<u8>>) -> PSETEX {
-    let key = iter.next().unwrap();
-    let milliseconds = iter.next().unwrap();
-    let value = iter.next().unwrap();
-    PSETEX {
+pub(crate) fn parse_psetex(mut iter: Iter<Vec<u8>>) -> Option<PSETEX> {
+    let key = iter.next()?;
+    let milliseconds = iter.next()?;
+    let value = iter.next()?;
+    Some(PSETEX {
         key,
         milliseconds,
         value,
-    }
+    })
 }
 
 #[derive(Debug)]
@@ -282,11 +282,11 @@ pub struct SETRANGE<'a> {
     pub value: &'a [u8],
 }
 
-pub(crate) fn parse_setrange(mut iter: Iter<Vec<u8>>) -> SETRANGE {
-    let key = iter.next().unwrap();
-    let offset = iter.next().unwrap();
-    let value = iter.next().unwrap();
-    SETRANGE { key, offset, value }
+pub(crate) fn parse_setrange(mut iter: Iter<Vec<u8>>) -> Option<SETRANGE> {
+    let key = iter.next()?;
+    let offset = iter.next()?;
+    let value = iter.next()?;
+    Some(SETRANGE { key, offset, value })
 }
 
 #[derive(Debug)]
@@ -294,9 +294,9 @@ pub struct DECR<'a> {
     pub key: &'a [u8],
 }
 
-pub(crate) fn parse_decr(mut iter: Iter<Vec<u8>>) -> DECR {
-    let key = iter.next().unwrap();
-    DECR { key }
+pub(crate) fn parse_decr(mut iter: Iter<Vec<u8>>) -> Option<DECR> {
+    let key = iter.next()?;
+    Some(DECR { key })
 }
 
 #[derive(Debug)]
@@ -305,10 +305,10 @@ pub struct DECRBY<'a> {
     pub decrement: &'a [u8],
 }
 
-pub(crate) fn parse_decrby(mut iter: Iter<Vec<u8>>) -> DECRBY {
-    let key = iter.next().unwrap();
-    let decrement = iter.next().unwrap();
-    DECRBY { key, decrement }
+pub(crate) fn parse_decrby(mut iter: Iter<Vec<u8>>) -> Option<DECRBY> {
+    let key = iter.next()?;
+    let decrement = iter.next()?;
+    Some(DECRBY { key, decrement })
 }
 
 #[derive(Debug)]
@@ -316,9 +316,9 @@ pub struct INCR<'a> {
     pub key: &'a [u8],
 }
 
-pub(crate) fn parse_incr(mut iter: Iter<Vec<u8>>) -> INCR {
-    let key = iter.next().unwrap();
-    INCR { key }
+pub(crate) fn parse_incr(mut iter: Iter<Vec<u8>>) -> Option<INCR> {
+    let key = iter.next()?;
+    Some(INCR { key })
 }
 
 #[derive(Debug)]
@@ -327,10 +327,10 @@ pub struct INCRBY<'a> {
     pub increment: &'a [u8],
 }
 
-pub(crate) fn parse_incrby(mut iter: Iter<Vec<u8>>) -> INCRBY {
-    let key = iter.next().unwrap();
-    let increment = iter.next().unwrap();
-    INCRBY { key, increment }
+pub(crate) fn parse_incrby(mut iter: Iter<Vec<u8>>) -> Option<INCRBY> {
+    let key = iter.next()?;
+    let increment = iter.next()?;
+    Some(INCRBY { key, increment })
 }
 
 #[derive(Debug)]
@@ -344,7 +344,7 @@ pub struct MSET<'a> {
     pub key_values: Vec<KeyValue<'a>>,
 }
 
-pub(crate) fn parse_mset(mut iter: Iter<Vec<u8>>) -> MSET {
+pub(crate) fn parse_mset(mut iter: Iter<Vec<u8>>) -> Option<MSET> {
     let mut key_values = Vec::new();
     while let Some(key) = iter.next() {
         if let Some(value) = iter.next() {
@@ -352,9 +352,9 @@ pub(crate) fn parse_mset(mut iter: Iter<Vec<u8>>) -> MSET {
         }
     }
     if key_values.is_empty() {
-        panic!("mset命令缺失key value");
+        return None;
     }
-    MSET { key_values }
+    Some(MSET { key_values })
 }
 
 #[derive(Debug)]
@@ -362,7 +362,7 @@ pub struct MSETNX<'a> {
     pub key_values: Vec<KeyValue<'a>>,
 }
 
-pub(crate) fn parse_msetnx(mut iter: Iter<Vec<u8>>) -> MSETNX {
+pub(crate) fn parse_msetnx(mut iter: Iter<Vec<u8>>) -> Option<MSETNX> {
     let mut key_values = Vec::new();
     while let Some(key) = iter.next() {
         if let Some(value) = iter.next() {
@@ -370,9 +370,9 @@ pub(crate) fn parse_msetnx(mut iter: Iter<Vec<u8>>) -> MSETNX {
         }
     }
     if key_values.is_empty() {
-        panic!("msetnx命令缺失key value");
+        return None;
     }
-    MSETNX { key_values }
+    Some(MSETNX { key_values })
 }
 
 #[derive(Debug)]
@@ -382,11 +382,11 @@ pub struct SETBIT<'a> {
     pub value: &'a [u8],
 }
 
-pub(crate) fn parse_setbit(mut iter: Iter<Vec<u8>>) -> SETBIT {
-    let key = iter.next().unwrap();
-    let offset = iter.next().unwrap();
-    let value = iter.next().unwrap();
-    SETBIT { key, value, offset }
+pub(crate) fn parse_setbit(mut iter: Iter<Vec<u8>>) -> Option<SETBIT> {
+    let key = iter.next()?;
+    let offset = iter.next()?;
+    let value = iter.next()?;
+    Some(SETBIT { key, value, offset })
 }
 
 #[derive(Debug)]
@@ -395,8 +395,8 @@ pub struct GETSET<'a> {
     pub value: &'a [u8],
 }
 
-pub(crate) fn parse_getset(mut iter: Iter<Vec<u8>>) -> GETSET {
-    let key = iter.next().unwrap();
-    let value = iter.next().unwrap();
-    GETSET { key, value }
+pub(crate) fn parse_getset(mut iter: Iter<Vec<u8>>) -> Option<GETSET> {
+    let key = iter.next()?;
+    let value = iter.next()?;
+    Some(GETSET { key, value })
 }
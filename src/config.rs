@@ -3,6 +3,7 @@
 
 [`RedisListener`]: trait.RedisListener.html
 */
+use std::io::{Error, ErrorKind, Result};
 use std::time::Duration;
 
 /// 配置信息结构体定义
@@ -10,12 +11,15 @@ use std::time::Duration;
 pub struct Config {
     /// 是否跳过整个RDB不进行处理，直接进入AOF处理
     pub is_discard_rdb: bool,
-    /// 是否需要处理AOF, 如为false, 处理完RDB后`RedisListener`将中止
+    /// 是否需要处理AOF, 如为false, 处理完RDB后`RedisListener`将中止。
+    /// 为false时会向Redis发送`REPLCONF rdb-only 1`(需Redis 7.0及以上)，Redis不会将`RedisListener`视为一个长期的replica
     pub is_aof: bool,
     /// Redis的地址
     pub host: String,
     /// Redis的端口
     pub port: u16,
+    /// Redis的unix socket路径，设置后将通过unix socket连接，忽略`host`及`port`
+    pub unix_socket_path: Option<String>,
     /// Redis的用户名
     pub username: String,
     /// Redis的密码
@@ -28,18 +32,63 @@ pub struct Config {
     pub read_timeout: Option<Duration>,
     /// Write Timeout
     pub write_timeout: Option<Duration>,
-    #[cfg(feature = "tls")]
+    /// 是否通过`HELLO 3`使用RESP3协议进行握手(需Redis 6.0及以上)，设置了密码时会同时进行认证
+    pub is_resp3: bool,
+    /// 是否将Redis发送的`REPLCONF GETACK`作为`Event::AOF`交给EventHandler处理，
+    /// 无论是否设置，都会立即回复`REPLCONF ACK`
+    pub is_forward_getack: bool,
+    /// 是否将`MULTI`与`EXEC`之间的命令合并为一个`Event::Transaction`，
+    /// 设置后事务中的命令不会逐条交给EventHandler，offset也只在`EXEC`之后才更新
+    pub is_group_transaction: bool,
+    /// 心跳(`REPLCONF ACK`)的间隔，Redis默认的`repl-timeout`为60秒，间隔需小于此值
+    pub heartbeat_interval: Duration,
+    /// 全量同步时，先将RDB完整地写入此目录下的临时文件，再从文件中解析RDB，解析完成后删除该文件。
+    /// 可避免EventHandler处理缓慢导致Redis的client-output-buffer溢出而断开连接。若为None则边接收边解析
+    pub rdb_spool_dir: Option<String>,
+    /// 将全量同步时接收到的RDB原样保存至此路径，可作为备份使用。
+    /// 接收过程中写入`<path>.tmp`，RDB完整接收后才重命名为`path`
+    pub rdb_backup_path: Option<String>,
+    /// `is_aof`为false时，通过`REPLCONF rdb-filter-only`指定RDB中需要包含的内容(需Redis 7.0及以上)。
+    /// 目前Redis仅支持`functions`，即RDB中只包含Function而不包含数据；空字符串则得到空的RDB
+    pub rdb_filter_only: Option<String>,
+    #[cfg(any(feature = "tls", feature = "tls-rustls"))]
     /// 是否启用TLS
     pub is_tls_enabled: bool,
-    #[cfg(feature = "tls")]
+    #[cfg(any(feature = "tls", feature = "tls-rustls"))]
     /// 是否信任无效的证书和域名
     pub is_tls_insecure: bool,
     #[cfg(feature = "tls")]
-    /// 客户端认证所使用的Key
+    /// 客户端认证所使用的Key(PKCS#12格式)，由native-tls使用
     pub identity: Option<String>,
     #[cfg(feature = "tls")]
     /// 解密Key所需的密码
     pub identity_passwd: Option<String>,
+    #[cfg(feature = "tls-rustls")]
+    /// 客户端证书(PEM格式)的路径，需与`tls_key`一同设置
+    pub tls_cert: Option<String>,
+    #[cfg(feature = "tls-rustls")]
+    /// 客户端证书对应私钥(PEM格式)的路径
+    pub tls_key: Option<String>,
+    #[cfg(feature = "tls-rustls")]
+    /// 用于校验Redis证书的CA(PEM格式)的路径，若为None则使用内置的根证书
+    pub tls_ca_cert: Option<String>,
+    #[cfg(feature = "tls-rustls")]
+    /// SNI以及校验证书时所使用的域名，若为None则使用`host`
+    pub tls_server_name: Option<String>,
+    #[cfg(all(feature = "tls", feature = "tls-rustls"))]
+    /// `tls`与`tls-rustls` feature同时开启时所使用的TLS实现，
+    /// 若为None，则设置了`identity`时使用native-tls，否则使用rustls
+    pub tls_backend: Option<TlsBackend>,
+}
+
+/// TLS的实现，仅在`tls`与`tls-rustls` feature同时开启时需要选择
+#[cfg(all(feature = "tls", feature = "tls-rustls"))]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TlsBackend {
+    /// native-tls，使用`identity`、`identity_passwd`
+    Native,
+    /// rustls，使用`tls_cert`、`tls_key`、`tls_ca_cert`、`tls_server_name`
+    Rustls,
 }
 
 impl Clone for Config {
@@ -49,20 +98,546 @@ impl Clone for Config {
             is_aof: self.is_aof,
             host: self.host.clone(),
             port: self.port.clone(),
+            unix_socket_path: self.unix_socket_path.clone(),
             username: self.username.clone(),
             password: self.password.clone(),
             repl_id: self.repl_id.clone(),
             repl_offset: self.repl_offset,
             read_timeout: self.read_timeout,
             write_timeout: self.write_timeout,
-            #[cfg(feature = "tls")]
+            is_resp3: self.is_resp3,
+            is_forward_getack: self.is_forward_getack,
+            is_group_transaction: self.is_group_transaction,
+            heartbeat_interval: self.heartbeat_interval,
+            rdb_spool_dir: self.rdb_spool_dir.clone(),
+            rdb_backup_path: self.rdb_backup_path.clone(),
+            rdb_filter_only: self.rdb_filter_only.clone(),
+            #[cfg(any(feature = "tls", feature = "tls-rustls"))]
             is_tls_enabled: self.is_tls_enabled,
-            #[cfg(feature = "tls")]
+            #[cfg(any(feature = "tls", feature = "tls-rustls"))]
             is_tls_insecure: self.is_tls_insecure,
             #[cfg(feature = "tls")]
             identity: self.identity.clone(),
             #[cfg(feature = "tls")]
             identity_passwd: self.identity_passwd.clone(),
+            #[cfg(feature = "tls-rustls")]
+            tls_cert: self.tls_cert.clone(),
+            #[cfg(feature = "tls-rustls")]
+            tls_key: self.tls_key.clone(),
+            #[cfg(feature = "tls-rustls")]
+            tls_ca_cert: self.tls_ca_cert.clone(),
+            #[cfg(feature = "tls-rustls")]
+            tls_server_name: self.tls_server_name.clone(),
+            #[cfg(all(feature = "tls", feature = "tls-rustls"))]
+            tls_backend: self.tls_backend,
+        }
+    }
+}
+
+impl Default for Config {
+    /// 连接`127.0.0.1:6379`，处理RDB及AOF，读写永不超时，每秒发送一次心跳，不启用TLS
+    fn default() -> Self {
+        Config {
+            is_discard_rdb: false,
+            is_aof: true,
+            host: String::from("127.0.0.1"),
+            port: 6379,
+            unix_socket_path: None,
+            username: String::new(),
+            password: String::new(),
+            repl_id: String::from("?"),
+            repl_offset: -1,
+            read_timeout: None,
+            write_timeout: None,
+            is_resp3: false,
+            is_forward_getack: false,
+            is_group_transaction: false,
+            heartbeat_interval: Duration::from_secs(1),
+            rdb_spool_dir: None,
+            rdb_backup_path: None,
+            rdb_filter_only: None,
+            #[cfg(any(feature = "tls", feature = "tls-rustls"))]
+            is_tls_enabled: false,
+            #[cfg(any(feature = "tls", feature = "tls-rustls"))]
+            is_tls_insecure: false,
+            #[cfg(feature = "tls")]
+            identity: None,
+            #[cfg(feature = "tls")]
+            identity_passwd: None,
+            #[cfg(feature = "tls-rustls")]
+            tls_cert: None,
+            #[cfg(feature = "tls-rustls")]
+            tls_key: None,
+            #[cfg(feature = "tls-rustls")]
+            tls_ca_cert: None,
+            #[cfg(feature = "tls-rustls")]
+            tls_server_name: None,
+            #[cfg(all(feature = "tls", feature = "tls-rustls"))]
+            tls_backend: None,
+        }
+    }
+}
+
+impl Config {
+    /// 从URL中解析配置，未指定的配置项使用[`Config::default`]中的值
+    ///
+    /// 支持以下格式:
+    ///
+    /// * `redis://[[username]:password@]host[:port][/db]`
+    /// * `rediss://...`: 同上，启用TLS(需开启`tls`或`tls-rustls` feature)
+    /// * `unix:///path/to/redis.sock`: 通过unix socket连接
+    ///
+    /// `db`会被忽略，replication总是包含所有的db。用户名及密码中的特殊字符需进行百分号编码。
+    /// 另可通过查询参数设置其他配置项，时间的单位为秒，可带小数:
+    ///
+    /// * `username`、`password`: 主要用于`unix://`
+    /// * `repl_id`、`repl_offset`
+    /// * `read_timeout`、`write_timeout`、`heartbeat_interval`
+    /// * `protocol`: `resp2`或`resp3`
+    /// * `discard_rdb`、`aof`、`forward_getack`、`group_transaction`: `true`或`false`
+    /// * `rdb_spool_dir`、`rdb_backup_path`、`rdb_filter_only`
+    /// * `insecure`: `true`时信任无效的证书和域名
+    /// * `identity`、`identity_passwd`: native-tls所使用的客户端证书
+    /// * `tls_cert`、`tls_key`、`tls_ca_cert`、`tls_server_name`: rustls的相关配置
+    /// * `tls_backend`: `native`或`rustls`，两个TLS feature同时开启时选择所使用的实现
+    ///
+    /// ```
+    /// use redis_event::config::Config;
+    ///
+    /// let conf = Config::from_url("redis://:secret@10.0.0.1:6380/?read_timeout=30").unwrap();
+    /// assert_eq!("10.0.0.1", conf.host);
+    /// assert_eq!(6380, conf.port);
+    /// assert_eq!("secret", conf.password);
+    /// ```
+    pub fn from_url(url: &str) -> Result<Config> {
+        let mut config = Config::default();
+        let (scheme, rest) = url
+            .split_once("://")
+            .ok_or_else(|| invalid_url(format!("missing scheme in {}", url)))?;
+        let (rest, query) = match rest.split_once('?') {
+            Some((rest, query)) => (rest, Some(query)),
+            None => (rest, None),
+        };
+        match scheme {
+            "redis" | "rediss" => {
+                if scheme == "rediss" {
+                    config.enable_tls()?;
+                }
+                let (authority, path) = match rest.split_once('/') {
+                    Some((authority, path)) => (authority, path),
+                    None => (rest, ""),
+                };
+                if !path.is_empty() && path.parse::<u32>().is_err() {
+                    return Err(invalid_url(format!("invalid db: {}", path)));
+                }
+                let address = match authority.rsplit_once('@') {
+                    Some((userinfo, address)) => {
+                        let (username, password) = userinfo.split_once(':').unwrap_or((userinfo, ""));
+                        config.username = percent_decode(username)?;
+                        config.password = percent_decode(password)?;
+                        address
+                    }
+                    None => authority,
+                };
+                let (host, port) = split_host_port(address)?;
+                if !host.is_empty() {
+                    config.host = host.to_owned();
+                }
+                if let Some(port) = port {
+                    config.port = port
+                        .parse()
+                        .map_err(|_| invalid_url(format!("invalid port: {}", port)))?;
+                }
+            }
+            "unix" | "redis+unix" => {
+                if rest.is_empty() {
+                    return Err(invalid_url("missing unix socket path"));
+                }
+                config.unix_socket_path = Some(percent_decode(rest)?);
+            }
+            _ => return Err(invalid_url(format!("unsupported scheme: {}", scheme))),
+        }
+        for pair in query.unwrap_or_default().split('&').filter(|pair| !pair.is_empty()) {
+            let (key, value) = pair.split_once('=').unwrap_or((pair, ""));
+            config.set_param(key, &percent_decode(value)?)?;
+        }
+        Ok(config)
+    }
+
+    /// 以链式调用的方式创建`Config`，未设置的配置项使用[`Config::default`]中的值
+    pub fn builder() -> ConfigBuilder {
+        ConfigBuilder {
+            config: Config::default(),
+        }
+    }
+
+    /// 检查配置是否有效，在创建`RedisListener`时调用，避免在连接之后才发现问题
+    pub(crate) fn validate(&self) -> Result<()> {
+        if self.unix_socket_path.is_none() {
+            if self.host.is_empty() {
+                return Err(invalid_config("host is required"));
+            }
+            if self.port == 0 {
+                return Err(invalid_config("port must not be 0"));
+            }
+        }
+        // 没有replication id时只能进行全量同步，offset需为-1
+        if self.repl_id == "?" {
+            if self.repl_offset != -1 {
+                return Err(invalid_config(format!(
+                    "repl_offset must be -1 when repl_id is ?, got {}",
+                    self.repl_offset
+                )));
+            }
+        } else if self.repl_id.is_empty() || self.repl_id.contains(char::is_whitespace) {
+            return Err(invalid_config(format!("invalid repl_id: {:?}", self.repl_id)));
+        } else if self.repl_offset < 0 {
+            return Err(invalid_config(format!(
+                "repl_offset must not be negative when repl_id is set, got {}",
+                self.repl_offset
+            )));
+        }
+        if self.is_aof && self.heartbeat_interval.is_zero() {
+            return Err(invalid_config("heartbeat_interval must not be 0"));
+        }
+        #[cfg(feature = "tls")]
+        if let Some(identity) = &self.identity {
+            check_readable("identity", identity)?;
+        }
+        #[cfg(feature = "tls-rustls")]
+        {
+            match (&self.tls_cert, &self.tls_key) {
+                (Some(cert), Some(key)) => {
+                    check_readable("tls_cert", cert)?;
+                    check_readable("tls_key", key)?;
+                }
+                (None, None) => {}
+                _ => return Err(invalid_config("tls_cert and tls_key must be set together")),
+            }
+            if let Some(ca_cert) = &self.tls_ca_cert {
+                check_readable("tls_ca_cert", ca_cert)?;
+            }
         }
+        // 两种实现的配置项不能混用，否则其中一部分会被静默忽略
+        #[cfg(all(feature = "tls", feature = "tls-rustls"))]
+        {
+            let is_native_set = self.identity.is_some() || self.identity_passwd.is_some();
+            let is_rustls_set = self.tls_cert.is_some()
+                || self.tls_key.is_some()
+                || self.tls_ca_cert.is_some()
+                || self.tls_server_name.is_some();
+            match self.tls_backend {
+                Some(TlsBackend::Native) if is_rustls_set => {
+                    return Err(invalid_config(
+                        "tls_cert, tls_key, tls_ca_cert and tls_server_name require the rustls backend",
+                    ));
+                }
+                Some(TlsBackend::Rustls) if is_native_set => {
+                    return Err(invalid_config(
+                        "identity and identity_passwd require the native backend",
+                    ));
+                }
+                None if is_native_set && is_rustls_set => {
+                    return Err(invalid_config(
+                        "identity and rustls options are both set, specify tls_backend explicitly",
+                    ));
+                }
+                _ => {}
+            }
+        }
+        Ok(())
+    }
+
+    fn set_param(&mut self, key: &str, value: &str) -> Result<()> {
+        match key {
+            "username" => self.username = value.to_owned(),
+            "password" => self.password = value.to_owned(),
+            "repl_id" => self.repl_id = value.to_owned(),
+            "repl_offset" => {
+                self.repl_offset = value
+                    .parse()
+                    .map_err(|_| invalid_url(format!("invalid repl_offset: {}", value)))?
+            }
+            "read_timeout" => self.read_timeout = Some(parse_duration(key, value)?),
+            "write_timeout" => self.write_timeout = Some(parse_duration(key, value)?),
+            "heartbeat_interval" => self.heartbeat_interval = parse_duration(key, value)?,
+            "protocol" => {
+                self.is_resp3 = match value {
+                    "resp3" | "3" => true,
+                    "resp2" | "2" => false,
+                    _ => return Err(invalid_url(format!("invalid protocol: {}", value))),
+                }
+            }
+            "discard_rdb" => self.is_discard_rdb = parse_bool(key, value)?,
+            "aof" => self.is_aof = parse_bool(key, value)?,
+            "forward_getack" => self.is_forward_getack = parse_bool(key, value)?,
+            "group_transaction" => self.is_group_transaction = parse_bool(key, value)?,
+            "rdb_spool_dir" => self.rdb_spool_dir = Some(value.to_owned()),
+            "rdb_backup_path" => self.rdb_backup_path = Some(value.to_owned()),
+            "rdb_filter_only" => self.rdb_filter_only = Some(value.to_owned()),
+            #[cfg(any(feature = "tls", feature = "tls-rustls"))]
+            "insecure" => self.is_tls_insecure = parse_bool(key, value)?,
+            #[cfg(feature = "tls")]
+            "identity" => self.identity = Some(value.to_owned()),
+            #[cfg(feature = "tls")]
+            "identity_passwd" => self.identity_passwd = Some(value.to_owned()),
+            #[cfg(feature = "tls-rustls")]
+            "tls_cert" => self.tls_cert = Some(value.to_owned()),
+            #[cfg(feature = "tls-rustls")]
+            "tls_key" => self.tls_key = Some(value.to_owned()),
+            #[cfg(feature = "tls-rustls")]
+            "tls_ca_cert" => self.tls_ca_cert = Some(value.to_owned()),
+            #[cfg(feature = "tls-rustls")]
+            "tls_server_name" => self.tls_server_name = Some(value.to_owned()),
+            #[cfg(all(feature = "tls", feature = "tls-rustls"))]
+            "tls_backend" => {
+                self.tls_backend = match value {
+                    "native" => Some(TlsBackend::Native),
+                    "rustls" => Some(TlsBackend::Rustls),
+                    _ => return Err(invalid_url(format!("invalid tls_backend: {}", value))),
+                }
+            }
+            _ => return Err(invalid_url(format!("unsupported parameter: {}", key))),
+        }
+        Ok(())
+    }
+
+    #[cfg(any(feature = "tls", feature = "tls-rustls"))]
+    fn enable_tls(&mut self) -> Result<()> {
+        self.is_tls_enabled = true;
+        Ok(())
+    }
+
+    #[cfg(not(any(feature = "tls", feature = "tls-rustls")))]
+    fn enable_tls(&mut self) -> Result<()> {
+        Err(invalid_url("rediss:// requires the tls or tls-rustls feature"))
+    }
+}
+
+/// 以链式调用的方式创建[`Config`]
+///
+/// ```
+/// use std::time::Duration;
+/// use redis_event::config::Config;
+///
+/// let conf = Config::builder()
+///     .with_host("10.0.0.1")
+///     .with_port(6380)
+///     .with_password("secret")
+///     .with_read_timeout(Duration::from_secs(30))
+///     .build();
+/// assert_eq!(6380, conf.port);
+/// ```
+///
+/// [`Config`]: struct.Config.html
+pub struct ConfigBuilder {
+    config: Config,
+}
+
+impl ConfigBuilder {
+    pub fn with_host(&mut self, host: &str) -> &mut Self {
+        self.config.host = host.to_owned();
+        self
+    }
+
+    pub fn with_port(&mut self, port: u16) -> &mut Self {
+        self.config.port = port;
+        self
+    }
+
+    pub fn with_unix_socket_path(&mut self, path: &str) -> &mut Self {
+        self.config.unix_socket_path = Some(path.to_owned());
+        self
+    }
+
+    pub fn with_username(&mut self, username: &str) -> &mut Self {
+        self.config.username = username.to_owned();
+        self
+    }
+
+    pub fn with_password(&mut self, password: &str) -> &mut Self {
+        self.config.password = password.to_owned();
+        self
+    }
+
+    /// 从指定的replication id及offset开始同步
+    pub fn with_repl(&mut self, repl_id: &str, repl_offset: i64) -> &mut Self {
+        self.config.repl_id = repl_id.to_owned();
+        self.config.repl_offset = repl_offset;
+        self
+    }
+
+    pub fn with_read_timeout(&mut self, timeout: Duration) -> &mut Self {
+        self.config.read_timeout = Some(timeout);
+        self
     }
+
+    pub fn with_write_timeout(&mut self, timeout: Duration) -> &mut Self {
+        self.config.write_timeout = Some(timeout);
+        self
+    }
+
+    pub fn with_heartbeat_interval(&mut self, interval: Duration) -> &mut Self {
+        self.config.heartbeat_interval = interval;
+        self
+    }
+
+    pub fn with_discard_rdb(&mut self, discard: bool) -> &mut Self {
+        self.config.is_discard_rdb = discard;
+        self
+    }
+
+    pub fn with_aof(&mut self, aof: bool) -> &mut Self {
+        self.config.is_aof = aof;
+        self
+    }
+
+    pub fn with_resp3(&mut self, resp3: bool) -> &mut Self {
+        self.config.is_resp3 = resp3;
+        self
+    }
+
+    pub fn with_forward_getack(&mut self, forward: bool) -> &mut Self {
+        self.config.is_forward_getack = forward;
+        self
+    }
+
+    pub fn with_group_transaction(&mut self, group: bool) -> &mut Self {
+        self.config.is_group_transaction = group;
+        self
+    }
+
+    pub fn with_rdb_spool_dir(&mut self, dir: &str) -> &mut Self {
+        self.config.rdb_spool_dir = Some(dir.to_owned());
+        self
+    }
+
+    pub fn with_rdb_backup_path(&mut self, path: &str) -> &mut Self {
+        self.config.rdb_backup_path = Some(path.to_owned());
+        self
+    }
+
+    pub fn with_rdb_filter_only(&mut self, filter: &str) -> &mut Self {
+        self.config.rdb_filter_only = Some(filter.to_owned());
+        self
+    }
+
+    #[cfg(any(feature = "tls", feature = "tls-rustls"))]
+    /// 启用TLS，`insecure`为true时信任无效的证书和域名
+    pub fn with_tls(&mut self, insecure: bool) -> &mut Self {
+        self.config.is_tls_enabled = true;
+        self.config.is_tls_insecure = insecure;
+        self
+    }
+
+    #[cfg(feature = "tls")]
+    /// native-tls所使用的客户端证书(PKCS#12格式)及其密码
+    pub fn with_identity(&mut self, identity: &str, passwd: Option<&str>) -> &mut Self {
+        self.config.identity = Some(identity.to_owned());
+        self.config.identity_passwd = passwd.map(str::to_owned);
+        self
+    }
+
+    #[cfg(feature = "tls-rustls")]
+    /// rustls所使用的客户端证书及私钥(PEM格式)
+    pub fn with_tls_cert(&mut self, cert: &str, key: &str) -> &mut Self {
+        self.config.tls_cert = Some(cert.to_owned());
+        self.config.tls_key = Some(key.to_owned());
+        self
+    }
+
+    #[cfg(feature = "tls-rustls")]
+    pub fn with_tls_ca_cert(&mut self, ca_cert: &str) -> &mut Self {
+        self.config.tls_ca_cert = Some(ca_cert.to_owned());
+        self
+    }
+
+    #[cfg(feature = "tls-rustls")]
+    pub fn with_tls_server_name(&mut self, server_name: &str) -> &mut Self {
+        self.config.tls_server_name = Some(server_name.to_owned());
+        self
+    }
+
+    #[cfg(all(feature = "tls", feature = "tls-rustls"))]
+    /// 两个TLS feature同时开启时，指定所使用的TLS实现
+    pub fn with_tls_backend(&mut self, backend: TlsBackend) -> &mut Self {
+        self.config.tls_backend = Some(backend);
+        self
+    }
+
+    pub fn build(&self) -> Config {
+        self.config.clone()
+    }
+}
+
+fn invalid_url<E: Into<String>>(msg: E) -> Error {
+    Error::new(ErrorKind::InvalidInput, msg.into())
+}
+
+fn invalid_config<E: Into<String>>(msg: E) -> Error {
+    Error::new(ErrorKind::InvalidInput, format!("invalid config: {}", msg.into()))
+}
+
+#[cfg(any(feature = "tls", feature = "tls-rustls"))]
+fn check_readable(name: &str, path: &str) -> Result<()> {
+    std::fs::File::open(path).map(|_| ()).map_err(|err| {
+        Error::new(
+            err.kind(),
+            format!("invalid config: cannot read {} {}: {}", name, path, err),
+        )
+    })
+}
+
+// 分离host及port，IPv6的地址需放在`[]`中
+fn split_host_port(address: &str) -> Result<(&str, Option<&str>)> {
+    if let Some(address) = address.strip_prefix('[') {
+        let (host, rest) = address
+            .split_once(']')
+            .ok_or_else(|| invalid_url(format!("invalid host: [{}", address)))?;
+        return match rest.strip_prefix(':') {
+            Some(port) => Ok((host, Some(port))),
+            None if rest.is_empty() => Ok((host, None)),
+            None => Err(invalid_url(format!("invalid host: [{}", address))),
+        };
+    }
+    match address.split_once(':') {
+        Some((host, port)) => Ok((host, Some(port))),
+        None => Ok((address, None)),
+    }
+}
+
+fn percent_decode(value: &str) -> Result<String> {
+    let bytes = value.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        if bytes[i] == b'%' {
+            let hex = bytes.get(i + 1..i + 3).and_then(|hex| std::str::from_utf8(hex).ok());
+            match hex.and_then(|hex| u8::from_str_radix(hex, 16).ok()) {
+                Some(byte) => decoded.push(byte),
+                None => return Err(invalid_url(format!("invalid percent-encoding: {}", value))),
+            }
+            i += 3;
+        } else {
+            decoded.push(bytes[i]);
+            i += 1;
+        }
+    }
+    String::from_utf8(decoded).map_err(|_| invalid_url(format!("invalid percent-encoding: {}", value)))
+}
+
+fn parse_bool(key: &str, value: &str) -> Result<bool> {
+    match value {
+        "true" | "1" => Ok(true),
+        "false" | "0" => Ok(false),
+        _ => Err(invalid_url(format!("invalid {}: {}", key, value))),
+    }
+}
+
+fn parse_duration(key: &str, value: &str) -> Result<Duration> {
+    value
+        .parse::<f64>()
+        .ok()
+        .and_then(|secs| Duration::try_from_secs_f64(secs).ok())
+        .ok_or_else(|| invalid_url(format!("invalid {}: {}", key, value)))
 }
@@ -0,0 +1,295 @@
+/*!
+按db、key、命令名称以及数据类型过滤事件
+
+[`Filter`]中可以设置多种条件，只有满足所有已设置条件的事件才会交给`EventHandler`，未设置的条件不做限制:
+
+* db: RDB中的数据使用其所在的db，AOF中的命令则使用最近一次`SELECT`所选择的db
+* key: 与glob(同Redis的`KEYS`命令)或正则表达式(需开启`regex` feature)进行匹配，
+  命令的任意一个key匹配即可，不涉及key的命令(如`FLUSHDB`)不受此条件限制。
+  未能解析的命令([`Command::Other`])无法确定其key，将第一个参数视为key进行匹配，没有参数时不受此条件限制
+* 命令名称: 仅对AOF中的命令生效，不区分大小写
+* 数据类型: 仅对RDB中的数据生效
+
+`Event::Transaction`中的命令会被逐条过滤，全部被过滤掉时整个事务都会被丢弃。
+`Filter`同时也是一个[`Middleware`]，可以放在[`MiddlewareChain`]中使用，如过滤`AofReader`读取到的命令。
+
+```
+use redis_event::filter::{DataType, Filter};
+
+let mut filter = Filter::new();
+filter.with_db(0);
+filter.with_key_pattern("user:*");
+filter.with_type(DataType::Hash);
+```
+
+[`Filter`]: struct.Filter.html
+[`Command::Other`]: ../cmd/enum.Command.html#variant.Other
+[`Middleware`]: ../handler/trait.Middleware.html
+[`MiddlewareChain`]: ../handler/struct.MiddlewareChain.html
+*/
+#[cfg(feature = "regex")]
+use std::io::{Error, ErrorKind, Result};
+
+use crate::cmd::Command;
+use crate::handler::Middleware;
+use crate::rdb::Object;
+use crate::{Event, EventHandler};
+
+/// RDB中数据的类型
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DataType {
+    String,
+    List,
+    Set,
+    SortedSet,
+    Hash,
+    Module,
+    Stream,
+    Function,
+}
+
+#[derive(Debug, Clone)]
+enum KeyPattern {
+    Glob(Vec<u8>),
+    #[cfg(feature = "regex")]
+    Regex(regex::bytes::Regex),
+}
+
+impl KeyPattern {
+    fn is_match(&self, key: &[u8]) -> bool {
+        match self {
+            KeyPattern::Glob(pattern) => glob_match(pattern, key),
+            #[cfg(feature = "regex")]
+            KeyPattern::Regex(regex) => regex.is_match(key),
+        }
+    }
+}
+
+/// 事件过滤器，同一种条件设置多次时满足其中之一即可
+#[derive(Debug, Clone, Default)]
+pub struct Filter {
+    dbs: Vec<isize>,
+    keys: Vec<KeyPattern>,
+    commands: Vec<String>,
+    types: Vec<DataType>,
+    // 最近一次SELECT所选择的db
+    db: isize,
+}
+
+impl Filter {
+    pub fn new() -> Filter {
+        Filter::default()
+    }
+
+    /// 只保留db为`db`的事件
+    pub fn with_db(&mut self, db: isize) {
+        self.dbs.push(db);
+    }
+
+    /// 只保留key与glob`pattern`匹配的事件，支持`*`、`?`、`[a-z]`、`[^a]`以及`\`转义
+    pub fn with_key_pattern(&mut self, pattern: &str) {
+        self.keys.push(KeyPattern::Glob(pattern.as_bytes().to_vec()));
+    }
+
+    /// 只保留key与正则表达式`regex`匹配的事件，`regex`无效时返回错误
+    #[cfg(feature = "regex")]
+    pub fn with_key_regex(&mut self, regex: &str) -> Result<()> {
+        let regex = regex::bytes::Regex::new(regex).map_err(|err| Error::new(ErrorKind::InvalidInput, err))?;
+        self.keys.push(KeyPattern::Regex(regex));
+        Ok(())
+    }
+
+    /// 只保留名称为`name`的命令，如`SET`、`SCRIPT LOAD`
+    pub fn with_command(&mut self, name: &str) {
+        self.commands.push(name.to_ascii_uppercase());
+    }
+
+    /// 只保留类型为`data_type`的RDB数据
+    pub fn with_type(&mut self, data_type: DataType) {
+        self.types.push(data_type);
+    }
+
+    /// 过滤`event`，不满足条件时返回None
+    pub(crate) fn apply<'a>(&mut self, event: Event<'a>) -> Option<Event<'a>> {
+        match event {
+            Event::RDB(obj) => {
+                if self.accept_object(&obj) {
+                    Some(Event::RDB(obj))
+                } else {
+                    None
+                }
+            }
+            Event::AOF(cmd) => {
+                if self.accept_command(&cmd) {
+                    Some(Event::AOF(cmd))
+                } else {
+                    None
+                }
+            }
+            Event::Transaction(cmds) => {
+                let cmds: Vec<Command> = cmds.into_iter().filter(|cmd| self.accept_command(cmd)).collect();
+                if cmds.is_empty() {
+                    None
+                } else {
+                    Some(Event::Transaction(cmds))
+                }
+            }
+        }
+    }
+
+    fn accept_object(&self, obj: &Object) -> bool {
+        let (data_type, key, meta) = match obj {
+            Object::String(kv) => (DataType::String, kv.key, kv.meta),
+            Object::List(list) => (DataType::List, list.key, list.meta),
+            Object::Set(set) => (DataType::Set, set.key, set.meta),
+            Object::SortedSet(sorted_set) => (DataType::SortedSet, sorted_set.key, sorted_set.meta),
+            Object::Hash(hash) => (DataType::Hash, hash.key, hash.meta),
+            Object::Module(key, _, meta) => (DataType::Module, key.as_slice(), *meta),
+            Object::Stream(key, stream) => (DataType::Stream, key.as_slice(), stream.meta),
+            // Function不属于任何db，也没有key
+            Object::Function(_) => return self.types.is_empty() || self.types.contains(&DataType::Function),
+            Object::BOR | Object::EOR => return true,
+        };
+        (self.types.is_empty() || self.types.contains(&data_type))
+            && self.accept_db(meta.db)
+            && self.accept_keys(&[key])
+    }
+
+    fn accept_command(&mut self, cmd: &Command) -> bool {
+        if let Command::SELECT(select) = cmd {
+            self.db = select.db as isize;
+        }
+        let keys = match cmd {
+            // 未能解析的命令无法确定其key，将第一个参数视为key
+            Command::Other(raw) => raw.args.first().map(Vec::as_slice).into_iter().collect(),
+            cmd => cmd.keys(),
+        };
+        let name = cmd.name();
+        (self.commands.is_empty() || self.commands.iter().any(|command| command.eq_ignore_ascii_case(name)))
+            && self.accept_db(self.db)
+            && self.accept_keys(&keys)
+    }
+
+    fn accept_db(&self, db: isize) -> bool {
+        self.dbs.is_empty() || self.dbs.contains(&db)
+    }
+
+    fn accept_keys(&self, keys: &[&[u8]]) -> bool {
+        self.keys.is_empty()
+            || keys.is_empty()
+            || keys
+                .iter()
+                .any(|key| self.keys.iter().any(|pattern| pattern.is_match(key)))
+    }
+}
+
+impl Middleware for Filter {
+    fn handle(&mut self, event: Event, next: &mut dyn EventHandler) {
+        if let Some(event) = self.apply(event) {
+            next.handle(event);
+        }
+    }
+}
+
+/// 只将满足`Filter`条件的事件交给`handler`
+pub(crate) struct FilteredHandler<'a> {
+    handler: &'a mut dyn EventHandler,
+    filter: &'a mut Filter,
+}
+
+impl<'a> FilteredHandler<'a> {
+    pub(crate) fn new(handler: &'a mut dyn EventHandler, filter: &'a mut Filter) -> FilteredHandler<'a> {
+        FilteredHandler { handler, filter }
+    }
+}
+
+impl EventHandler for FilteredHandler<'_> {
+    fn handle(&mut self, event: Event) {
+        if let Some(event) = self.filter.apply(event) {
+            self.handler.handle(event);
+        }
+    }
+}
+
+/// 与Redis的`stringmatchlen`一致的glob匹配
+pub(crate) fn glob_match(pattern: &[u8], string: &[u8]) -> bool {
+    let (mut p, mut s) = (0, 0);
+    // 最近一个`*`之后的位置，以及它当前匹配到的string位置
+    let mut star: Option<(usize, usize)> = None;
+    while s < string.len() {
+        if p < pattern.len() {
+            match pattern[p] {
+                b'*' => {
+                    p += 1;
+                    star = Some((p, s));
+                    continue;
+                }
+                b'?' => {
+                    p += 1;
+                    s += 1;
+                    continue;
+                }
+                b'[' => {
+                    let (matched, len) = match_class(&pattern[p..], string[s]);
+                    if matched {
+                        p += len;
+                        s += 1;
+                        continue;
+                    }
+                }
+                b'\\' if p + 1 < pattern.len() => {
+                    if pattern[p + 1] == string[s] {
+                        p += 2;
+                        s += 1;
+                        continue;
+                    }
+                }
+                c => {
+                    if c == string[s] {
+                        p += 1;
+                        s += 1;
+                        continue;
+                    }
+                }
+            }
+        }
+        // 匹配失败，让上一个`*`多匹配一个字符
+        match star {
+            Some((star_p, star_s)) => {
+                p = star_p;
+                s = star_s + 1;
+                star = Some((star_p, s));
+            }
+            None => return false,
+        }
+    }
+    pattern[p..].iter().all(|&c| c == b'*')
+}
+
+/// 匹配`[...]`，返回是否匹配以及`[...]`的长度
+fn match_class(pattern: &[u8], c: u8) -> (bool, usize) {
+    let mut i = 1;
+    let not = pattern.get(i) == Some(&b'^');
+    if not {
+        i += 1;
+    }
+    let mut matched = false;
+    while i < pattern.len() && pattern[i] != b']' {
+        if pattern[i] == b'\\' && i + 1 < pattern.len() {
+            matched |= pattern[i + 1] == c;
+            i += 2;
+        } else if i + 2 < pattern.len() && pattern[i + 1] == b'-' {
+            let (start, end) = if pattern[i] <= pattern[i + 2] {
+                (pattern[i], pattern[i + 2])
+            } else {
+                (pattern[i + 2], pattern[i])
+            };
+            matched |= start <= c && c <= end;
+            i += 3;
+        } else {
+            matched |= pattern[i] == c;
+            i += 1;
+        }
+    }
+    (matched != not, (i + 1).min(pattern.len()))
+}
@@ -0,0 +1,159 @@
+/*!
+组合多个[`EventHandler`]
+
+* [`FanOutHandler`]，将同一个事件依次交给多个`EventHandler`处理
+* [`MiddlewareChain`]，事件在交给`EventHandler`之前，依次经过多个[`Middleware`]，每一个`Middleware`都可以查看、转换或丢弃事件
+
+```
+use std::sync::{Arc, Mutex};
+use redis_event::handler::{FanOutHandler, Middleware, MiddlewareChain};
+use redis_event::{Event, EventHandler, NoOpEventHandler};
+use redis_event::rdb::Object;
+
+// 丢弃RDB中的数据，只保留AOF
+struct SkipRdb;
+
+impl Middleware for SkipRdb {
+    fn handle(&mut self, event: Event, next: &mut dyn EventHandler) {
+        if !matches!(event, Event::RDB(_)) {
+            next.handle(event);
+        }
+    }
+}
+
+let mut fan_out = FanOutHandler::new();
+fan_out.with_handler(Arc::new(Mutex::new(NoOpEventHandler {})));
+fan_out.with_handler(Arc::new(Mutex::new(NoOpEventHandler {})));
+
+let mut chain = MiddlewareChain::new(Arc::new(Mutex::new(fan_out)));
+chain.with_middleware(Box::new(SkipRdb));
+```
+
+[`EventHandler`]: ../trait.EventHandler.html
+[`FanOutHandler`]: struct.FanOutHandler.html
+[`MiddlewareChain`]: struct.MiddlewareChain.html
+[`Middleware`]: trait.Middleware.html
+*/
+use std::any::Any;
+use std::sync::{Arc, Mutex};
+
+use crate::rdb::{Module, Object};
+use crate::{Event, EventHandler};
+
+/// 将同一个事件依次交给多个`EventHandler`处理，处理的顺序与添加的顺序一致
+#[derive(Default)]
+pub struct FanOutHandler {
+    handlers: Vec<Arc<Mutex<dyn EventHandler + Send>>>,
+}
+
+impl FanOutHandler {
+    pub fn new() -> FanOutHandler {
+        FanOutHandler { handlers: Vec::new() }
+    }
+
+    pub fn with_handler(&mut self, handler: Arc<Mutex<dyn EventHandler + Send>>) {
+        self.handlers.push(handler);
+    }
+}
+
+impl EventHandler for FanOutHandler {
+    fn handle(&mut self, mut event: Event) {
+        if let Some((last, handlers)) = self.handlers.split_last() {
+            for handler in handlers {
+                handler.lock().unwrap().handle(reborrow(&mut event));
+            }
+            last.lock().unwrap().handle(event);
+        }
+    }
+}
+
+/// 事件处理的中间环节
+pub trait Middleware {
+    /// 调用`next.handle`将事件(或转换之后的事件)交给下一个环节，不调用则丢弃此事件
+    fn handle(&mut self, event: Event, next: &mut dyn EventHandler);
+}
+
+/// 事件依次经过各个[`Middleware`]，最后交给`EventHandler`处理
+///
+/// [`Middleware`]: trait.Middleware.html
+pub struct MiddlewareChain {
+    middlewares: Vec<Box<dyn Middleware + Send>>,
+    handler: Arc<Mutex<dyn EventHandler + Send>>,
+}
+
+impl MiddlewareChain {
+    pub fn new(handler: Arc<Mutex<dyn EventHandler + Send>>) -> MiddlewareChain {
+        MiddlewareChain {
+            middlewares: Vec::new(),
+            handler,
+        }
+    }
+
+    /// 添加一个`Middleware`，先添加的先执行
+    pub fn with_middleware(&mut self, middleware: Box<dyn Middleware + Send>) {
+        self.middlewares.push(middleware);
+    }
+}
+
+impl EventHandler for MiddlewareChain {
+    fn handle(&mut self, event: Event) {
+        let mut handler = self.handler.lock().unwrap();
+        let mut next = Next {
+            middlewares: &mut self.middlewares,
+            handler: &mut *handler,
+        };
+        next.handle(event);
+    }
+}
+
+/// 剩余的`Middleware`及最终的`EventHandler`
+struct Next<'a> {
+    middlewares: &'a mut [Box<dyn Middleware + Send>],
+    handler: &'a mut dyn EventHandler,
+}
+
+impl EventHandler for Next<'_> {
+    fn handle(&mut self, event: Event) {
+        match self.middlewares.split_first_mut() {
+            Some((middleware, middlewares)) => {
+                let mut next = Next {
+                    middlewares,
+                    handler: &mut *self.handler,
+                };
+                middleware.handle(event, &mut next);
+            }
+            None => self.handler.handle(event),
+        }
+    }
+}
+
+/// 借用`event`中的数据构造一个相同的事件，module则以引用的方式共享
+fn reborrow<'b>(event: &'b mut Event<'_>) -> Event<'b> {
+    match event {
+        Event::RDB(obj) => Event::RDB(match obj {
+            Object::String(kv) => Object::String(kv.clone()),
+            Object::List(list) => Object::List(list.clone()),
+            Object::Set(set) => Object::Set(set.clone()),
+            Object::SortedSet(sorted_set) => Object::SortedSet(sorted_set.clone()),
+            Object::Hash(hash) => Object::Hash(hash.clone()),
+            Object::Module(name, module, meta) => {
+                Object::Module(name.clone(), Box::new(ModuleRef(&mut **module)), meta)
+            }
+            Object::Stream(key, stream) => Object::Stream(key.clone(), stream.clone()),
+            Object::Function(code) => Object::Function(code.clone()),
+            Object::BOR => Object::BOR,
+            Object::EOR => Object::EOR,
+        }),
+        Event::AOF(cmd) => Event::AOF(cmd.clone()),
+        Event::Transaction(cmds) => Event::Transaction(cmds.clone()),
+    }
+}
+
+/// 借用的module，`as_any`返回原module的数据
+struct ModuleRef<'a>(&'a mut (dyn Module + 'a));
+
+impl Module for ModuleRef<'_> {
+    fn as_any(&self) -> &dyn Any {
+        self.0.as_any()
+    }
+}
@@ -3,19 +3,29 @@
 */
 
 use crate::resp::*;
-use std::io::{BufReader, Error, ErrorKind, Read, Result, Write};
+use log::{info, warn};
+use std::fs::{self, File, OpenOptions};
+use std::io::{BufRead, BufReader, BufWriter, Cursor, Error, ErrorKind, Read, Result, Write};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::time::{SystemTime, UNIX_EPOCH};
 
-pub(crate) struct CountReader<'a> {
-    input: BufReader<&'a mut dyn Read>,
+pub(crate) struct CountReader<R> {
+    input: BufReader<R>,
     len: i64,
     marked: bool,
+    // mark之后读取到的原始数据，None表示不保存
+    captured: Option<Vec<u8>>,
 }
 
-impl Read for CountReader<'_> {
+impl<R: Read> Read for CountReader<R> {
     fn read(&mut self, buf: &mut [u8]) -> Result<usize> {
         let len = self.input.read(buf)?;
         if self.marked {
             self.len += len as i64;
+            if let Some(captured) = &mut self.captured {
+                captured.extend_from_slice(&buf[..len]);
+            }
         };
         Ok(len)
     }
@@ -24,22 +34,44 @@ impl Read for CountReader<'_> {
         self.input.read_exact(buf)?;
         if self.marked {
             self.len += buf.len() as i64;
+            if let Some(captured) = &mut self.captured {
+                captured.extend_from_slice(buf);
+            }
         };
         Ok(())
     }
 }
 
-impl CountReader<'_> {
-    pub(crate) fn new(input: &mut dyn Read) -> CountReader {
+impl<R: Read> CountReader<R> {
+    pub(crate) fn new(input: R) -> CountReader<R> {
         CountReader {
             input: BufReader::new(input),
             len: 0,
             marked: false,
+            captured: None,
         }
     }
 
+    /// 保存mark之后读取到的原始数据，通过`captured`获取
+    pub(crate) fn capture(&mut self) {
+        self.captured = Some(Vec::new());
+    }
+
+    /// mark之后读取到的原始数据，在下一次mark时清空
+    pub(crate) fn captured(&self) -> &[u8] {
+        self.captured.as_deref().unwrap_or_default()
+    }
+
+    /// 获取底层的输入，用于在读取的同时向Redis写入数据
+    pub(crate) fn get_mut(&mut self) -> &mut R {
+        self.input.get_mut()
+    }
+
     pub(crate) fn mark(&mut self) {
         self.marked = true;
+        if let Some(captured) = &mut self.captured {
+            captured.clear();
+        }
     }
 
     pub(crate) fn reset(&mut self) -> Result<i64> {
@@ -53,29 +85,223 @@ impl CountReader<'_> {
     }
 }
 
-pub(crate) fn send<T: Write>(output: &mut T, command: &[u8], args: &[&[u8]]) -> Result<()> {
+/// 先读取`prefix`中的数据，读完之后再从`inner`中读取，写入则直接写入`inner`
+///
+/// 用于将读取RDB时多读取的数据交还给后续的AOF处理
+pub(crate) struct PrefixStream<T> {
+    prefix: Cursor<Vec<u8>>,
+    inner: T,
+}
+
+impl<T> PrefixStream<T> {
+    pub(crate) fn new(prefix: Vec<u8>, inner: T) -> PrefixStream<T> {
+        PrefixStream {
+            prefix: Cursor::new(prefix),
+            inner,
+        }
+    }
+}
+
+impl<T: Read> Read for PrefixStream<T> {
+    fn read(&mut self, buf: &mut [u8]) -> Result<usize> {
+        if (self.prefix.position() as usize) < self.prefix.get_ref().len() {
+            self.prefix.read(buf)
+        } else {
+            self.inner.read(buf)
+        }
+    }
+}
+
+impl<T: Write> Write for PrefixStream<T> {
+    fn write(&mut self, buf: &[u8]) -> Result<usize> {
+        self.inner.write(buf)
+    }
+
+    fn flush(&mut self) -> Result<()> {
+        self.inner.flush()
+    }
+}
+
+/// 可读可写的连接
+pub(crate) trait ReadWrite: Read + Write {}
+
+impl<T: Read + Write> ReadWrite for T {}
+
+pub(crate) fn send<T: Write + ?Sized>(output: &mut T, command: &[u8], args: &[&[u8]]) -> Result<()> {
     let mut buf = vec![];
-    buf.write(&[STAR])?;
-    let args_len = args.len() + 1;
-    buf.write(&args_len.to_string().into_bytes())?;
-    buf.write(&[CR, LF, DOLLAR])?;
-    buf.write(&command.len().to_string().into_bytes())?;
-    buf.write(&[CR, LF])?;
-    buf.write(command)?;
-    buf.write(&[CR, LF])?;
-    for arg in args {
-        buf.write(&[DOLLAR])?;
-        buf.write(&arg.len().to_string().into_bytes())?;
-        buf.write(&[CR, LF])?;
-        buf.write(arg)?;
-        buf.write(&[CR, LF])?;
-    }
-    output.write_all(&mut buf)?;
+    buf.encode_command(command, args)?;
+    output.write_all(&buf)?;
     output.flush()
 }
 
-// 跳过rdb的字节
-pub(crate) fn skip(input: &mut dyn Read, length: isize) -> Result<()> {
-    std::io::copy(&mut input.take(length as u64), &mut std::io::sink())?;
-    Ok(())
+/// 将`mark`之前的数据写入`output`，并跳过`mark`本身，返回写入的字节数
+pub(crate) fn copy_until(input: &mut dyn BufRead, mark: &[u8], output: &mut dyn Write) -> Result<u64> {
+    // 上一次读取的末尾，mark可能跨越两次读取
+    let mut carry: Vec<u8> = Vec::with_capacity(mark.len());
+    let mut copied = 0;
+    loop {
+        let buf = input.fill_buf()?;
+        if buf.is_empty() {
+            return Err(Error::new(ErrorKind::UnexpectedEof, "EOF mark not found"));
+        }
+        let len = buf.len();
+        let carried = carry.len();
+        let mut data = carry;
+        data.extend_from_slice(buf);
+        if let Some(pos) = data.windows(mark.len()).position(|window| window == mark) {
+            input.consume(pos + mark.len() - carried);
+            output.write_all(&data[..pos])?;
+            return Ok(copied + pos as u64);
+        }
+        let keep = data.len().min(mark.len() - 1);
+        carry = data.split_off(data.len() - keep);
+        output.write_all(&data)?;
+        copied += data.len() as u64;
+        input.consume(len);
+    }
+}
+
+/// 读取的同时，将读取到的数据写入`output`
+pub(crate) struct TeeReader<'a, R> {
+    input: R,
+    output: &'a mut dyn Write,
+    count: u64,
+}
+
+impl<'a, R: Read> TeeReader<'a, R> {
+    pub(crate) fn new(input: R, output: &'a mut dyn Write) -> TeeReader<'a, R> {
+        TeeReader {
+            input,
+            output,
+            count: 0,
+        }
+    }
+
+    /// 已读取的字节数
+    pub(crate) fn count(&self) -> u64 {
+        self.count
+    }
+}
+
+impl<R: Read> Read for TeeReader<'_, R> {
+    fn read(&mut self, buf: &mut [u8]) -> Result<usize> {
+        let len = self.input.read(buf)?;
+        self.output.write_all(&buf[..len])?;
+        self.count += len as u64;
+        Ok(len)
+    }
+}
+
+/// 将接收到的RDB原样写入文件及writer
+///
+/// 文件先写入`<path>.tmp`，`commit`时再重命名为`path`，未`commit`则删除临时文件
+pub(crate) struct RdbBackup {
+    file: Option<BackupFile>,
+    writer: Option<Arc<Mutex<dyn Write + Send>>>,
+}
+
+struct BackupFile {
+    path: PathBuf,
+    tmp_path: PathBuf,
+    file: BufWriter<File>,
+}
+
+impl RdbBackup {
+    pub(crate) fn new(path: Option<&str>, writer: Option<&Arc<Mutex<dyn Write + Send>>>) -> Result<RdbBackup> {
+        let file = match path {
+            Some(path) => {
+                let mut tmp_path = path.to_string();
+                tmp_path.push_str(".tmp");
+                let file = BufWriter::new(File::create(&tmp_path)?);
+                Some(BackupFile {
+                    path: PathBuf::from(path),
+                    tmp_path: PathBuf::from(tmp_path),
+                    file,
+                })
+            }
+            None => None,
+        };
+        Ok(RdbBackup {
+            file,
+            writer: writer.cloned(),
+        })
+    }
+
+    /// RDB已完整接收
+    pub(crate) fn commit(mut self) -> Result<()> {
+        if let Some(writer) = &self.writer {
+            writer.lock().unwrap().flush()?;
+        }
+        if let Some(mut backup) = self.file.take() {
+            backup.file.flush()?;
+            backup.file.get_ref().sync_all()?;
+            fs::rename(&backup.tmp_path, &backup.path)?;
+            info!("RDB已保存至{}", backup.path.display());
+        }
+        Ok(())
+    }
+}
+
+impl Write for RdbBackup {
+    fn write(&mut self, buf: &[u8]) -> Result<usize> {
+        if let Some(backup) = &mut self.file {
+            backup.file.write_all(buf)?;
+        }
+        if let Some(writer) = &self.writer {
+            writer.lock().unwrap().write_all(buf)?;
+        }
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> Result<()> {
+        if let Some(backup) = &mut self.file {
+            backup.file.flush()?;
+        }
+        if let Some(writer) = &self.writer {
+            writer.lock().unwrap().flush()?;
+        }
+        Ok(())
+    }
+}
+
+impl Drop for RdbBackup {
+    fn drop(&mut self) {
+        if let Some(BackupFile { tmp_path, file, .. }) = self.file.take() {
+            drop(file);
+            if let Err(err) = fs::remove_file(&tmp_path) {
+                warn!("删除临时文件{}失败: {}", tmp_path.display(), err);
+            }
+        }
+    }
+}
+
+/// 存放RDB的临时文件，drop时删除
+pub(crate) struct SpoolFile {
+    path: PathBuf,
+    file: File,
+}
+
+impl SpoolFile {
+    pub(crate) fn create(dir: &str) -> Result<SpoolFile> {
+        let nanos = SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_nanos();
+        let path = Path::new(dir).join(format!("redis-event-{}-{}.rdb", std::process::id(), nanos));
+        let file = OpenOptions::new().read(true).write(true).create_new(true).open(&path)?;
+        Ok(SpoolFile { path, file })
+    }
+
+    pub(crate) fn path(&self) -> &Path {
+        &self.path
+    }
+
+    pub(crate) fn file(&mut self) -> &mut File {
+        &mut self.file
+    }
+}
+
+impl Drop for SpoolFile {
+    fn drop(&mut self) {
+        if let Err(err) = fs::remove_file(&self.path) {
+            warn!("删除临时文件{}失败: {}", self.path.display(), err);
+        }
+    }
 }
@@ -0,0 +1,91 @@
+/*!
+复制延迟的监控
+
+开启之后，[`Listener`]会额外建立一个连接(使用相同的`Config`)，定时执行`INFO replication`，
+将其中的`master_repl_offset`与已处理的offset进行比较，得出延迟的字节数以及大致的延迟时间。
+
+[`Listener`]: ../listener/struct.Listener.html
+*/
+use std::collections::VecDeque;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+/// 最多保留的采样数
+const MAX_SAMPLES: usize = 4096;
+
+/// 某一时刻的复制延迟
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ReplicationLag {
+    /// Redis的`master_repl_offset`
+    pub master_offset: i64,
+    /// 已交给`EventHandler`处理的offset
+    pub applied_offset: i64,
+    /// 落后的字节数
+    pub bytes: i64,
+    /// 大致的延迟时间，即Redis的offset越过`applied_offset`至今的时长，精度取决于采样间隔
+    pub time: Duration,
+}
+
+/// 复制延迟的回调，每次采样之后调用
+pub trait LagHandler {
+    fn handle(&mut self, lag: &ReplicationLag);
+}
+
+/// 用于在其他线程中获取最近一次采样到的复制延迟
+#[derive(Clone, Default)]
+pub struct LagMonitor {
+    pub(crate) latest: Arc<Mutex<Option<ReplicationLag>>>,
+}
+
+impl LagMonitor {
+    /// 最近一次采样到的复制延迟，未开启监控或尚未采样时为None
+    pub fn latest(&self) -> Option<ReplicationLag> {
+        *self.latest.lock().unwrap()
+    }
+}
+
+/// 记录`master_repl_offset`的变化，据此估算延迟时间
+#[derive(Default)]
+pub(crate) struct LagTracker {
+    samples: VecDeque<(Instant, i64)>,
+}
+
+impl LagTracker {
+    pub(crate) fn update(&mut self, now: Instant, master_offset: i64, applied_offset: i64) -> ReplicationLag {
+        // offset变小说明Redis发生了切换或重启，之前的采样已无意义
+        if self.samples.back().is_some_and(|&(_, offset)| offset > master_offset) {
+            self.samples.clear();
+        }
+        if self.samples.back().is_none_or(|&(_, offset)| offset < master_offset) {
+            if self.samples.len() == MAX_SAMPLES {
+                self.samples.pop_front();
+            }
+            self.samples.push_back((now, master_offset));
+        }
+        while self
+            .samples
+            .front()
+            .is_some_and(|&(_, offset)| offset <= applied_offset)
+        {
+            self.samples.pop_front();
+        }
+        let time = match self.samples.front() {
+            Some(&(since, _)) => now.duration_since(since),
+            None => Duration::ZERO,
+        };
+        ReplicationLag {
+            master_offset,
+            applied_offset,
+            bytes: (master_offset - applied_offset).max(0),
+            time,
+        }
+    }
+}
+
+/// 从`INFO replication`的回复中取出`master_repl_offset`
+pub(crate) fn parse_master_offset(info: &[u8]) -> Option<i64> {
+    String::from_utf8_lossy(info)
+        .lines()
+        .find_map(|line| line.strip_prefix("master_repl_offset:"))
+        .and_then(|offset| offset.trim().parse::<i64>().ok())
+}
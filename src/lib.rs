@@ -14,33 +14,25 @@
 * ```no_run
 * use std::net::{IpAddr, SocketAddr};
 * use std::sync::atomic::AtomicBool;
-* use std::sync::Arc;
 * use std::str::FromStr;
-* use std::rc::Rc;
-* use std::cell::RefCell;
+* use std::time::Duration;
+* use std::sync::{Arc, Mutex};
 * use redis_event::listener;
 * use redis_event::config::Config;
 * use redis_event::{NoOpEventHandler, RedisListener};
 *
 * fn main() -> std::io::Result<()> {
-*     let host = String::from("127.0.0.1");
-*     let port = 6379;
-*
+*     // 也可以通过`Config::from_url("redis://127.0.0.1:6379")`或`Config::builder()`创建
 *     let conf = Config {
 *         is_discard_rdb: false,            // 不跳过RDB
 *         is_aof: false,                    // 不处理AOF
-*         host,
-*         port,
-*         username: String::new(),          // 用户名为空
-*         password: String::new(),          // 密码为空
+*         host: String::from("127.0.0.1"),
+*         port: 6379,
 *         repl_id: String::from("?"),       // replication id，若无此id，设置为?即可
 *         repl_offset: -1,                  // replication offset，若无此offset，设置为-1即可
 *         read_timeout: None,               // None，即读取永不超时
-*         write_timeout: None,              // None，即写入永不超时
-*         is_tls_enabled: false,            // 不启用TLS
-*         is_tls_insecure: false,           // 未启用TLS，设置为false即可
-*         identity: None,                   // 未启用TLS，设置为None即可
-*         identity_passwd: None             // 未启用TLS，设置为None即可
+*         heartbeat_interval: Duration::from_secs(1), // 每秒发送一次心跳
+*         ..Config::default()               // 其余配置项(包括TLS相关的配置)使用默认值
 *     };
 *     let running = Arc::new(AtomicBool::new(true));
 *
@@ -49,9 +41,9 @@
 *     // 设置控制变量, 通过此变量在外界中断`redis_event`内部的逻辑
 *     builder.with_control_flag(running);
 *     // 设置事件处理器
-*     builder.with_event_handler(Rc::new(RefCell::new(NoOpEventHandler{})));
+*     builder.with_event_handler(Arc::new(Mutex::new(NoOpEventHandler{})));
 *
-*     let mut redis_listener = builder.build();
+*     let mut redis_listener = builder.build()?;
 *     // 启动程序
 *     redis_listener.start()?;
 *     Ok(())
@@ -67,15 +59,26 @@ use std::io::{Read, Result};
 use crate::cmd::Command;
 use crate::rdb::{Module, Object};
 
+#[cfg(feature = "async")]
+pub mod aio;
+pub mod aof;
 pub mod cmd;
 pub mod config;
+pub mod filter;
+pub mod handler;
 mod io;
 mod iter;
+pub mod lag;
 pub mod listener;
 mod lzf;
+pub mod metrics;
 pub mod rdb;
 pub mod resp;
+#[cfg(any(test, feature = "testing"))]
+pub mod testing;
 mod tests;
+#[cfg(any(feature = "tls", feature = "tls-rustls"))]
+mod tls;
 
 /// Redis事件监听器的定义，所有类型的监听器都实现此接口
 pub trait RedisListener {
@@ -107,6 +110,11 @@ pub enum Event<'a> {
     /// 在上面rdb数据处理完毕之后，客户端对Redis的数据写入操作将会发送给`RedisListener`，
     /// 此写入操作即对应此事件
     AOF(Command<'a>),
+    /// 事务事件
+    ///
+    /// `Config::is_group_transaction`为true时，`MULTI`与`EXEC`之间的命令会被合并为此事件，
+    /// 不包括`MULTI`及`EXEC`本身
+    Transaction(Vec<Command<'a>>),
 }
 
 /// Redis事件处理器的定义，所有类型的处理器都必须实现此接口
@@ -3,215 +3,204 @@
 
 [`RedisListener`]: trait.RedisListener.html
 */
-use std::cell::RefCell;
-use std::io::{BufRead, BufReader, Error, ErrorKind, Read, Result, Write};
-use std::net::TcpStream;
+use std::io::{BufRead, BufReader, Error, ErrorKind, Read, Result, Seek, SeekFrom, Write};
+use std::net::{Shutdown, SocketAddr, TcpStream, ToSocketAddrs};
 use std::ops::DerefMut;
-use std::rc::Rc;
+#[cfg(unix)]
+use std::os::unix::net::UnixStream;
 use std::result::Result::Ok;
-use std::sync::Arc;
 use std::sync::atomic::{AtomicBool, AtomicI64, Ordering};
+use std::sync::{Arc, Mutex};
 use std::thread::sleep;
 use std::time::{Duration, Instant};
 
+#[cfg(any(feature = "tls", feature = "tls-rustls"))]
+use crate::tls::{self, TlsStream};
 use log::{error, info, warn};
-#[cfg(feature = "tls")]
-use native_tls::{Identity, TlsConnector, TlsStream};
 
+use crate::aof::AofWriter;
 use crate::config::Config;
-use crate::io::send;
+use crate::filter::{Filter, FilteredHandler};
+use crate::io::{ReadWrite, send};
+use crate::lag::{self, LagHandler, LagMonitor, LagTracker};
+use crate::metrics::{MeteredHandler, MeteredStream, Metrics};
 use crate::rdb::DefaultRDBParser;
 use crate::resp::{Resp, RespDecode, Type};
 use crate::{EventHandler, ModuleParser, NoOpEventHandler, RDBParser, RedisListener, cmd, io};
 use scheduled_thread_pool::{JobHandle, ScheduledThreadPool};
-use std::fs::File;
 
 /// 用于监听单个Redis实例的事件
 pub struct Listener {
     pub config: Config,
     conn: Option<Stream>,
-    rdb_parser: Rc<RefCell<dyn RDBParser>>,
-    event_handler: Rc<RefCell<dyn EventHandler>>,
+    rdb_parser: Arc<Mutex<dyn RDBParser + Send>>,
+    event_handler: Arc<Mutex<dyn EventHandler + Send>>,
     heartbeat_thread: HeartbeatWorker,
     running: Arc<AtomicBool>,
     local_ip: Option<String>,
     local_port: Option<u16>,
     thread_pool: Arc<ScheduledThreadPool>,
     repl_offset: Arc<AtomicI64>,
+    socket: Arc<Mutex<Option<Stream>>>,
+    buffered: Vec<u8>,
+    rdb_writer: Option<Arc<Mutex<dyn Write + Send>>>,
+    aof_writer: Option<Arc<Mutex<AofWriter>>>,
+    lag_interval: Option<Duration>,
+    lag_handler: Option<Arc<Mutex<dyn LagHandler + Send>>>,
+    lag_monitor: LagMonitor,
+    lag_thread_pool: Option<ScheduledThreadPool>,
+    lag_thread: HeartbeatWorker,
+    aof_sync_thread: HeartbeatWorker,
+    metrics: Metrics,
+    filter: Option<Filter>,
+    // 发送`REPLCONF ACK`所用的连接，心跳线程与读取线程通过同一把锁写入，避免两者的数据交错
+    ack_writer: Option<Arc<Mutex<Stream>>>,
 }
 
 impl Listener {
-    /// 连接Redis，创建TCP连接
-    fn connect(&mut self) -> Result<()> {
-        let addr = format!("{}:{}", &self.config.host, self.config.port);
-        let stream = TcpStream::connect(&addr)?;
-        stream
-            .set_read_timeout(self.config.read_timeout)
-            .expect("read timeout set failed");
-        stream
-            .set_write_timeout(self.config.write_timeout)
-            .expect("write timeout set failed");
+    /// 获取此Listener的[`ShutdownHandle`]，可在其他线程中中断正在运行的`start()`
+    ///
+    /// 在`build`之后、调用`start()`之前通过此方法获取，同一个Listener多次获取的`ShutdownHandle`是等价的
+    ///
+    /// [`ShutdownHandle`]: struct.ShutdownHandle.html
+    pub fn shutdown_handle(&self) -> ShutdownHandle {
+        ShutdownHandle {
+            running: Arc::clone(&self.running),
+            socket: Arc::clone(&self.socket),
+        }
+    }
 
-        info!("Connected to server {}", &addr);
+    /// 获取此Listener的[`LagMonitor`]，可在其他线程中查看复制延迟
+    ///
+    /// [`LagMonitor`]: ../lag/struct.LagMonitor.html
+    pub fn lag_monitor(&self) -> LagMonitor {
+        self.lag_monitor.clone()
+    }
 
-        let socket_addr = stream.local_addr().unwrap();
-        let local_ip = socket_addr.ip().to_string();
-        self.local_ip = Some(local_ip);
-
-        let local_port = socket_addr.port();
-        self.local_port = Some(local_port);
-
-        if cfg!(feature = "tls") {
-            #[cfg(feature = "tls")]
-            if self.config.is_tls_enabled {
-                let mut builder = TlsConnector::builder();
-                builder.danger_accept_invalid_hostnames(self.config.is_tls_insecure);
-                builder.danger_accept_invalid_certs(self.config.is_tls_insecure);
-
-                if let Some(id) = &self.config.identity {
-                    let mut file = File::open(id)?;
-                    let mut buff = Vec::new();
-                    file.read_to_end(&mut buff)?;
-                    let identity_passwd = match &self.config.identity_passwd {
-                        None => "",
-                        Some(passwd) => passwd.as_str(),
-                    };
-                    let identity = Identity::from_pkcs12(&buff, identity_passwd).expect("解析key失败");
-                    builder.identity(identity);
-                }
+    /// 获取此Listener的[`Metrics`]，可在其他线程中获取监控指标的快照
+    ///
+    /// [`Metrics`]: ../metrics/struct.Metrics.html
+    pub fn metrics(&self) -> Metrics {
+        self.metrics.clone()
+    }
 
-                let connector = builder.build().unwrap();
-                let tls_stream = connector
-                    .connect(&self.config.host, stream)
-                    .expect("TLS connect failed");
-                self.conn = Option::Some(Stream::Tls(tls_stream));
-                return Ok(());
-            }
+    /// 连接Redis，创建TCP连接，若设置了`unix_socket_path`则通过unix socket连接
+    fn connect(&mut self) -> Result<()> {
+        let conn = Stream::connect(&self.config)?;
+        self.metrics.add_connection();
+
+        // unix socket没有本地的ip及端口
+        let local_addr = conn.local_addr()?;
+        self.local_ip = local_addr.map(|addr| addr.ip().to_string());
+        self.local_port = local_addr.map(|addr| addr.port());
+
+        let socket = conn.socket()?;
+        let mut guard = self.socket.lock().unwrap();
+        if !self.is_running() {
+            socket.shutdown(Shutdown::Read)?;
         }
-        self.conn = Option::Some(Stream::Tcp(stream));
+        *guard = Some(socket);
+        drop(guard);
+
+        self.conn = Some(conn);
         Ok(())
     }
 
     /// 如果有设置密码，将尝试使用此密码进行认证
     fn auth(&mut self) -> Result<()> {
-        if !self.config.password.is_empty() {
-            let mut args = Vec::with_capacity(2);
-            if !self.config.username.is_empty() {
-                args.push(self.config.username.as_bytes());
-            }
-            args.push(self.config.password.as_bytes());
-            let conn = self.conn.as_mut().unwrap();
-            let conn: &mut dyn Read = match conn {
-                Stream::Tcp(tcp_stream) => {
-                    send(tcp_stream, b"AUTH", &args)?;
-                    tcp_stream
-                }
-                #[cfg(feature = "tls")]
-                Stream::Tls(tls_stream) => {
-                    send(tls_stream, b"AUTH", &args)?;
-                    tls_stream
-                }
-            };
-            conn.decode_resp()?;
-        }
-        Ok(())
+        auth(self.conn.as_mut().unwrap(), &self.config)
     }
 
     /// 发送replica相关信息到redis，此端口展现在`info replication`中
+    ///
+    /// 通过unix socket连接时没有本地的ip及端口，将不发送`listening-port`以及`ip-address`
     fn send_replica_info(&mut self) -> Result<()> {
-        let port = self.local_port.unwrap().to_string();
-        let port = port.as_bytes();
-
-        let ip = self.local_ip.as_ref().unwrap();
-        let ip = ip.as_bytes();
-
+        let port = self.local_port.map(|port| port.to_string());
+        let ip = self.local_ip.as_deref();
         let conn = self.conn.as_mut().unwrap();
-        match conn {
-            Stream::Tcp(tcp_stream) => Listener::de_send_replica_info(&port, &ip, tcp_stream)?,
-            #[cfg(feature = "tls")]
-            Stream::Tls(tls_stream) => Listener::de_send_replica_info(&port, &ip, tls_stream)?,
-        };
-        Ok(())
-    }
 
-    fn de_send_replica_info<T: Write + Read>(port: &&[u8], ip: &&[u8], tcp_stream: &mut T) -> Result<()> {
         info!("PING");
-        send(tcp_stream, b"PING", &vec![])?;
-        Listener::reply(tcp_stream)?;
+        send(conn, b"PING", &[])?;
+        Listener::reply(conn)?;
 
-        info!("REPLCONF listening-port {}", String::from_utf8_lossy(*port));
-        send(tcp_stream, b"REPLCONF", &[b"listening-port", port])?;
-        Listener::reply(tcp_stream)?;
+        if let Some(port) = &port {
+            info!("REPLCONF listening-port {}", port);
+            send(conn, b"REPLCONF", &[b"listening-port", port.as_bytes()])?;
+            Listener::reply(conn)?;
+        }
 
-        info!("REPLCONF ip-address {}", String::from_utf8_lossy(*ip));
-        send(tcp_stream, b"REPLCONF", &[b"ip-address", ip])?;
-        Listener::reply(tcp_stream)?;
+        if let Some(ip) = ip {
+            info!("REPLCONF ip-address {}", ip);
+            send(conn, b"REPLCONF", &[b"ip-address", ip.as_bytes()])?;
+            Listener::reply(conn)?;
+        }
 
         info!("REPLCONF capa eof");
-        send(tcp_stream, b"REPLCONF", &[b"capa", b"eof"])?;
-        Listener::reply(tcp_stream)?;
+        send(conn, b"REPLCONF", &[b"capa", b"eof"])?;
+        Listener::reply(conn)?;
 
         info!("REPLCONF capa psync2");
-        send(tcp_stream, b"REPLCONF", &[b"capa", b"psync2"])?;
-        Listener::reply(tcp_stream)
-    }
+        send(conn, b"REPLCONF", &[b"capa", b"psync2"])?;
+        Listener::reply(conn)?;
 
-    fn reply<T: Read>(tcp_stream: &mut T) -> Result<()> {
-        match tcp_stream.decode_resp()? {
-            Resp::String(str) => info!("{}", str),
-            Resp::Error(err) => {
-                warn!("{}", &err);
-                if (err.contains("NOAUTH") || err.contains("NOPERM"))
-                    && !err.contains("no password")
-                    && !err.contains("Unrecognized REPLCONF option")
-                {
-                    return Err(Error::new(ErrorKind::InvalidData, err));
-                }
+        // 只需要RDB时告知Redis(7.0及以上)，Redis不会为此连接创建backlog，RDB发送完毕之后即断开连接。
+        // 低版本的Redis会回复错误，此时按普通的replica进行处理
+        if !self.config.is_aof {
+            info!("REPLCONF rdb-only 1");
+            send(conn, b"REPLCONF", &[b"rdb-only", b"1"])?;
+            Listener::reply(conn)?;
+            if let Some(filter) = &self.config.rdb_filter_only {
+                info!("REPLCONF rdb-filter-only {}", filter);
+                send(conn, b"REPLCONF", &[b"rdb-filter-only", filter.as_bytes()])?;
+                Listener::reply(conn)?;
             }
-            _ => panic!("Unexpected response type"),
         }
         Ok(())
     }
 
+    fn reply<T: Read>(tcp_stream: &mut T) -> Result<()> {
+        check_reply(tcp_stream.decode_resp()?)
+    }
+
     /// 开启replication
     /// 默认使用PSYNC命令，若不支持PSYNC则尝试使用SYNC命令
     fn start_sync(&mut self) -> Result<Mode> {
-        let (next_step, mut length) = self.psync()?;
+        let (next_step, size) = self.psync()?;
         match next_step {
             NextStep::FullSync | NextStep::ChangeMode => {
-                let mode;
-                if let NextStep::ChangeMode = next_step {
+                let (mode, size) = if let NextStep::ChangeMode = next_step {
                     info!("源Redis不支持PSYNC命令, 使用SYNC命令再次进行尝试");
-                    mode = Mode::Sync;
-                    length = self.sync()?;
-                } else {
-                    mode = Mode::PSync;
-                }
-                if length != -1 {
-                    info!("Full Sync, size: {}bytes", length);
+                    (Mode::Sync, self.sync()?)
                 } else {
-                    info!("Disk-less replication.");
+                    (Mode::PSync, size.expect("Expect RDB size"))
+                };
+                match &size {
+                    RdbSize::Length(length) => info!("Full Sync, size: {}bytes", length),
+                    RdbSize::EofMark(_) => info!("Disk-less replication."),
                 }
                 let conn = self.conn.as_mut().unwrap();
-
-                let conn: &mut dyn Read = match conn {
-                    Stream::Tcp(tcp_stream) => tcp_stream,
-                    #[cfg(feature = "tls")]
-                    Stream::Tls(tls_stream) => tls_stream,
-                };
-                let mut reader = BufReader::new(conn);
+                let mut reader = BufReader::new(MeteredStream::new(conn, &self.metrics));
                 reader.fill_buf()?;
-                if length != -1 && self.config.is_discard_rdb {
-                    info!("跳过RDB不进行处理");
-                    io::skip(&mut reader, length as isize)?;
-                } else {
-                    let mut event_handler = self.event_handler.borrow_mut();
-                    let mut rdb_parser = self.rdb_parser.borrow_mut();
-                    rdb_parser.parse(&mut reader, length, event_handler.deref_mut())?;
-                    if length == -1 {
-                        io::skip(&mut reader, 40)?;
+                let mut event_handler = self.event_handler.lock().unwrap();
+                let mut filtered;
+                let event_handler: &mut dyn EventHandler = match self.filter.as_mut() {
+                    Some(filter) => {
+                        filtered = FilteredHandler::new(event_handler.deref_mut(), filter);
+                        &mut filtered
                     }
-                }
+                    None => event_handler.deref_mut(),
+                };
+                let mut rdb_parser = self.rdb_parser.lock().unwrap();
+                receive_rdb(
+                    &mut reader,
+                    &size,
+                    &self.config,
+                    self.rdb_writer.as_ref(),
+                    rdb_parser.deref_mut(),
+                    &mut MeteredHandler::new(event_handler, &self.metrics),
+                )?;
+                // RDB之后的数据已属于AOF，留到receive_aof中处理
+                self.buffered = reader.buffer().to_vec();
                 Ok(mode)
             }
             NextStep::PartialResync => {
@@ -222,220 +211,317 @@ impl Listener {
         }
     }
 
-    fn psync(&mut self) -> Result<(NextStep, i64)> {
+    fn psync(&mut self) -> Result<(NextStep, Option<RdbSize>)> {
         let offset = self.config.repl_offset.to_string();
         let repl_offset = offset.as_bytes();
         let repl_id = self.config.repl_id.as_bytes();
 
         let conn = self.conn.as_mut().unwrap();
-        let conn: &mut dyn Read = match conn {
-            Stream::Tcp(tcp_stream) => {
-                send(tcp_stream, b"PSYNC", &[repl_id, repl_offset])?;
+        send(conn, b"PSYNC", &[repl_id, repl_offset])?;
 
-                tcp_stream
+        let response = conn.decode_resp()?;
+        let next_step = match &response {
+            Resp::String(resp) => {
+                info!("{}", resp);
+                psync_next_step(resp, &mut self.config)?
             }
-            #[cfg(feature = "tls")]
-            Stream::Tls(tls_stream) => {
-                send(tls_stream, b"PSYNC", &[repl_id, repl_offset])?;
-                tls_stream
+            // 不支持PSYNC，或者Redis暂时无法进行同步(-LOADING、-NOMASTERLINK)
+            Resp::Error(err) => {
+                warn!("{}", err);
+                psync_next_step(err, &mut self.config)?
             }
+            _ => None,
         };
-
-        match conn.decode_resp() {
-            Ok(response) => {
-                if let Resp::String(resp) = &response {
-                    info!("{}", resp);
-                    if resp.starts_with("FULLRESYNC") {
-                        let mut iter = resp.split_whitespace();
-                        if let Some(repl_id) = iter.nth(1) {
-                            self.config.repl_id = repl_id.to_owned();
-                        } else {
-                            panic!("Expect replication id, but got None");
-                        }
-                        if let Some(repl_offset) = iter.next() {
-                            self.config.repl_offset = repl_offset.parse::<i64>().unwrap();
-                        } else {
-                            panic!("Expect replication offset, but got None");
-                        }
-                        info!("等待Redis dump完成...");
-                        if let Type::BulkString = conn.decode_type()? {
-                            let reply = conn.decode_string()?;
-                            if reply.starts_with("EOF") {
-                                return Ok((NextStep::FullSync, -1));
-                            } else {
-                                let length = reply.parse::<i64>().unwrap();
-                                return Ok((NextStep::FullSync, length));
-                            }
-                        } else {
-                            panic!("Expect BulkString response");
-                        }
-                    } else if resp.starts_with("CONTINUE") {
-                        let mut iter = resp.split_whitespace();
-                        if let Some(repl_id) = iter.nth(1) {
-                            if !repl_id.eq(&self.config.repl_id) {
-                                self.config.repl_id = repl_id.to_owned();
-                            }
-                        }
-                        return Ok((NextStep::PartialResync, -1));
-                    } else if resp.starts_with("NOMASTERLINK") {
-                        return Ok((NextStep::Wait, -1));
-                    } else if resp.starts_with("LOADING") {
-                        return Ok((NextStep::Wait, -1));
-                    }
-                }
-                panic!("Unexpected Response: {:?}", response);
-            }
-            Err(error) => {
-                if error.to_string().eq("ERR unknown command 'PSYNC'") {
-                    return Ok((NextStep::ChangeMode, -1));
+        match next_step {
+            Some(NextStep::FullSync) => {
+                info!("等待Redis dump完成...");
+                if let Type::BulkString = conn.decode_type()? {
+                    let reply = conn.decode_string()?;
+                    Ok((NextStep::FullSync, Some(RdbSize::parse(&reply)?)))
                 } else {
-                    return Err(error);
+                    Err(Error::new(ErrorKind::InvalidData, "Expect BulkString response"))
                 }
             }
+            Some(next_step) => Ok((next_step, None)),
+            None => Err(Error::new(
+                ErrorKind::InvalidData,
+                format!("Unexpected Response: {:?}", response),
+            )),
         }
     }
 
-    fn sync(&mut self) -> Result<i64> {
+    fn sync(&mut self) -> Result<RdbSize> {
         let conn = self.conn.as_mut().unwrap();
-        let conn: &mut dyn Read = match conn {
-            Stream::Tcp(tcp_stream) => {
-                send(tcp_stream, b"SYNC", &vec![])?;
-                tcp_stream
-            }
-            #[cfg(feature = "tls")]
-            Stream::Tls(tls_stream) => {
-                send(tls_stream, b"SYNC", &vec![])?;
-                tls_stream
-            }
-        };
+        send(conn, b"SYNC", &[])?;
         if let Type::BulkString = conn.decode_type()? {
             if let Resp::Int(length) = conn.decode_int()? {
-                return Ok(length);
+                Ok(RdbSize::Length(length))
             } else {
-                panic!("Expect int response")
+                Err(Error::new(ErrorKind::InvalidData, "Expect int response"))
             }
         } else {
-            panic!("Expect BulkString response");
+            Err(Error::new(ErrorKind::InvalidData, "Expect BulkString response"))
         }
     }
 
     /// 开启心跳
-    fn start_heartbeat(&mut self, mode: &Mode) {
+    fn start_heartbeat(&mut self, mode: &Mode) -> Result<()> {
         if !self.is_running() {
-            return;
+            return Ok(());
         }
         if let Mode::Sync = mode {
-            return;
-        }
-        #[cfg(feature = "tls")]
-        if self.config.is_tls_enabled {
-            return;
+            return Ok(());
         }
-        let conn = self.conn.as_ref().unwrap();
-        let conn = match conn {
-            Stream::Tcp(tcp_stream) => tcp_stream,
-            #[cfg(feature = "tls")]
-            Stream::Tls(_) => panic!("Expect TcpStream"),
+        // PSYNC之后offset可能已被更新，心跳需从最新的offset开始
+        self.repl_offset.store(self.config.repl_offset, Ordering::SeqCst);
+        let writer = match self.conn.take().unwrap() {
+            // TLS连接无法clone出可独立写入的连接，整个连接交由心跳线程与读取线程共享
+            #[cfg(any(feature = "tls", feature = "tls-rustls"))]
+            Stream::Tls(tls_stream) => {
+                // 读取时使用较短的超时时间轮询，让心跳线程能够及时拿到锁
+                let poll = self
+                    .config
+                    .heartbeat_interval
+                    .clamp(Duration::from_millis(1), Duration::from_millis(100));
+                let poll = self.config.read_timeout.map_or(poll, |timeout| timeout.min(poll));
+                tls_stream.get_ref().set_read_timeout(Some(poll))?;
+                Arc::new(Mutex::new(Stream::Tls(tls_stream)))
+            }
+            conn => {
+                let socket = conn.socket();
+                self.conn = Some(conn);
+                Arc::new(Mutex::new(socket?))
+            }
         };
-        let mut conn_clone = conn.try_clone().unwrap();
+        self.ack_writer = Some(Arc::clone(&writer));
         info!("Start heartbeat");
         let repl_offset = Arc::clone(&self.repl_offset);
+        let interval = self.config.heartbeat_interval;
+        let handle = self
+            .thread_pool
+            .execute_with_fixed_delay(Duration::from_secs(0), interval, move || {
+                let offset = repl_offset.load(Ordering::Relaxed);
+                if let Err(error) = send_ack(&mut *writer.lock().unwrap(), offset) {
+                    error!("heartbeat error: {}", error);
+                }
+            });
+
+        self.heartbeat_thread = HeartbeatWorker { handle: Some(handle) };
+        Ok(())
+    }
+
+    /// 开启复制延迟的监控，通过另外一个连接定时执行`INFO replication`
+    ///
+    /// 查询在单独的线程中进行，连接Redis或者LagHandler处理缓慢都不会推迟心跳
+    fn start_lag_monitor(&mut self, mode: &Mode) {
+        let (interval, thread_pool) = match (self.lag_interval, &self.lag_thread_pool) {
+            (Some(interval), Some(thread_pool)) if self.is_running() => (interval, thread_pool),
+            _ => return,
+        };
+        // SYNC模式下没有offset
+        if let Mode::Sync = mode {
+            return;
+        }
+        let mut config = self.config.clone();
+        // 连接及读写都不超过interval，避免一次查询无限期地阻塞之后的查询
+        let timeout = Some(config.read_timeout.map_or(interval, |timeout| timeout.min(interval)));
+        config.read_timeout = timeout;
+        config.write_timeout = timeout;
+        let repl_offset = Arc::clone(&self.repl_offset);
+        let latest = Arc::clone(&self.lag_monitor.latest);
+        let lag_handler = self.lag_handler.clone();
+        let mut conn = None;
+        let mut tracker = LagTracker::default();
+        info!("Start lag monitor");
+        let handle = thread_pool.execute_with_fixed_delay(Duration::from_secs(0), interval, move || {
+            match query_master_offset(&mut conn, &config, interval) {
+                Ok(master_offset) => {
+                    let applied_offset = repl_offset.load(Ordering::Relaxed);
+                    let lag = tracker.update(Instant::now(), master_offset, applied_offset);
+                    *latest.lock().unwrap() = Some(lag);
+                    if let Some(handler) = &lag_handler {
+                        handler.lock().unwrap().handle(&lag);
+                    }
+                }
+                Err(error) => {
+                    warn!("query replication lag failed: {}", error);
+                    conn = None;
+                }
+            }
+        });
+        self.lag_thread = HeartbeatWorker { handle: Some(handle) };
+    }
+
+    /// 停止复制延迟的监控
+    fn stop_lag_monitor(&mut self) {
+        if let Some(handle) = self.lag_thread.handle.take() {
+            info!("Cancel lag monitor");
+            handle.cancel();
+        }
+    }
+
+    /// 每秒调用一次`AofWriter::tick`，`FsyncPolicy::EverySec`的fsync不依赖于下一条命令的写入
+    fn start_aof_sync(&mut self) {
+        let aof_writer = match &self.aof_writer {
+            Some(aof_writer) => Arc::clone(aof_writer),
+            None => return,
+        };
         let handle =
             self.thread_pool
-                .execute_with_fixed_delay(Duration::from_secs(0), Duration::from_secs(1), move || {
-                    let offset_str = repl_offset.load(Ordering::Relaxed).to_string();
-                    let offset_bytes = offset_str.as_bytes();
-                    if let Err(error) = send(&mut conn_clone, b"REPLCONF", &[b"ACK", offset_bytes]) {
-                        error!("heartbeat error: {}", error);
+                .execute_with_fixed_delay(Duration::from_secs(1), Duration::from_secs(1), move || {
+                    if let Err(error) = aof_writer.lock().unwrap().tick() {
+                        warn!("sync aof failed: {}", error);
                     }
                 });
+        self.aof_sync_thread = HeartbeatWorker { handle: Some(handle) };
+    }
 
-        self.heartbeat_thread = HeartbeatWorker { handle: Some(handle) };
+    /// 停止AOF的定时fsync
+    fn stop_aof_sync(&mut self) {
+        if let Some(handle) = self.aof_sync_thread.handle.take() {
+            handle.cancel();
+        }
     }
 
     fn receive_aof(&mut self, mode: &Mode) -> Result<()> {
-        let mut handler = self.event_handler.as_ref().borrow_mut();
-
-        let __conn = self.conn.as_mut().unwrap();
-        match __conn {
-            Stream::Tcp(tcp_stream) => {
-                let mut reader = io::CountReader::new(tcp_stream);
-
-                while self.running.load(Ordering::Relaxed) {
-                    reader.mark();
-                    if let Resp::Array(array) = reader.decode_resp()? {
-                        let size = reader.reset()?;
-                        let mut vec = Vec::with_capacity(array.len());
-                        for x in array {
-                            if let Resp::BulkBytes(bytes) = x {
-                                vec.push(bytes);
-                            } else {
-                                panic!("Expected BulkString response");
-                            }
-                        }
-                        cmd::parse(vec, handler.deref_mut());
-                        if let Mode::PSync = mode {
-                            self.config.repl_offset += size;
-                            self.repl_offset.store(self.config.repl_offset, Ordering::SeqCst);
-                        }
+        let mut handler = self.event_handler.lock().unwrap();
+        let mut filtered;
+        let handler: &mut dyn EventHandler = match self.filter.as_mut() {
+            Some(filter) => {
+                filtered = FilteredHandler::new(handler.deref_mut(), filter);
+                &mut filtered
+            }
+            None => handler.deref_mut(),
+        };
+        let mut handler = MeteredHandler::new(handler, &self.metrics);
+
+        let mut shared;
+        let input: &mut dyn ReadWrite = match self.conn.as_mut() {
+            Some(conn) => conn,
+            None => {
+                shared = SharedStream::new(Arc::clone(self.ack_writer.as_ref().unwrap()), self.config.read_timeout);
+                &mut shared
+            }
+        };
+        let input = MeteredStream::new(input, &self.metrics);
+        let input = io::PrefixStream::new(std::mem::take(&mut self.buffered), input);
+        let mut reader = io::CountReader::new(input);
+        if self.aof_writer.is_some() {
+            reader.capture();
+        }
+        let mut transaction = cmd::TransactionBuffer::default();
+
+        while self.running.load(Ordering::Relaxed) {
+            reader.mark();
+            if let Resp::Array(array) = reader.decode_resp()? {
+                let size = reader.reset()?;
+                let mut vec = Vec::with_capacity(array.len());
+                for x in array {
+                    if let Resp::BulkBytes(bytes) = x {
+                        vec.push(bytes);
                     } else {
-                        panic!("Expected array response");
+                        return Err(Error::new(ErrorKind::InvalidData, "Expected BulkString response"));
                     }
                 }
-            }
-            #[cfg(feature = "tls")]
-            Stream::Tls(tls_stream) => {
-                let mut timer = Instant::now();
-                let one_sec = Duration::from_secs(1);
-
-                while self.running.load(Ordering::Relaxed) {
-                    {
-                        let mut reader = io::CountReader::new(tls_stream);
-                        reader.mark();
-                        if let Resp::Array(array) = reader.decode_resp()? {
-                            let size = reader.reset()?;
-                            let mut vec = Vec::with_capacity(array.len());
-                            for x in array {
-                                if let Resp::BulkBytes(bytes) = x {
-                                    vec.push(bytes);
-                                } else {
-                                    panic!("Expected BulkString response");
-                                }
-                            }
-                            cmd::parse(vec, handler.deref_mut());
-                            self.config.repl_offset += size;
-                        } else {
-                            panic!("Expected array response");
-                        }
+                let getack = is_getack(&vec);
+                if getack && let Mode::PSync = mode {
+                    // offset不包含GETACK本身，与Redis的行为一致
+                    match &self.ack_writer {
+                        Some(writer) => send_ack(&mut *writer.lock().unwrap(), self.config.repl_offset)?,
+                        None => send_ack(reader.get_mut(), self.config.repl_offset)?,
                     }
-
-                    let elapsed = timer.elapsed();
-                    if elapsed.ge(&one_sec) {
-                        let offset_str = self.config.repl_offset.to_string();
-                        let offset_bytes = offset_str.as_bytes();
-                        if let Err(error) = send(tls_stream, b"REPLCONF", &[b"ACK", offset_bytes]) {
-                            error!("heartbeat error: {}", error);
-                            break;
+                }
+                if let Some(aof_writer) = &self.aof_writer {
+                    let mut aof_writer = aof_writer.lock().unwrap();
+                    let offset = self.config.repl_offset + transaction.pending_size();
+                    // 事务未结束时不滚动文件，避免MULTI与EXEC被写入不同的文件
+                    aof_writer.defer_rotation(transaction.is_open());
+                    aof_writer.append(&self.config.repl_id, offset, reader.captured())?;
+                }
+                let size = if getack && !self.config.is_forward_getack {
+                    size
+                } else if self.config.is_group_transaction {
+                    match transaction.push(vec, size) {
+                        cmd::Buffered::Command(data, size) => {
+                            cmd::parse(data, &mut handler);
+                            size
                         }
-                        timer = Instant::now();
+                        cmd::Buffered::Transaction(commands, size) => {
+                            cmd::parse_transaction(&commands, &mut handler);
+                            size
+                        }
+                        cmd::Buffered::Flushed(commands, size) => {
+                            commands.into_iter().for_each(|data| cmd::parse(data, &mut handler));
+                            size
+                        }
+                        cmd::Buffered::Pending => 0,
                     }
+                } else {
+                    if self.aof_writer.is_some() {
+                        transaction.track(&vec);
+                    }
+                    cmd::parse(vec, &mut handler);
+                    size
+                };
+                if let Mode::PSync = mode {
+                    self.config.repl_offset += size;
+                    self.repl_offset.store(self.config.repl_offset, Ordering::SeqCst);
                 }
+            } else {
+                return Err(Error::new(ErrorKind::InvalidData, "Expected array response"));
             }
-        };
+        }
         Ok(())
     }
 
+    /// 停止心跳
+    fn stop_heartbeat(&mut self) {
+        if let Some(handle) = self.heartbeat_thread.handle.take() {
+            info!("Cancel heartbeat");
+            handle.cancel();
+        }
+    }
+
+    /// 将最终的offset发送给Redis
+    fn send_final_ack(&mut self) {
+        let offset = self.config.repl_offset;
+        let result = match &self.ack_writer {
+            Some(writer) => send_ack(&mut *writer.lock().unwrap(), offset),
+            None => send_ack(self.conn.as_mut().unwrap(), offset),
+        };
+        if let Err(error) = result {
+            warn!("send final ack failed: {}", error);
+        }
+    }
+
+    /// 关闭与Redis的连接
+    fn close(&mut self) {
+        if let Some(socket) = self.socket.lock().unwrap().take() {
+            let _ = socket.shutdown(Shutdown::Both);
+        }
+        self.conn = None;
+        self.ack_writer = None;
+        if let Some(aof_writer) = &self.aof_writer
+            && let Err(error) = aof_writer.lock().unwrap().sync()
+        {
+            warn!("sync aof failed: {}", error);
+        }
+    }
+
     /// 获取当前运行的状态，若为false，程序将有序退出
     fn is_running(&self) -> bool {
         self.running.load(Ordering::Relaxed)
     }
-}
 
-impl RedisListener for Listener {
-    /// 程序运行的整体逻辑都在这个方法里面实现
-    ///
-    /// 具体的细节体现在各个方法内
-    fn start(&mut self) -> Result<()> {
+    /// 等待一段时间，期间若被中断则提前返回
+    fn wait(&self, duration: Duration) {
+        let start = Instant::now();
+        while self.is_running() && start.elapsed() < duration {
+            sleep(Duration::from_millis(100));
+        }
+    }
+
+    fn run(&mut self) -> Result<()> {
         self.connect()?;
         self.auth()?;
         self.send_replica_info()?;
@@ -445,7 +531,7 @@ impl RedisListener for Listener {
             match mode {
                 Mode::Wait => {
                     if self.is_running() {
-                        sleep(Duration::from_secs(5));
+                        self.wait(Duration::from_secs(5));
                     } else {
                         return Ok(());
                     }
@@ -456,18 +542,65 @@ impl RedisListener for Listener {
         if !self.config.is_aof {
             Ok(())
         } else {
-            self.start_heartbeat(&mode);
-            self.receive_aof(&mode)?;
-            Ok(())
+            self.start_heartbeat(&mode)?;
+            self.start_lag_monitor(&mode);
+            self.start_aof_sync();
+            let result = self.receive_aof(&mode);
+            self.stop_aof_sync();
+            self.stop_lag_monitor();
+            self.stop_heartbeat();
+            if let Mode::PSync = mode {
+                self.send_final_ack();
+            }
+            result
+        }
+    }
+}
+
+impl RedisListener for Listener {
+    /// 程序运行的整体逻辑都在这个方法里面实现
+    ///
+    /// 具体的细节体现在各个方法内
+    fn start(&mut self) -> Result<()> {
+        let result = self.run();
+        self.close();
+        match result {
+            Err(error) if !self.is_running() => {
+                info!("Listener shutdown: {}", error);
+                Ok(())
+            }
+            result => result,
         }
     }
 }
 
 impl Drop for Listener {
     fn drop(&mut self) {
-        if let Some(handle) = &self.heartbeat_thread.handle {
-            info!("Cancel heartbeat");
-            handle.cancel();
+        self.stop_aof_sync();
+        self.stop_lag_monitor();
+        self.stop_heartbeat();
+    }
+}
+
+/// 用于中断[`Listener`]的运行
+///
+/// 除了设置控制变量之外，还会中断阻塞中的读取，即使Redis一直没有写入，`start()`也能及时返回。
+/// 若已进入AOF阶段，在返回之前会将最终的offset通过`REPLCONF ACK`发送给Redis
+///
+/// [`Listener`]: struct.Listener.html
+#[derive(Clone)]
+pub struct ShutdownHandle {
+    running: Arc<AtomicBool>,
+    socket: Arc<Mutex<Option<Stream>>>,
+}
+
+impl ShutdownHandle {
+    pub fn shutdown(&self) {
+        self.running.store(false, Ordering::SeqCst);
+        if let Some(socket) = self.socket.lock().unwrap().as_ref()
+            && let Err(error) = socket.shutdown(Shutdown::Read)
+        {
+            warn!("shutdown failed: {}", error);
         }
     }
 }
@@ -476,26 +609,281 @@ struct HeartbeatWorker {
     handle: Option<JobHandle>,
 }
 
-enum NextStep {
+pub(crate) enum NextStep {
     FullSync,
     PartialResync,
     ChangeMode,
     Wait,
 }
 
-enum Mode {
+pub(crate) enum Mode {
     PSync,
     Sync,
     Wait,
 }
 
+/// 检查握手阶段Redis的响应，认证、权限相关的错误将中止程序
+pub(crate) fn check_reply(resp: Resp) -> Result<()> {
+    match resp {
+        Resp::String(str) => info!("{}", str),
+        Resp::Error(err) => {
+            warn!("{}", &err);
+            if (err.contains("NOAUTH") || err.contains("NOPERM"))
+                && !err.contains("no password")
+                && !err.contains("Unrecognized REPLCONF option")
+            {
+                return Err(Error::new(ErrorKind::InvalidData, err));
+            }
+        }
+        resp => {
+            return Err(Error::new(
+                ErrorKind::InvalidData,
+                format!("Unexpected response: {:?}", resp),
+            ));
+        }
+    }
+    Ok(())
+}
+
+/// 如果有设置密码，将尝试使用此密码进行认证，开启了RESP3则通过`HELLO 3`认证
+fn auth(conn: &mut Stream, config: &Config) -> Result<()> {
+    if config.is_resp3 {
+        info!("HELLO 3");
+        send(conn, b"HELLO", &hello_args(config))?;
+        return check_hello(conn.decode_resp()?);
+    }
+    if !config.password.is_empty() {
+        let mut args = Vec::with_capacity(2);
+        if !config.username.is_empty() {
+            args.push(config.username.as_bytes());
+        }
+        args.push(config.password.as_bytes());
+        send(conn, b"AUTH", &args)?;
+        conn.decode_resp()?;
+    }
+    Ok(())
+}
+
+/// 通过`INFO replication`获取Redis当前的`master_repl_offset`，连接断开时重新建立连接
+fn query_master_offset(conn: &mut Option<Stream>, config: &Config, connect_timeout: Duration) -> Result<i64> {
+    if conn.is_none() {
+        let mut stream = Stream::connect_timeout(config, connect_timeout)?;
+        auth(&mut stream, config)?;
+        *conn = Some(stream);
+    }
+    let stream = conn.as_mut().unwrap();
+    send(stream, b"INFO", &[b"replication"])?;
+    let info = match stream.decode_resp()? {
+        Resp::BulkBytes(info) => info,
+        Resp::VerbatimString(_, info) => info,
+        Resp::Error(err) => return Err(Error::new(ErrorKind::InvalidData, err)),
+        resp => {
+            return Err(Error::new(
+                ErrorKind::InvalidData,
+                format!("Unexpected INFO response: {:?}", resp),
+            ));
+        }
+    };
+    lag::parse_master_offset(&info).ok_or_else(|| Error::new(ErrorKind::InvalidData, "master_repl_offset not found"))
+}
+
+/// 依次尝试`addr`解析出的各个地址，每个地址的连接时间不超过`timeout`
+fn connect_tcp_timeout(addr: &str, timeout: Duration) -> Result<TcpStream> {
+    let mut last_error = Error::new(ErrorKind::InvalidInput, format!("could not resolve {}", addr));
+    for socket_addr in addr.to_socket_addrs()? {
+        match TcpStream::connect_timeout(&socket_addr, timeout) {
+            Ok(stream) => return Ok(stream),
+            Err(error) => last_error = error,
+        }
+    }
+    Err(last_error)
+}
+
+/// 发送`REPLCONF ACK <offset>`
+fn send_ack<T: Write + ?Sized>(output: &mut T, offset: i64) -> Result<()> {
+    let offset_str = offset.to_string();
+    send(output, b"REPLCONF", &[b"ACK", offset_str.as_bytes()])
+}
+
+/// `HELLO 3`的参数，若设置了密码则同时进行认证
+pub(crate) fn hello_args(config: &Config) -> Vec<&[u8]> {
+    let mut args: Vec<&[u8]> = vec![b"3"];
+    if !config.password.is_empty() {
+        let username = if config.username.is_empty() {
+            "default"
+        } else {
+            &config.username
+        };
+        args.extend_from_slice(&[b"AUTH", username.as_bytes(), config.password.as_bytes()]);
+    }
+    args
+}
+
+/// 检查`HELLO 3`的响应，Redis不支持RESP3或认证失败时返回错误
+pub(crate) fn check_hello(resp: Resp) -> Result<()> {
+    match resp {
+        Resp::Map(_) => Ok(()),
+        Resp::Error(err) => Err(Error::new(ErrorKind::InvalidData, err)),
+        resp => Err(Error::new(
+            ErrorKind::InvalidData,
+            format!("Unexpected HELLO response: {:?}", resp),
+        )),
+    }
+}
+
+/// 根据PSYNC的响应决定下一步的操作，并更新replication id及offset
+///
+/// 无法识别的响应返回None，`FULLRESYNC`中缺少replication id或offset时返回错误
+pub(crate) fn psync_next_step(resp: &str, config: &mut Config) -> Result<Option<NextStep>> {
+    if resp.starts_with("FULLRESYNC") {
+        let mut iter = resp.split_whitespace();
+        let (repl_id, repl_offset) = match (iter.nth(1), iter.next().map(str::parse::<i64>)) {
+            (Some(repl_id), Some(Ok(repl_offset))) => (repl_id, repl_offset),
+            _ => {
+                return Err(Error::new(
+                    ErrorKind::InvalidData,
+                    format!("Invalid FULLRESYNC response: {}", resp),
+                ));
+            }
+        };
+        config.repl_id = repl_id.to_owned();
+        config.repl_offset = repl_offset;
+        Ok(Some(NextStep::FullSync))
+    } else if resp.starts_with("CONTINUE") {
+        let mut iter = resp.split_whitespace();
+        if let Some(repl_id) = iter.nth(1)
+            && !repl_id.eq(&config.repl_id)
+        {
+            config.repl_id = repl_id.to_owned();
+        }
+        Ok(Some(NextStep::PartialResync))
+    } else if resp.starts_with("NOMASTERLINK") || resp.starts_with("LOADING") {
+        Ok(Some(NextStep::Wait))
+    } else if resp.starts_with("ERR unknown command") && resp.to_ascii_uppercase().contains("PSYNC") {
+        Ok(Some(NextStep::ChangeMode))
+    } else {
+        Ok(None)
+    }
+}
+
+/// 是否为Redis发送的`REPLCONF GETACK`
+pub(crate) fn is_getack(data: &[Vec<u8>]) -> bool {
+    data.len() >= 2 && data[0].eq_ignore_ascii_case(b"REPLCONF") && data[1].eq_ignore_ascii_case(b"GETACK")
+}
+
+/// FULLRESYNC之后`$`所携带的RDB大小
+pub(crate) enum RdbSize {
+    /// RDB的字节数
+    Length(i64),
+    /// disk-less replication(`$EOF:<mark>`)，RDB的大小未知，RDB之后跟随着40字节的EOF标记
+    EofMark(Vec<u8>),
+}
+
+impl RdbSize {
+    pub(crate) fn parse(reply: &str) -> Result<RdbSize> {
+        match reply.strip_prefix("EOF:") {
+            Some(mark) => Ok(RdbSize::EofMark(mark.as_bytes().to_vec())),
+            None => match reply.parse::<i64>() {
+                Ok(length) if length >= 0 => Ok(RdbSize::Length(length)),
+                _ => Err(Error::new(
+                    ErrorKind::InvalidData,
+                    format!("Invalid RDB size: {}", reply),
+                )),
+            },
+        }
+    }
+}
+
+/// 接收并处理RDB数据
+///
+/// disk-less replication时，RDB之后的EOF标记需与Redis所声明的一致，否则返回错误。
+/// 若设置了`Config::rdb_backup_path`或`rdb_writer`，接收到的RDB会被原样写入其中
+pub(crate) fn receive_rdb(
+    input: &mut dyn BufRead, size: &RdbSize, config: &Config, rdb_writer: Option<&Arc<Mutex<dyn Write + Send>>>,
+    rdb_parser: &mut dyn RDBParser, event_handler: &mut dyn EventHandler,
+) -> Result<()> {
+    let mut backup = io::RdbBackup::new(config.rdb_backup_path.as_deref(), rdb_writer)?;
+    let completed = if !config.is_discard_rdb
+        && let Some(dir) = &config.rdb_spool_dir
+    {
+        spool_rdb(input, size, dir, &mut backup, rdb_parser, event_handler)?
+    } else {
+        match size {
+            RdbSize::Length(length) if config.is_discard_rdb => {
+                info!("跳过RDB不进行处理");
+                std::io::copy(&mut Read::take(&mut *input, *length as u64), &mut backup)? == *length as u64
+            }
+            RdbSize::Length(length) => {
+                let mut input = io::TeeReader::new(input, &mut backup);
+                rdb_parser.parse(&mut input, *length, event_handler)?;
+                input.count() == *length as u64
+            }
+            RdbSize::EofMark(mark) if config.is_discard_rdb => {
+                info!("跳过RDB不进行处理");
+                io::copy_until(input, mark, &mut backup)?;
+                true
+            }
+            RdbSize::EofMark(mark) => {
+                rdb_parser.parse(&mut io::TeeReader::new(&mut *input, &mut backup), -1, event_handler)?;
+                let mut trailer = vec![0; mark.len()];
+                input.read_exact(&mut trailer)?;
+                if !trailer.eq(mark) {
+                    return Err(Error::new(
+                        ErrorKind::InvalidData,
+                        format!(
+                            "EOF mark mismatch, expect: {}, got: {}",
+                            String::from_utf8_lossy(mark),
+                            String::from_utf8_lossy(&trailer)
+                        ),
+                    ));
+                }
+                true
+            }
+        }
+    };
+    // RDB未完整读取(如被中止)时，不保留备份
+    if completed { backup.commit() } else { Ok(()) }
+}
+
+/// 先将RDB完整地写入`dir`下的临时文件，再从文件中解析，解析完成后删除文件
+fn spool_rdb(
+    input: &mut dyn BufRead, size: &RdbSize, dir: &str, backup: &mut dyn Write, rdb_parser: &mut dyn RDBParser,
+    event_handler: &mut dyn EventHandler,
+) -> Result<bool> {
+    let mut spool = io::SpoolFile::create(dir)?;
+    info!("接收RDB至{}", spool.path().display());
+    let written = match size {
+        RdbSize::Length(length) => {
+            let written = std::io::copy(&mut Read::take(&mut *input, *length as u64), spool.file())?;
+            if written < *length as u64 {
+                return Err(Error::new(ErrorKind::UnexpectedEof, "RDB is incomplete"));
+            }
+            written
+        }
+        RdbSize::EofMark(mark) => io::copy_until(input, mark, spool.file())?,
+    };
+    info!("RDB接收完毕, size: {}bytes, 开始解析", written);
+    let file = spool.file();
+    file.flush()?;
+    file.seek(SeekFrom::Start(0))?;
+    let mut input = io::TeeReader::new(BufReader::new(file), backup);
+    rdb_parser.parse(&mut input, written as i64, event_handler)?;
+    Ok(input.count() == written)
+}
+
 pub struct Builder {
     pub config: Option<Config>,
-    pub rdb_parser: Option<Rc<RefCell<dyn RDBParser>>>,
-    pub event_handler: Option<Rc<RefCell<dyn EventHandler>>>,
-    pub module_parser: Option<Rc<RefCell<dyn ModuleParser>>>,
+    pub rdb_parser: Option<Arc<Mutex<dyn RDBParser + Send>>>,
+    pub event_handler: Option<Arc<Mutex<dyn EventHandler + Send>>>,
+    pub module_parser: Option<Arc<Mutex<dyn ModuleParser + Send>>>,
     pub control_flag: Option<Arc<AtomicBool>>,
     pub thread_pool: Option<Arc<ScheduledThreadPool>>,
+    pub rdb_writer: Option<Arc<Mutex<dyn Write + Send>>>,
+    pub aof_writer: Option<Arc<Mutex<AofWriter>>>,
+    pub lag_interval: Option<Duration>,
+    pub lag_handler: Option<Arc<Mutex<dyn LagHandler + Send>>>,
+    pub metrics: Option<Metrics>,
+    pub filter: Option<Filter>,
 }
 
 impl Builder {
@@ -507,6 +895,12 @@ impl Builder {
             module_parser: None,
             control_flag: None,
             thread_pool: None,
+            rdb_writer: None,
+            aof_writer: None,
+            lag_interval: None,
+            lag_handler: None,
+            metrics: None,
+            filter: None,
         }
     }
 
@@ -514,15 +908,15 @@ impl Builder {
         self.config = Some(config);
     }
 
-    pub fn with_rdb_parser(&mut self, parser: Rc<RefCell<dyn RDBParser>>) {
+    pub fn with_rdb_parser(&mut self, parser: Arc<Mutex<dyn RDBParser + Send>>) {
         self.rdb_parser = Some(parser);
     }
 
-    pub fn with_event_handler(&mut self, handler: Rc<RefCell<dyn EventHandler>>) {
+    pub fn with_event_handler(&mut self, handler: Arc<Mutex<dyn EventHandler + Send>>) {
         self.event_handler = Some(handler);
     }
 
-    pub fn with_module_parser(&mut self, parser: Rc<RefCell<dyn ModuleParser>>) {
+    pub fn with_module_parser(&mut self, parser: Arc<Mutex<dyn ModuleParser + Send>>) {
         self.module_parser = Some(parser);
     }
 
@@ -534,11 +928,45 @@ impl Builder {
         self.thread_pool = Option::Some(thread_pool);
     }
 
-    pub fn build(&mut self) -> Listener {
+    /// 全量同步时，将接收到的RDB原样写入`writer`
+    pub fn with_rdb_writer(&mut self, writer: Arc<Mutex<dyn Write + Send>>) {
+        self.rdb_writer = Some(writer);
+    }
+
+    /// 将接收到的每一条命令原样写入AOF文件
+    pub fn with_aof_writer(&mut self, writer: Arc<Mutex<AofWriter>>) {
+        self.aof_writer = Some(writer);
+    }
+
+    /// 每隔`interval`通过另外一个连接执行`INFO replication`，计算复制延迟
+    pub fn with_lag_monitor(&mut self, interval: Duration) {
+        self.lag_interval = Some(interval);
+    }
+
+    /// 每次计算出复制延迟之后调用`handler`，需同时设置`with_lag_monitor`
+    pub fn with_lag_handler(&mut self, handler: Arc<Mutex<dyn LagHandler + Send>>) {
+        self.lag_handler = Some(handler);
+    }
+
+    /// 使用指定的`Metrics`，多个Listener可共用同一个`Metrics`
+    pub fn with_metrics(&mut self, metrics: Metrics) {
+        self.metrics = Some(metrics);
+    }
+
+    /// 只将满足`filter`条件的事件交给EventHandler
+    pub fn with_filter(&mut self, filter: Filter) {
+        self.filter = Some(filter);
+    }
+
+    /// 创建`Listener`，缺少必需的参数或`Config`无效时返回错误
+    ///
+    /// 用于中断`start()`的`ShutdownHandle`需通过返回的`Listener::shutdown_handle()`获取
+    pub fn build(&mut self) -> Result<Listener> {
         let config = match &self.config {
             Some(c) => c,
-            None => panic!("Parameter Config is required"),
+            None => return Err(Error::new(ErrorKind::InvalidInput, "Parameter Config is required")),
         };
+        config.validate()?;
 
         let module_parser = match &self.module_parser {
             None => None,
@@ -546,20 +974,28 @@ impl Builder {
         };
 
         let running = match &self.control_flag {
-            None => panic!("Parameter Control_flag is required"),
+            None => {
+                return Err(Error::new(
+                    ErrorKind::InvalidInput,
+                    "Parameter Control_flag is required",
+                ));
+            }
             Some(flag) => flag.clone(),
         };
 
+        let metrics = self.metrics.clone().unwrap_or_default();
+
         let rdb_parser = match &self.rdb_parser {
-            None => Rc::new(RefCell::new(DefaultRDBParser {
+            None => Arc::new(Mutex::new(DefaultRDBParser {
                 running: Arc::clone(&running),
                 module_parser,
+                metrics: Some(metrics.clone()),
             })),
             Some(parser) => parser.clone(),
         };
 
         let event_handler = match &self.event_handler {
-            None => Rc::new(RefCell::new(NoOpEventHandler {})),
+            None => Arc::new(Mutex::new(NoOpEventHandler {})),
             Some(handler) => handler.clone(),
         };
 
@@ -573,7 +1009,15 @@ impl Builder {
             Some(pool) => Arc::clone(pool),
         };
 
-        Listener {
+        // 复制延迟的监控使用单独的线程，不与心跳共用
+        let lag_thread_pool = self.lag_interval.map(|_| {
+            ScheduledThreadPool::builder()
+                .num_threads(1)
+                .thread_name_pattern("lag-monitor-thread-{}")
+                .build()
+        });
+
+        Ok(Listener {
             config: config.clone(),
             conn: None,
             rdb_parser,
@@ -584,12 +1028,198 @@ impl Builder {
             local_port: None,
             thread_pool,
             repl_offset: Arc::new(AtomicI64::from(config.repl_offset)),
-        }
+            socket: Arc::new(Mutex::new(None)),
+            buffered: Vec::new(),
+            rdb_writer: self.rdb_writer.clone(),
+            aof_writer: self.aof_writer.clone(),
+            lag_interval: self.lag_interval,
+            lag_handler: self.lag_handler.clone(),
+            lag_monitor: LagMonitor::default(),
+            lag_thread_pool,
+            lag_thread: HeartbeatWorker { handle: None },
+            aof_sync_thread: HeartbeatWorker { handle: None },
+            metrics,
+            filter: self.filter.clone(),
+            ack_writer: None,
+        })
     }
 }
 
 enum Stream {
     Tcp(TcpStream),
-    #[cfg(feature = "tls")]
-    Tls(TlsStream<TcpStream>),
+    #[cfg(unix)]
+    Unix(UnixStream),
+    #[cfg(any(feature = "tls", feature = "tls-rustls"))]
+    Tls(TlsStream),
+}
+
+impl Stream {
+    /// 按照`config`连接Redis，若设置了`unix_socket_path`则通过unix socket连接
+    fn connect(config: &Config) -> Result<Stream> {
+        match &config.unix_socket_path {
+            Some(path) => Stream::connect_unix(config, path),
+            None => Stream::connect_tcp(config, None),
+        }
+    }
+
+    /// 同`connect`，但建立TCP连接的时间不超过`timeout`，unix socket不受此限制
+    fn connect_timeout(config: &Config, timeout: Duration) -> Result<Stream> {
+        match &config.unix_socket_path {
+            Some(path) => Stream::connect_unix(config, path),
+            None => Stream::connect_tcp(config, Some(timeout)),
+        }
+    }
+
+    fn connect_tcp(config: &Config, timeout: Option<Duration>) -> Result<Stream> {
+        let addr = format!("{}:{}", &config.host, config.port);
+        let stream = match timeout {
+            None => TcpStream::connect(&addr)?,
+            Some(timeout) => connect_tcp_timeout(&addr, timeout)?,
+        };
+        stream.set_read_timeout(config.read_timeout)?;
+        stream.set_write_timeout(config.write_timeout)?;
+
+        info!("Connected to server {}", &addr);
+
+        #[cfg(any(feature = "tls", feature = "tls-rustls"))]
+        if config.is_tls_enabled {
+            return Ok(Stream::Tls(tls::connect(config, stream)?));
+        }
+        Ok(Stream::Tcp(stream))
+    }
+
+    #[cfg(unix)]
+    fn connect_unix(config: &Config, path: &str) -> Result<Stream> {
+        #[cfg(any(feature = "tls", feature = "tls-rustls"))]
+        if config.is_tls_enabled {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                "TLS over unix socket is not supported",
+            ));
+        }
+        let stream = UnixStream::connect(path)?;
+        stream.set_read_timeout(config.read_timeout)?;
+        stream.set_write_timeout(config.write_timeout)?;
+
+        info!("Connected to server {}", path);
+        Ok(Stream::Unix(stream))
+    }
+
+    #[cfg(not(unix))]
+    fn connect_unix(_: &Config, _: &str) -> Result<Stream> {
+        Err(Error::new(
+            ErrorKind::Unsupported,
+            "unix socket is not supported on this platform",
+        ))
+    }
+
+    /// 本地的地址，unix socket没有本地的ip及端口
+    fn local_addr(&self) -> Result<Option<SocketAddr>> {
+        match self {
+            Stream::Tcp(tcp_stream) => Ok(Some(tcp_stream.local_addr()?)),
+            #[cfg(unix)]
+            Stream::Unix(_) => Ok(None),
+            #[cfg(any(feature = "tls", feature = "tls-rustls"))]
+            Stream::Tls(tls_stream) => Ok(Some(tls_stream.get_ref().local_addr()?)),
+        }
+    }
+
+    /// clone出底层的socket，用于心跳以及中断读取
+    ///
+    /// TLS连接返回的是底层的TCP连接，不能直接用于读写
+    fn socket(&self) -> Result<Stream> {
+        match self {
+            Stream::Tcp(tcp_stream) => Ok(Stream::Tcp(tcp_stream.try_clone()?)),
+            #[cfg(unix)]
+            Stream::Unix(unix_stream) => Ok(Stream::Unix(unix_stream.try_clone()?)),
+            #[cfg(any(feature = "tls", feature = "tls-rustls"))]
+            Stream::Tls(tls_stream) => Ok(Stream::Tcp(tls_stream.get_ref().try_clone()?)),
+        }
+    }
+
+    fn shutdown(&self, how: Shutdown) -> Result<()> {
+        match self {
+            Stream::Tcp(tcp_stream) => tcp_stream.shutdown(how),
+            #[cfg(unix)]
+            Stream::Unix(unix_stream) => unix_stream.shutdown(how),
+            #[cfg(any(feature = "tls", feature = "tls-rustls"))]
+            Stream::Tls(tls_stream) => tls_stream.get_ref().shutdown(how),
+        }
+    }
+}
+
+impl Read for Stream {
+    fn read(&mut self, buf: &mut [u8]) -> Result<usize> {
+        match self {
+            Stream::Tcp(tcp_stream) => tcp_stream.read(buf),
+            #[cfg(unix)]
+            Stream::Unix(unix_stream) => unix_stream.read(buf),
+            #[cfg(any(feature = "tls", feature = "tls-rustls"))]
+            Stream::Tls(tls_stream) => tls_stream.read(buf),
+        }
+    }
+}
+
+impl Write for Stream {
+    fn write(&mut self, buf: &[u8]) -> Result<usize> {
+        match self {
+            Stream::Tcp(tcp_stream) => tcp_stream.write(buf),
+            #[cfg(unix)]
+            Stream::Unix(unix_stream) => unix_stream.write(buf),
+            #[cfg(any(feature = "tls", feature = "tls-rustls"))]
+            Stream::Tls(tls_stream) => tls_stream.write(buf),
+        }
+    }
+
+    fn flush(&mut self) -> Result<()> {
+        match self {
+            Stream::Tcp(tcp_stream) => tcp_stream.flush(),
+            #[cfg(unix)]
+            Stream::Unix(unix_stream) => unix_stream.flush(),
+            #[cfg(any(feature = "tls", feature = "tls-rustls"))]
+            Stream::Tls(tls_stream) => tls_stream.flush(),
+        }
+    }
+}
+
+/// 与心跳线程共享的连接，仅在每次读取时持有锁
+///
+/// 连接的读取超时为较短的轮询间隔，超时后释放锁再重试，直到超过`read_timeout`
+struct SharedStream {
+    stream: Arc<Mutex<Stream>>,
+    read_timeout: Option<Duration>,
+}
+
+impl SharedStream {
+    fn new(stream: Arc<Mutex<Stream>>, read_timeout: Option<Duration>) -> SharedStream {
+        SharedStream { stream, read_timeout }
+    }
+}
+
+impl Write for SharedStream {
+    fn write(&mut self, buf: &[u8]) -> Result<usize> {
+        self.stream.lock().unwrap().write(buf)
+    }
+
+    fn flush(&mut self) -> Result<()> {
+        self.stream.lock().unwrap().flush()
+    }
+}
+
+impl Read for SharedStream {
+    fn read(&mut self, buf: &mut [u8]) -> Result<usize> {
+        let start = Instant::now();
+        loop {
+            match self.stream.lock().unwrap().read(buf) {
+                Err(error) if matches!(error.kind(), ErrorKind::WouldBlock | ErrorKind::TimedOut) => {
+                    if self.read_timeout.is_some_and(|timeout| start.elapsed() >= timeout) {
+                        return Err(error);
+                    }
+                }
+                result => return result,
+            }
+            // 锁已释放，稍作等待让心跳线程有机会写入
+            sleep(Duration::from_millis(1));
+        }
+    }
 }
@@ -0,0 +1,326 @@
+/*!
+内置的监控指标
+
+[`Listener`]运行时会统计各类事件的数量、接收到的字节数、RDB的解析进度、重连次数以及`EventHandler`的耗时，
+通过[`Metrics::snapshot`]获取当前的快照，[`MetricsSnapshot::to_prometheus`]可将其转换为Prometheus的文本格式。
+
+[`Listener`]: ../listener/struct.Listener.html
+[`Metrics::snapshot`]: struct.Metrics.html#method.snapshot
+[`MetricsSnapshot::to_prometheus`]: struct.MetricsSnapshot.html#method.to_prometheus
+*/
+use std::collections::BTreeMap;
+use std::fmt::{Display, Write as _};
+use std::io::{Read, Result, Write};
+use std::sync::atomic::{AtomicI64, AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use crate::rdb::Object;
+use crate::{Event, EventHandler};
+
+/// 监控指标，可clone之后在其他线程中获取快照
+#[derive(Clone, Default)]
+pub struct Metrics {
+    inner: Arc<Inner>,
+}
+
+#[derive(Default)]
+struct Inner {
+    commands: Mutex<BTreeMap<String, u64>>,
+    objects: Mutex<BTreeMap<&'static str, u64>>,
+    // 上一个被分批处理的key，同一个key的后续批次不再计数
+    last_key: Mutex<Option<(&'static str, isize, Vec<u8>)>>,
+    transactions: AtomicU64,
+    bytes_received: AtomicU64,
+    rdb_length: AtomicI64,
+    rdb_parsed: AtomicU64,
+    connections: AtomicU64,
+    handler_nanos: AtomicU64,
+}
+
+impl Metrics {
+    /// 获取当前的快照
+    pub fn snapshot(&self) -> MetricsSnapshot {
+        let inner = &self.inner;
+        let rdb_length = inner.rdb_length.load(Ordering::Relaxed);
+        MetricsSnapshot {
+            commands: inner.commands.lock().unwrap().clone(),
+            objects: (inner.objects.lock().unwrap().iter())
+                .map(|(name, count)| (name.to_string(), *count))
+                .collect(),
+            transactions: inner.transactions.load(Ordering::Relaxed),
+            bytes_received: inner.bytes_received.load(Ordering::Relaxed),
+            rdb_length: if rdb_length < 0 { None } else { Some(rdb_length as u64) },
+            rdb_parsed: inner.rdb_parsed.load(Ordering::Relaxed),
+            reconnects: inner.connections.load(Ordering::Relaxed).saturating_sub(1),
+            handler_time: Duration::from_nanos(inner.handler_nanos.load(Ordering::Relaxed)),
+        }
+    }
+
+    pub(crate) fn record_event(&self, event: &Event) {
+        match event {
+            Event::RDB(obj) => {
+                if let Object::BOR = obj {
+                    *self.inner.last_key.lock().unwrap() = None;
+                }
+                if let Some(name) = object_type(obj)
+                    && self.is_new_key(name, obj)
+                {
+                    *self.inner.objects.lock().unwrap().entry(name).or_insert(0) += 1;
+                }
+            }
+            Event::AOF(cmd) => {
+                let mut commands = self.inner.commands.lock().unwrap();
+                *commands.entry(cmd.name().to_owned()).or_insert(0) += 1;
+            }
+            Event::Transaction(cmds) => {
+                self.inner.transactions.fetch_add(1, Ordering::Relaxed);
+                let mut commands = self.inner.commands.lock().unwrap();
+                for cmd in cmds {
+                    *commands.entry(cmd.name().to_owned()).or_insert(0) += 1;
+                }
+            }
+        }
+    }
+
+    /// List、Set、SortedSet及Hash的元素较多时会分为多个批次，只有第一个批次才是一个新的key
+    fn is_new_key(&self, name: &'static str, obj: &Object) -> bool {
+        let (key, db) = match obj {
+            Object::List(list) => (list.key, list.meta.db),
+            Object::Set(set) => (set.key, set.meta.db),
+            Object::SortedSet(sorted_set) => (sorted_set.key, sorted_set.meta.db),
+            Object::Hash(hash) => (hash.key, hash.meta.db),
+            _ => return true,
+        };
+        let mut last_key = self.inner.last_key.lock().unwrap();
+        if let Some((last_name, last_db, last)) = last_key.as_ref()
+            && (*last_name, *last_db, last.as_slice()) == (name, db, key)
+        {
+            return false;
+        }
+        *last_key = Some((name, db, key.to_vec()));
+        true
+    }
+
+    pub(crate) fn add_handler_time(&self, elapsed: Duration) {
+        (self.inner.handler_nanos).fetch_add(elapsed.as_nanos() as u64, Ordering::Relaxed);
+    }
+
+    pub(crate) fn add_received(&self, len: usize) {
+        self.inner.bytes_received.fetch_add(len as u64, Ordering::Relaxed);
+    }
+
+    pub(crate) fn add_connection(&self) {
+        self.inner.connections.fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// 开始解析新的RDB，`length`为-1表示长度未知(disk-less replication)
+    pub(crate) fn start_rdb(&self, length: i64) {
+        self.inner.rdb_length.store(length, Ordering::Relaxed);
+        self.inner.rdb_parsed.store(0, Ordering::Relaxed);
+    }
+
+    pub(crate) fn add_rdb_parsed(&self, len: usize) {
+        self.inner.rdb_parsed.fetch_add(len as u64, Ordering::Relaxed);
+    }
+}
+
+/// 某一时刻的监控指标
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct MetricsSnapshot {
+    /// 各个命令的数量，事务中的命令也会被计入
+    pub commands: BTreeMap<String, u64>,
+    /// RDB中各类数据key的数量，元素较多而被分为多个批次的key只计一次
+    pub objects: BTreeMap<String, u64>,
+    /// 事务的数量，仅在开启了`Config::is_group_transaction`时统计
+    pub transactions: u64,
+    /// 从Redis接收到的RDB及AOF的字节数
+    pub bytes_received: u64,
+    /// 最近一次全量同步时Redis告知的RDB长度，disk-less replication时为None
+    pub rdb_length: Option<u64>,
+    /// 最近一次全量同步时已解析的RDB字节数
+    pub rdb_parsed: u64,
+    /// 重新连接的次数，即除第一次之外的连接次数(共用同一个`Metrics`的Listener合并计算)
+    pub reconnects: u64,
+    /// `EventHandler`处理事件的总耗时
+    pub handler_time: Duration,
+}
+
+impl MetricsSnapshot {
+    /// 转换为Prometheus的文本格式
+    pub fn to_prometheus(&self) -> String {
+        let mut out = String::new();
+        header(
+            &mut out,
+            "redis_event_commands_total",
+            "counter",
+            "Number of commands received.",
+        );
+        for (name, count) in &self.commands {
+            let _ = writeln!(
+                out,
+                "redis_event_commands_total{{command=\"{}\"}} {}",
+                escape(name),
+                count
+            );
+        }
+        header(
+            &mut out,
+            "redis_event_rdb_objects_total",
+            "counter",
+            "Number of RDB keys parsed.",
+        );
+        for (name, count) in &self.objects {
+            let _ = writeln!(out, "redis_event_rdb_objects_total{{type=\"{}\"}} {}", name, count);
+        }
+        let rdb_length = self.rdb_length.map_or(-1, |length| length as i64);
+        let handler_time = self.handler_time.as_secs_f64();
+        metric(
+            &mut out,
+            "redis_event_transactions_total",
+            "counter",
+            "Number of transactions received.",
+            self.transactions,
+        );
+        metric(
+            &mut out,
+            "redis_event_received_bytes_total",
+            "counter",
+            "Bytes received from Redis.",
+            self.bytes_received,
+        );
+        metric(
+            &mut out,
+            "redis_event_rdb_length_bytes",
+            "gauge",
+            "Length of the RDB announced by Redis, -1 if unknown.",
+            rdb_length,
+        );
+        metric(
+            &mut out,
+            "redis_event_rdb_parsed_bytes",
+            "gauge",
+            "Bytes of the RDB parsed so far.",
+            self.rdb_parsed,
+        );
+        metric(
+            &mut out,
+            "redis_event_reconnects_total",
+            "counter",
+            "Number of reconnects.",
+            self.reconnects,
+        );
+        metric(
+            &mut out,
+            "redis_event_handler_seconds_total",
+            "counter",
+            "Time spent in the event handler.",
+            handler_time,
+        );
+        out
+    }
+}
+
+fn header(out: &mut String, name: &str, kind: &str, help: &str) {
+    let _ = writeln!(out, "# HELP {} {}", name, help);
+    let _ = writeln!(out, "# TYPE {} {}", name, kind);
+}
+
+fn metric(out: &mut String, name: &str, kind: &str, help: &str, value: impl Display) {
+    header(out, name, kind, help);
+    let _ = writeln!(out, "{} {}", name, value);
+}
+
+// label中的`\`、`"`及换行需要转义
+fn escape(value: &str) -> String {
+    value.replace('\\', "\\\\").replace('"', "\\\"").replace('\n', "\\n")
+}
+
+fn object_type(obj: &Object) -> Option<&'static str> {
+    match obj {
+        Object::String(_) => Some("string"),
+        Object::List(_) => Some("list"),
+        Object::Set(_) => Some("set"),
+        Object::SortedSet(_) => Some("zset"),
+        Object::Hash(_) => Some("hash"),
+        Object::Module(..) => Some("module"),
+        Object::Stream(..) => Some("stream"),
+        Object::Function(_) => Some("function"),
+        Object::BOR | Object::EOR => None,
+    }
+}
+
+/// 统计事件数量及处理耗时的`EventHandler`
+pub(crate) struct MeteredHandler<'a> {
+    handler: &'a mut dyn EventHandler,
+    metrics: &'a Metrics,
+}
+
+impl<'a> MeteredHandler<'a> {
+    pub(crate) fn new(handler: &'a mut dyn EventHandler, metrics: &'a Metrics) -> MeteredHandler<'a> {
+        MeteredHandler { handler, metrics }
+    }
+}
+
+impl EventHandler for MeteredHandler<'_> {
+    fn handle(&mut self, event: Event) {
+        self.metrics.record_event(&event);
+        let start = Instant::now();
+        self.handler.handle(event);
+        self.metrics.add_handler_time(start.elapsed());
+    }
+}
+
+/// 统计接收到的字节数
+pub(crate) struct MeteredStream<T> {
+    inner: T,
+    metrics: Metrics,
+}
+
+impl<T> MeteredStream<T> {
+    pub(crate) fn new(inner: T, metrics: &Metrics) -> MeteredStream<T> {
+        MeteredStream {
+            inner,
+            metrics: metrics.clone(),
+        }
+    }
+}
+
+impl<T: Read> Read for MeteredStream<T> {
+    fn read(&mut self, buf: &mut [u8]) -> Result<usize> {
+        let len = self.inner.read(buf)?;
+        self.metrics.add_received(len);
+        Ok(len)
+    }
+}
+
+impl<T: Write> Write for MeteredStream<T> {
+    fn write(&mut self, buf: &[u8]) -> Result<usize> {
+        self.inner.write(buf)
+    }
+
+    fn flush(&mut self) -> Result<()> {
+        self.inner.flush()
+    }
+}
+
+/// 统计已解析的RDB字节数
+pub(crate) struct RdbProgress<'a> {
+    input: &'a mut dyn Read,
+    metrics: &'a Metrics,
+}
+
+impl<'a> RdbProgress<'a> {
+    pub(crate) fn new(input: &'a mut dyn Read, length: i64, metrics: &'a Metrics) -> RdbProgress<'a> {
+        metrics.start_rdb(length);
+        RdbProgress { input, metrics }
+    }
+}
+
+impl Read for RdbProgress<'_> {
+    fn read(&mut self, buf: &mut [u8]) -> Result<usize> {
+        let len = self.input.read(buf)?;
+        self.metrics.add_rdb_parsed(len);
+        Ok(len)
+    }
+}
@@ -6,7 +6,7 @@ use std::any::Any;
 use std::cmp;
 use std::collections::BTreeMap;
 use std::fmt::{Debug, Error, Formatter};
-use std::io::{Cursor, Read, Result};
+use std::io::{self, Cursor, ErrorKind, Read, Result};
 use std::sync::atomic::{AtomicBool, Ordering};
 
 use byteorder::{BigEndian, LittleEndian, ReadBytesExt};
@@ -15,13 +15,12 @@ use log::info;
 use crate::cmd::Command;
 use crate::cmd::connection::SELECT;
 use crate::iter::{IntSetIter, Iter, QuickListIter, SortedSetIter, StrValIter, ZipListIter, ZipMapIter};
+use crate::metrics::{Metrics, RdbProgress};
 use crate::{Event, EventHandler, ModuleParser, RDBParser, lzf, to_string};
-use std::cell::RefCell;
 use std::f64::{INFINITY, NAN, NEG_INFINITY};
 use std::iter::FromIterator;
-use std::rc::Rc;
 use std::str::FromStr;
-use std::sync::Arc;
+use std::sync::{Arc, Mutex};
 
 /// 一些解析RDB数据的方法
 pub trait RDBDecode: Read {
@@ -131,11 +130,21 @@ impl<R: Read + ?Sized> RDBDecode for R {}
 
 pub(crate) struct DefaultRDBParser {
     pub(crate) running: Arc<AtomicBool>,
-    pub(crate) module_parser: Option<Rc<RefCell<dyn ModuleParser>>>,
+    pub(crate) module_parser: Option<Arc<Mutex<dyn ModuleParser + Send>>>,
+    pub(crate) metrics: Option<Metrics>,
 }
 
 impl RDBParser for DefaultRDBParser {
-    fn parse(&mut self, input: &mut dyn Read, _: i64, event_handler: &mut dyn EventHandler) -> Result<()> {
+    fn parse(&mut self, input: &mut dyn Read, length: i64, event_handler: &mut dyn EventHandler) -> Result<()> {
+        match self.metrics.clone() {
+            Some(metrics) => self.parse_rdb(&mut RdbProgress::new(input, length, &metrics), event_handler),
+            None => self.parse_rdb(input, event_handler),
+        }
+    }
+}
+
+impl DefaultRDBParser {
+    fn parse_rdb(&mut self, input: &mut dyn Read, event_handler: &mut dyn EventHandler) -> Result<()> {
         event_handler.handle(Event::RDB(Object::BOR));
         let mut bytes = vec![0; 5];
         // 开头5个字节: REDIS
@@ -164,7 +173,6 @@ impl RDBParser for DefaultRDBParser {
                 }
                 RDB_OPCODE_SELECTDB => {
                     let (_db, _) = input.read_length()?;
-                    meta.db = _db;
                     db = _db;
                     let cmd = SELECT { db: _db as i32 };
                     event_handler.handle(Event::AOF(Command::SELECT(&cmd)));
@@ -225,10 +233,15 @@ impl RDBParser for DefaultRDBParser {
                     break;
                 }
                 RDB_OPCODE_FUNCTION => {
-                    unimplemented!()
+                    // Redis 7.0的RC版本所使用的格式，正式版中已被FUNCTION2替代
+                    return Err(io::Error::new(
+                        ErrorKind::InvalidData,
+                        "RDB_OPCODE_FUNCTION is not supported",
+                    ));
                 }
                 RDB_OPCODE_FUNCTION2 => {
-                    unimplemented!()
+                    let code = input.read_string()?;
+                    event_handler.handle(Event::RDB(Object::Function(code)));
                 }
                 _ => {
                     self.read_object(input, data_type, event_handler, &meta)?;
@@ -238,9 +251,7 @@ impl RDBParser for DefaultRDBParser {
         event_handler.handle(Event::RDB(Object::EOR));
         Ok(())
     }
-}
 
-impl DefaultRDBParser {
     // 根据传入的数据类型，从流中读取对应类型的数据
     fn read_object(
         &mut self, input: &mut dyn Read, value_type: u8, event_handler: &mut dyn EventHandler, meta: &Meta,
@@ -575,7 +586,7 @@ impl DefaultRDBParser {
                 if let Some(parser) = &mut self.module_parser {
                     let module: Box<dyn Module>;
                     if value_type == RDB_TYPE_MODULE_2 {
-                        module = parser.borrow_mut().parse(input, &module_name, 2);
+                        module = parser.lock().unwrap().parse(input, &module_name, 2);
                         let (len, _) = input.read_length()?;
                         if len != 0 {
                             panic!(
@@ -584,7 +595,7 @@ impl DefaultRDBParser {
                             );
                         }
                     } else {
-                        module = parser.borrow_mut().parse(input, &module_name, module_version);
+                        module = parser.lock().unwrap().parse(input, &module_name, module_version);
                     }
                     event_handler.handle(Event::RDB(Object::Module(key, module, meta)));
                 } else {
@@ -1011,20 +1022,22 @@ pub enum Object<'a> {
     /// 代表Redis中的Hash类型数据
     Hash(Hash<'a>),
     /// 代表Redis中的module, 需要额外实现Module解析器
-    Module(Vec<u8>, Box<dyn Module>, &'a Meta),
+    Module(Vec<u8>, Box<dyn Module + 'a>, &'a Meta),
     /// 代表Redis中的Stream类型数据
     Stream(Vec<u8>, Stream<'a>),
+    /// 代表Redis 7中的Function，内容为Function库的代码
+    Function(Vec<u8>),
     /// 代表rdb数据解析开始
     BOR,
     /// 代表rdb数据解析完毕
     EOR,
 }
 
-pub trait Module {
+pub trait Module: Send {
     fn as_any(&self) -> &dyn Any;
 }
 
-impl Debug for dyn Module {
+impl Debug for dyn Module + '_ {
     fn fmt(&self, _: &mut Formatter) -> result::Result<(), Error> {
         unimplemented!()
     }
@@ -1060,7 +1073,7 @@ pub enum EvictType {
 }
 
 /// 代表Redis中的String类型数据
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct KeyValue<'a> {
     /// 数据的key
     pub key: &'a [u8],
@@ -1071,7 +1084,7 @@ pub struct KeyValue<'a> {
 }
 
 /// 代表Redis中的List类型数据
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct List<'a> {
     /// 数据的key
     pub key: &'a [u8],
@@ -1082,7 +1095,7 @@ pub struct List<'a> {
 }
 
 /// 代表Redis中的Set类型数据
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct Set<'a> {
     /// 数据的key
     pub key: &'a [u8],
@@ -1093,7 +1106,7 @@ pub struct Set<'a> {
 }
 
 /// 代表Redis中的SortedSet类型数据
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct SortedSet<'a> {
     /// 数据的key
     pub key: &'a [u8],
@@ -1113,7 +1126,7 @@ pub struct Item {
 }
 
 /// 代表Redis中的Hash类型数据
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct Hash<'a> {
     /// 数据的key
     pub key: &'a [u8],
@@ -1132,7 +1145,7 @@ pub struct Field {
     pub value: Vec<u8>,
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct Stream<'a> {
     pub entries: BTreeMap<ID, Entry>,
     pub groups: Vec<Group>,
@@ -1211,14 +1224,14 @@ impl Ord for ID {
     }
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct Entry {
     pub id: ID,
     pub deleted: bool,
     pub fields: BTreeMap<Vec<u8>, Vec<u8>>,
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct Group {
     pub name: Vec<u8>,
     pub last_id: ID,
@@ -2,13 +2,13 @@
 Redis Serialization Protocol相关的解析代码
 */
 
-use std::io::{Read, Result};
+use std::io::{Error, ErrorKind, Read, Result, Write};
 
 use byteorder::ReadBytesExt;
 
 use crate::to_string;
 
-/// Redis Serialization Protocol解析
+/// Redis Serialization Protocol解析，支持RESP2及RESP3
 pub trait RespDecode: Read {
     /// 读取并解析Redis响应
     fn decode_resp(&mut self) -> Result<Resp> {
@@ -18,6 +18,37 @@ pub trait RespDecode: Read {
             Type::Error => Ok(Resp::Error(self.decode_string()?)),
             Type::BulkString => self.decode_bulk_string(),
             Type::Array => self.decode_array(),
+            Type::Null => {
+                self.decode_string()?;
+                Ok(Resp::Null)
+            }
+            Type::Double => self.decode_double(),
+            Type::Boolean => self.decode_boolean(),
+            Type::BlobError => match self.decode_bulk_string()? {
+                Resp::BulkBytes(bytes) => Ok(Resp::BlobError(bytes)),
+                resp => Ok(resp),
+            },
+            Type::VerbatimString => self.decode_verbatim_string(),
+            Type::BigNumber => Ok(Resp::BigNumber(self.decode_string()?)),
+            Type::Map => match self.decode_length()? {
+                Some(len) => Ok(Resp::Map(self.decode_pairs(len)?)),
+                None => Ok(Resp::Null),
+            },
+            Type::Set => match self.decode_aggregate()? {
+                Resp::Array(elements) => Ok(Resp::Set(elements)),
+                resp => Ok(resp),
+            },
+            Type::Push => match self.decode_aggregate()? {
+                Resp::Array(elements) => Ok(Resp::Push(elements)),
+                resp => Ok(resp),
+            },
+            Type::Attribute => {
+                let len = self.decode_length()?.unwrap_or(0);
+                let attributes = self.decode_pairs(len)?;
+                // 属性之后紧跟着真正的响应
+                let value = self.decode_resp()?;
+                Ok(Resp::Attribute(attributes, Box::new(value)))
+            }
         }
     }
     /// 读取解析Redis响应的类型
@@ -27,14 +58,24 @@ pub trait RespDecode: Read {
             if b == LF {
                 continue;
             } else {
-                match b {
-                    PLUS => return Ok(Type::String),
-                    MINUS => return Ok(Type::Error),
-                    COLON => return Ok(Type::Int),
-                    DOLLAR => return Ok(Type::BulkString),
-                    STAR => return Ok(Type::Array),
-                    _ => panic!("Unexpected Data Type: {}", b),
-                }
+                return match b {
+                    PLUS => Ok(Type::String),
+                    MINUS => Ok(Type::Error),
+                    COLON => Ok(Type::Int),
+                    DOLLAR => Ok(Type::BulkString),
+                    STAR => Ok(Type::Array),
+                    UNDERSCORE => Ok(Type::Null),
+                    COMMA => Ok(Type::Double),
+                    HASH => Ok(Type::Boolean),
+                    BANG => Ok(Type::BlobError),
+                    EQUAL => Ok(Type::VerbatimString),
+                    LEFT_PAREN => Ok(Type::BigNumber),
+                    PERCENT => Ok(Type::Map),
+                    TILDE => Ok(Type::Set),
+                    GREATER => Ok(Type::Push),
+                    PIPE => Ok(Type::Attribute),
+                    _ => Err(invalid_data(format!("Unexpected Data Type: {}", b))),
+                };
             }
         }
     }
@@ -52,64 +93,218 @@ pub trait RespDecode: Read {
         if self.read_u8()? == LF {
             Ok(to_string(buf))
         } else {
-            panic!("Expect LF after CR");
+            Err(invalid_data("Expect LF after CR"))
         }
     }
 
     /// 解析Integer响应
     fn decode_int(&mut self) -> Result<Resp> {
         let s = self.decode_string()?;
-        let i = s.parse::<i64>().unwrap();
-        return Ok(Resp::Int(i));
+        match s.parse::<i64>() {
+            Ok(i) => Ok(Resp::Int(i)),
+            Err(_) => Err(invalid_data(format!("Expect Integer, but got: {}", s))),
+        }
     }
 
-    /// 解析Bulk String响应
+    /// 解析Bulk String响应，`$-1`为Null
     fn decode_bulk_string(&mut self) -> Result<Resp> {
-        let r = self.decode_int()?;
-        if let Resp::Int(i) = r {
-            if i > 0 {
-                let mut buf = vec![0; i as usize];
-                self.read_exact(&mut buf)?;
-                let mut end = vec![0; 2];
+        match self.decode_length()? {
+            Some(len) => {
+                // 长度来自对端，不预先按此长度分配内存，避免异常数据导致内存耗尽
+                let mut buf = Vec::with_capacity(len.min(MAX_PREALLOC));
+                Read::take(&mut *self, len as u64).read_to_end(&mut buf)?;
+                if buf.len() != len {
+                    return Err(Error::new(ErrorKind::UnexpectedEof, "failed to fill whole buffer"));
+                }
+                let mut end = [0; 2];
                 self.read_exact(&mut end)?;
-                if !end.eq(&[CR, LF]) {
-                    panic!("Expected CRLF");
-                } else {
-                    return Ok(Resp::BulkBytes(buf));
+                if end != [CR, LF] {
+                    return Err(invalid_data("Expected CRLF"));
                 }
-            } else {
-                self.read_exact(&mut [0; 2])?;
-                return Ok(Resp::BulkBytes(vec![0; 0]));
+                Ok(Resp::BulkBytes(buf))
             }
-        } else {
-            panic!("Expected Int Response");
+            None => Ok(Resp::Null),
         }
     }
 
-    /// 解析Array响应
+    /// 解析Array响应，`*-1`为Null
     fn decode_array(&mut self) -> Result<Resp> {
-        let r = self.decode_int()?;
-        if let Resp::Int(i) = r {
-            let mut arr = Vec::with_capacity(i as usize);
-            for _ in 0..i {
-                let resp = self.decode_resp()?;
-                arr.push(resp);
+        self.decode_aggregate()
+    }
+
+    /// 解析Double响应，包括`inf`、`-inf`以及`nan`
+    fn decode_double(&mut self) -> Result<Resp> {
+        let s = self.decode_string()?;
+        match s.parse::<f64>() {
+            Ok(d) => Ok(Resp::Double(d)),
+            Err(_) => Err(invalid_data(format!("Expect Double, but got: {}", s))),
+        }
+    }
+
+    /// 解析Boolean响应
+    fn decode_boolean(&mut self) -> Result<Resp> {
+        match self.decode_string()?.as_str() {
+            "t" => Ok(Resp::Boolean(true)),
+            "f" => Ok(Resp::Boolean(false)),
+            s => Err(invalid_data(format!("Expect Boolean, but got: {}", s))),
+        }
+    }
+
+    /// 解析Verbatim String响应，格式为`<3字节的类型>:<内容>`
+    fn decode_verbatim_string(&mut self) -> Result<Resp> {
+        match self.decode_bulk_string()? {
+            Resp::BulkBytes(mut bytes) => {
+                if bytes.len() < 4 || bytes[3] != b':' {
+                    return Err(invalid_data("Invalid Verbatim String"));
+                }
+                let text = bytes.split_off(4);
+                bytes.truncate(3);
+                Ok(Resp::VerbatimString(to_string(bytes), text))
             }
-            return Ok(Resp::Array(arr));
-        } else {
-            panic!("Expected Int Response");
+            resp => Ok(resp),
+        }
+    }
+
+    /// 解析聚合类型的元素，长度为-1时为Null
+    fn decode_aggregate(&mut self) -> Result<Resp> {
+        match self.decode_length()? {
+            Some(len) => {
+                let mut arr = Vec::with_capacity(len.min(MAX_PREALLOC));
+                for _ in 0..len {
+                    arr.push(self.decode_resp()?);
+                }
+                Ok(Resp::Array(arr))
+            }
+            None => Ok(Resp::Null),
+        }
+    }
+
+    /// 解析Map及Attribute中的键值对
+    fn decode_pairs(&mut self, len: usize) -> Result<Vec<(Resp, Resp)>> {
+        let mut pairs = Vec::with_capacity(len.min(MAX_PREALLOC));
+        for _ in 0..len {
+            let key = self.decode_resp()?;
+            let value = self.decode_resp()?;
+            pairs.push((key, value));
+        }
+        Ok(pairs)
+    }
+
+    /// 解析长度，-1表示Null
+    fn decode_length(&mut self) -> Result<Option<usize>> {
+        match self.decode_int()? {
+            Resp::Int(-1) => Ok(None),
+            Resp::Int(len) if len >= 0 => Ok(Some(len as usize)),
+            resp => Err(invalid_data(format!("Invalid length: {:?}", resp))),
         }
     }
 }
 
 impl<R: Read + ?Sized> RespDecode for R {}
 
+/// Redis Serialization Protocol编码，支持RESP2及RESP3
+pub trait RespEncode: Write {
+    /// 将`resp`编码后写入，`Resp::Null`按照RESP3编码为`_\r\n`
+    fn encode_resp(&mut self, resp: &Resp) -> Result<()> {
+        match resp {
+            Resp::String(s) => self.encode_line(PLUS, s.as_bytes()),
+            Resp::Error(err) => self.encode_line(MINUS, err.as_bytes()),
+            Resp::Int(i) => self.encode_line(COLON, i.to_string().as_bytes()),
+            Resp::BulkBytes(bytes) => self.encode_blob(DOLLAR, &[bytes]),
+            Resp::Array(elements) => self.encode_aggregate(STAR, elements),
+            Resp::Null => self.encode_line(UNDERSCORE, b""),
+            Resp::Double(d) => {
+                let d = if d.is_nan() { String::from("nan") } else { d.to_string() };
+                self.encode_line(COMMA, d.as_bytes())
+            }
+            Resp::Boolean(b) => self.encode_line(HASH, if *b { b"t" } else { b"f" }),
+            Resp::BlobError(bytes) => self.encode_blob(BANG, &[bytes]),
+            Resp::VerbatimString(format, text) => self.encode_blob(EQUAL, &[format.as_bytes(), b":", text]),
+            Resp::BigNumber(n) => self.encode_line(LEFT_PAREN, n.as_bytes()),
+            Resp::Map(pairs) => self.encode_pairs(PERCENT, pairs),
+            Resp::Set(elements) => self.encode_aggregate(TILDE, elements),
+            Resp::Push(elements) => self.encode_aggregate(GREATER, elements),
+            Resp::Attribute(attributes, value) => {
+                self.encode_pairs(PIPE, attributes)?;
+                self.encode_resp(value)
+            }
+        }
+    }
+
+    /// 将命令编码为由Bulk String组成的Array
+    fn encode_command(&mut self, command: &[u8], args: &[&[u8]]) -> Result<()> {
+        self.encode_line(STAR, (args.len() + 1).to_string().as_bytes())?;
+        self.encode_blob(DOLLAR, &[command])?;
+        for arg in args {
+            self.encode_blob(DOLLAR, &[arg])?;
+        }
+        Ok(())
+    }
+
+    /// 写入类型及以CRLF结尾的一行
+    fn encode_line(&mut self, type_byte: u8, line: &[u8]) -> Result<()> {
+        self.write_all(&[type_byte])?;
+        self.write_all(line)?;
+        self.write_all(&[CR, LF])
+    }
+
+    /// 写入带长度的数据，`parts`拼接之后为数据的内容
+    fn encode_blob(&mut self, type_byte: u8, parts: &[&[u8]]) -> Result<()> {
+        let len: usize = parts.iter().map(|part| part.len()).sum();
+        self.encode_line(type_byte, len.to_string().as_bytes())?;
+        for part in parts {
+            self.write_all(part)?;
+        }
+        self.write_all(&[CR, LF])
+    }
+
+    fn encode_aggregate(&mut self, type_byte: u8, elements: &[Resp]) -> Result<()> {
+        self.encode_line(type_byte, elements.len().to_string().as_bytes())?;
+        for element in elements {
+            self.encode_resp(element)?;
+        }
+        Ok(())
+    }
+
+    fn encode_pairs(&mut self, type_byte: u8, pairs: &[(Resp, Resp)]) -> Result<()> {
+        self.encode_line(type_byte, pairs.len().to_string().as_bytes())?;
+        for (key, value) in pairs {
+            self.encode_resp(key)?;
+            self.encode_resp(value)?;
+        }
+        Ok(())
+    }
+}
+
+impl<W: Write + ?Sized> RespEncode for W {}
+
+/// 将`resp`编码为字节
+pub fn encode(resp: &Resp) -> Vec<u8> {
+    let mut buf = Vec::new();
+    buf.encode_resp(resp).expect("write to Vec never fails");
+    buf
+}
+
+fn invalid_data<E: Into<String>>(msg: E) -> Error {
+    Error::new(ErrorKind::InvalidData, msg.into())
+}
+
 pub enum Type {
     String,
     Error,
     Int,
     BulkString,
     Array,
+    Null,
+    Double,
+    Boolean,
+    BlobError,
+    VerbatimString,
+    BigNumber,
+    Map,
+    Set,
+    Push,
+    Attribute,
 }
 
 #[derive(Debug)]
@@ -119,8 +314,25 @@ pub enum Resp {
     Error(String),
     BulkBytes(Vec<u8>),
     Array(Vec<Resp>),
+    /// RESP3的Null，以及RESP2中的`$-1`、`*-1`
+    Null,
+    Double(f64),
+    Boolean(bool),
+    BlobError(Vec<u8>),
+    /// 类型(如`txt`、`mkd`)及内容
+    VerbatimString(String, Vec<u8>),
+    /// 大数，以字符串形式保存
+    BigNumber(String),
+    Map(Vec<(Resp, Resp)>),
+    Set(Vec<Resp>),
+    Push(Vec<Resp>),
+    /// 属性，以及紧随其后的响应
+    Attribute(Vec<(Resp, Resp)>, Box<Resp>),
 }
 
+/// 按对端告知的长度预先分配的最大元素数
+const MAX_PREALLOC: usize = 1024;
+
 // 回车换行，在redis响应中一般表示终结符，或用作分隔符以分隔数据
 pub(crate) const CR: u8 = b'\r';
 pub(crate) const LF: u8 = b'\n';
@@ -134,11 +346,22 @@ pub(crate) const PLUS: u8 = b'+';
 pub(crate) const MINUS: u8 = b'-';
 // 代表integer响应
 pub(crate) const COLON: u8 = b':';
+// 以下为RESP3中新增的类型
+pub(crate) const UNDERSCORE: u8 = b'_';
+pub(crate) const COMMA: u8 = b',';
+pub(crate) const HASH: u8 = b'#';
+pub(crate) const BANG: u8 = b'!';
+pub(crate) const EQUAL: u8 = b'=';
+pub(crate) const LEFT_PAREN: u8 = b'(';
+pub(crate) const PERCENT: u8 = b'%';
+pub(crate) const TILDE: u8 = b'~';
+pub(crate) const GREATER: u8 = b'>';
+pub(crate) const PIPE: u8 = b'|';
 
 #[cfg(test)]
 mod test {
-    use crate::resp::{Resp, RespDecode};
-    use std::io::Cursor;
+    use crate::resp::{Resp, RespDecode, RespEncode, encode};
+    use std::io::{Cursor, ErrorKind};
 
     #[test]
     fn test_decode_array() {
@@ -163,4 +386,69 @@ mod test {
             Err(err) => panic!("{}", err),
         }
     }
+
+    #[test]
+    fn test_decode_resp3() {
+        let b = b"$-1\r\n*-1\r\n_\r\n,1.5\r\n,-inf\r\n#t\r\n(3492890328409238509324850943850943825024385\r\n\
+            =15\r\ntxt:Some string\r\n!5\r\nERROR\r\n%1\r\n+key\r\n:1\r\n~2\r\n+a\r\n+b\r\n\
+            >2\r\n+message\r\n$0\r\n\r\n|1\r\n+ttl\r\n:3600\r\n:2\r\n";
+        let mut cursor = Cursor::new(&b[..]);
+        assert!(matches!(cursor.decode_resp().unwrap(), Resp::Null));
+        assert!(matches!(cursor.decode_resp().unwrap(), Resp::Null));
+        assert!(matches!(cursor.decode_resp().unwrap(), Resp::Null));
+        assert!(matches!(cursor.decode_resp().unwrap(), Resp::Double(d) if d == 1.5));
+        assert!(matches!(cursor.decode_resp().unwrap(), Resp::Double(d) if d == f64::NEG_INFINITY));
+        assert!(matches!(cursor.decode_resp().unwrap(), Resp::Boolean(true)));
+        assert!(
+            matches!(cursor.decode_resp().unwrap(), Resp::BigNumber(n) if n == "3492890328409238509324850943850943825024385")
+        );
+        assert!(
+            matches!(cursor.decode_resp().unwrap(), Resp::VerbatimString(f, t) if f == "txt" && t == b"Some string")
+        );
+        assert!(matches!(cursor.decode_resp().unwrap(), Resp::BlobError(e) if e == b"ERROR"));
+        match cursor.decode_resp().unwrap() {
+            Resp::Map(map) => assert!(matches!(&map[..], [(Resp::String(k), Resp::Int(1))] if k == "key")),
+            resp => panic!("wrong type: {:?}", resp),
+        }
+        assert!(matches!(cursor.decode_resp().unwrap(), Resp::Set(set) if set.len() == 2));
+        match cursor.decode_resp().unwrap() {
+            Resp::Push(push) => assert!(matches!(&push[1], Resp::BulkBytes(bytes) if bytes.is_empty())),
+            resp => panic!("wrong type: {:?}", resp),
+        }
+        match cursor.decode_resp().unwrap() {
+            Resp::Attribute(attributes, value) => {
+                assert_eq!(1, attributes.len());
+                assert!(matches!(*value, Resp::Int(2)));
+            }
+            resp => panic!("wrong type: {:?}", resp),
+        }
+
+        let mut cursor = Cursor::new(b"@1\r\n");
+        assert_eq!(ErrorKind::InvalidData, cursor.decode_resp().unwrap_err().kind());
+        let mut cursor = Cursor::new(b":abc\r\n");
+        assert_eq!(ErrorKind::InvalidData, cursor.decode_resp().unwrap_err().kind());
+        // 异常的长度不会导致预先分配大量内存
+        let mut cursor = Cursor::new(b"$99999999999999\r\nabc");
+        assert_eq!(ErrorKind::UnexpectedEof, cursor.decode_resp().unwrap_err().kind());
+        let mut cursor = Cursor::new(b"*99999999999999\r\n+a\r\n");
+        assert_eq!(ErrorKind::UnexpectedEof, cursor.decode_resp().unwrap_err().kind());
+    }
+
+    #[test]
+    fn test_encode_resp() {
+        let b: &[u8] = b"*2\r\n$3\r\nSET\r\n:-1\r\n-ERR wrong\r\n+OK\r\n_\r\n,1.5\r\n,-inf\r\n#f\r\n(123\r\n\
+            =7\r\ntxt:abc\r\n!3\r\nerr\r\n%1\r\n+k\r\n*0\r\n~1\r\n+a\r\n>1\r\n+m\r\n|1\r\n+ttl\r\n:1\r\n:2\r\n";
+        let mut cursor = Cursor::new(b);
+        let mut buf = Vec::new();
+        while (cursor.position() as usize) < b.len() {
+            let resp = cursor.decode_resp().unwrap();
+            buf.encode_resp(&resp).unwrap();
+        }
+        assert_eq!(b, buf.as_slice());
+
+        let mut buf = Vec::new();
+        buf.encode_command(b"SELECT", &[b"0"]).unwrap();
+        assert_eq!(b"*2\r\n$6\r\nSELECT\r\n$1\r\n0\r\n", buf.as_slice());
+        assert_eq!(b"_\r\n".to_vec(), encode(&Resp::Null));
+    }
 }
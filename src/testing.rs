@@ -0,0 +1,460 @@
+/*!
+用于测试的Redis master
+
+[`FakeMaster`]在当前进程中监听一个本地端口，与[`Listener`]完成握手(`AUTH`/`HELLO`、`PING`、`REPLCONF`、`PSYNC`/`SYNC`)，
+发送指定的RDB(可选disk-less replication)，之后按顺序发送预先设置的命令，无需启动`redis-server`。
+命令发送完毕之后关闭写入端，`Listener`读取到EOF后将返回错误，所以通常在最后一个事件处理完毕后通过控制变量停止`Listener`，
+或直接忽略`start()`返回的错误。
+
+通过[`Fault`]可以模拟生产环境中常见的异常情况，如RDB发送到一半时断开连接、PSYNC返回`-LOADING`等。
+
+需开启`testing` feature:
+
+```
+use std::sync::Arc;
+use std::sync::atomic::AtomicBool;
+use redis_event::listener;
+use redis_event::testing::FakeMaster;
+use redis_event::RedisListener;
+
+let mut master = FakeMaster::new();
+master.with_command(&["SET", "a", "b"]);
+let handle = master.spawn().unwrap();
+
+let mut builder = listener::Builder::new();
+builder.with_config(handle.config());
+builder.with_control_flag(Arc::new(AtomicBool::new(true)));
+let mut redis_listener = builder.build().unwrap();
+// 命令发送完毕之后master关闭连接，返回UnexpectedEof
+assert!(redis_listener.start().is_err());
+
+// master接收到的所有命令
+let received = handle.join().unwrap();
+assert!(received.iter().any(|args| args[0] == b"PSYNC"));
+```
+
+[`FakeMaster`]: struct.FakeMaster.html
+[`Fault`]: enum.Fault.html
+[`Listener`]: ../listener/struct.Listener.html
+*/
+use std::fs;
+use std::io::{BufReader, Error, ErrorKind, Read, Result, Write};
+use std::net::{Shutdown, TcpListener, TcpStream};
+use std::path::Path;
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, Instant};
+
+use crate::config::Config;
+use crate::resp::{Resp, RespDecode, RespEncode};
+
+/// 不包含任何数据的RDB
+const EMPTY_RDB: &[u8] = b"REDIS0009\xff\0\0\0\0\0\0\0\0";
+
+/// disk-less replication时RDB前后的EOF标记
+const EOF_MARK: &[u8] = b"e6a1b5d2f1c0a9b8e7d6c5b4a3f2e1d0c9b8a7f6";
+
+/// 预先设置的master行为
+#[derive(Clone)]
+enum Step {
+    Send(Vec<u8>),
+    Sleep(Duration),
+}
+
+/// 生产环境中常见的异常情况
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Fault {
+    /// RDB只发送了一半就断开连接
+    DisconnectInRdb,
+    /// 第一次PSYNC返回`-LOADING`，之后正常进行全量同步
+    Loading,
+    /// 第一次PSYNC返回`-NOMASTERLINK`，之后正常进行全量同步
+    NoMasterLink,
+    /// 不支持PSYNC(返回`-ERR unknown command 'PSYNC'`)，需改用SYNC
+    PsyncUnsupported,
+    /// RDB及命令每次只发送16字节，每次间隔10毫秒
+    SlowWrite,
+    /// 发送无法解析的数据
+    Garbage,
+    /// 发送格式正确但缺少参数的命令，如只有名称的`SET`
+    MalformedCommand,
+}
+
+impl Fault {
+    /// 所有的异常情况
+    pub const ALL: [Fault; 7] = [
+        Fault::DisconnectInRdb,
+        Fault::Loading,
+        Fault::NoMasterLink,
+        Fault::PsyncUnsupported,
+        Fault::SlowWrite,
+        Fault::Garbage,
+        Fault::MalformedCommand,
+    ];
+}
+
+/// 可编排的Redis master，通过`with_*`设置行为之后调用[`spawn`]启动
+///
+/// [`spawn`]: #method.spawn
+#[derive(Clone)]
+pub struct FakeMaster {
+    password: Option<String>,
+    rdb: Vec<u8>,
+    diskless: bool,
+    repl_id: String,
+    repl_offset: i64,
+    steps: Vec<Step>,
+    timeout: Duration,
+    psync_replies: Vec<String>,
+    rdb_limit: Option<usize>,
+    pace: Option<(usize, Duration)>,
+}
+
+impl Default for FakeMaster {
+    fn default() -> Self {
+        FakeMaster {
+            password: None,
+            rdb: EMPTY_RDB.to_vec(),
+            diskless: false,
+            repl_id: String::from("8de1787ba490483314a4d30f1c628bc5025eb761"),
+            repl_offset: 0,
+            steps: Vec::new(),
+            timeout: Duration::from_secs(10),
+            psync_replies: Vec::new(),
+            rdb_limit: None,
+            pace: None,
+        }
+    }
+}
+
+impl FakeMaster {
+    /// 不需要认证，发送一个空的RDB，没有任何命令
+    pub fn new() -> FakeMaster {
+        FakeMaster::default()
+    }
+
+    /// 需要通过`AUTH`或`HELLO 3 AUTH`认证，用户名不做检查
+    pub fn with_password(&mut self, password: &str) {
+        self.password = Some(password.to_owned());
+    }
+
+    /// 全量同步时发送的RDB
+    pub fn with_rdb(&mut self, rdb: Vec<u8>) {
+        self.rdb = rdb;
+    }
+
+    /// 全量同步时发送`path`中的RDB
+    pub fn with_rdb_file<P: AsRef<Path>>(&mut self, path: P) -> Result<()> {
+        self.rdb = fs::read(path)?;
+        Ok(())
+    }
+
+    /// 以disk-less replication(`$EOF:<mark>`)的方式发送RDB，仅对`PSYNC`生效
+    pub fn with_diskless(&mut self, diskless: bool) {
+        self.diskless = diskless;
+    }
+
+    /// `+FULLRESYNC`中的replication id及offset
+    pub fn with_replication(&mut self, repl_id: &str, repl_offset: i64) {
+        self.repl_id = repl_id.to_owned();
+        self.repl_offset = repl_offset;
+    }
+
+    /// RDB之后发送的命令，按添加的顺序发送
+    pub fn with_command(&mut self, args: &[&str]) {
+        let mut data = Vec::new();
+        let args: Vec<&[u8]> = args.iter().map(|arg| arg.as_bytes()).collect();
+        data.encode_command(args[0], &args[1..]).unwrap();
+        self.steps.push(Step::Send(data));
+    }
+
+    /// RDB之后原样发送`data`
+    pub fn with_raw(&mut self, data: &[u8]) {
+        self.steps.push(Step::Send(data.to_vec()));
+    }
+
+    /// 等待`duration`之后再发送后续的数据
+    pub fn with_delay(&mut self, duration: Duration) {
+        self.steps.push(Step::Sleep(duration));
+    }
+
+    /// 依次使用`reply`(如`-LOADING Redis is loading the dataset in memory`)回复PSYNC，用完之后再正常进行全量同步
+    pub fn with_psync_reply(&mut self, reply: &str) {
+        self.psync_replies.push(reply.to_owned());
+    }
+
+    /// RDB只发送前`len`字节，之后断开连接
+    pub fn with_disconnect_in_rdb(&mut self, len: usize) {
+        self.rdb_limit = Some(len);
+    }
+
+    /// 全量同步之后的数据每次只发送`chunk`字节，每次间隔`delay`
+    pub fn with_slow_write(&mut self, chunk: usize, delay: Duration) {
+        self.pace = Some((chunk.max(1), delay));
+    }
+
+    /// 模拟`fault`，`Fault::DisconnectInRdb`按当前设置的RDB计算断开的位置，`Fault::Garbage`及`Fault::MalformedCommand`在已添加的命令之后发送
+    pub fn with_fault(&mut self, fault: Fault) {
+        match fault {
+            Fault::DisconnectInRdb => self.with_disconnect_in_rdb(self.rdb.len() / 2),
+            Fault::Loading => self.with_psync_reply("-LOADING Redis is loading the dataset in memory"),
+            Fault::NoMasterLink => self.with_psync_reply("-NOMASTERLINK Can't SYNC while not connected with my master"),
+            Fault::PsyncUnsupported => self.with_psync_reply("-ERR unknown command 'PSYNC'"),
+            Fault::SlowWrite => self.with_slow_write(16, Duration::from_millis(10)),
+            // 声称有大量元素的Array，之后跟着无法解析的数据
+            Fault::Garbage => self.with_raw(b"*99999999999\r\n\x00garbage\r\n"),
+            Fault::MalformedCommand => {
+                self.with_command(&["SET"]);
+                self.with_command(&["SCRIPT"]);
+                self.with_command(&["HSET", "h", "f"]);
+                self.with_command(&["SELECT", "x"]);
+            }
+        }
+    }
+
+    /// 等待连接以及读取的超时时间，默认10秒
+    pub fn with_timeout(&mut self, timeout: Duration) {
+        self.timeout = timeout;
+    }
+
+    /// 监听`127.0.0.1`上的随机端口，在新线程中处理一个连接
+    pub fn spawn(&self) -> Result<FakeMasterHandle> {
+        let listener = TcpListener::bind("127.0.0.1:0")?;
+        let port = listener.local_addr()?.port();
+        let master = self.clone();
+        let thread = thread::Builder::new()
+            .name(format!("fake-master-{}", port))
+            .spawn(move || master.serve(listener))?;
+        Ok(FakeMasterHandle {
+            port,
+            password: self.password.clone(),
+            thread,
+        })
+    }
+
+    fn serve(&self, listener: TcpListener) -> Result<Vec<Vec<Vec<u8>>>> {
+        let conn = accept(&listener, self.timeout)?;
+        conn.set_nodelay(true)?;
+        conn.set_read_timeout(Some(self.timeout))?;
+        let mut writer = conn.try_clone()?;
+        let mut reader = BufReader::new(conn);
+        let mut received = Vec::new();
+        let mut authenticated = self.password.is_none();
+        let mut psync_replies = self.psync_replies.iter();
+
+        // 握手，直到PSYNC或SYNC
+        loop {
+            let args = match read_command(&mut reader)? {
+                Some(args) => args,
+                None => return Ok(received),
+            };
+            let name = args[0].to_ascii_uppercase();
+            received.push(args);
+            let args = received.last().unwrap();
+            let reply = match name.as_slice() {
+                b"AUTH" => {
+                    authenticated = self.check_password(args.last().unwrap());
+                    auth_reply(authenticated, self.password.is_some())
+                }
+                b"HELLO" => {
+                    // HELLO 3 AUTH <username> <password>
+                    if args.len() >= 5 {
+                        authenticated = self.check_password(&args[4]);
+                    }
+                    if authenticated {
+                        Resp::Map(vec![
+                            (Resp::BulkBytes(b"server".to_vec()), Resp::BulkBytes(b"redis".to_vec())),
+                            (Resp::BulkBytes(b"proto".to_vec()), Resp::Int(3)),
+                        ])
+                    } else if args.len() >= 5 {
+                        auth_reply(false, true)
+                    } else {
+                        Resp::Error(String::from(
+                            "NOAUTH HELLO must be called with the client already authenticated",
+                        ))
+                    }
+                }
+                _ if !authenticated => Resp::Error(String::from("NOAUTH Authentication required.")),
+                b"PING" => Resp::String(String::from("PONG")),
+                b"PSYNC" => {
+                    if let Some(reply) = psync_replies.next() {
+                        writer.write_all(format!("{}\r\n", reply).as_bytes())?;
+                        continue;
+                    }
+                    let reply = format!("+FULLRESYNC {} {}\r\n", self.repl_id, self.repl_offset);
+                    self.write(&mut writer, reply.as_bytes())?;
+                    if !self.send_rdb(&mut writer, self.diskless)? {
+                        return Ok(received);
+                    }
+                    break;
+                }
+                b"SYNC" => {
+                    if !self.send_rdb(&mut writer, false)? {
+                        return Ok(received);
+                    }
+                    break;
+                }
+                _ => Resp::String(String::from("OK")),
+            };
+            writer.encode_resp(&reply)?;
+        }
+
+        for step in &self.steps {
+            match step {
+                Step::Send(data) => self.write(&mut writer, data)?,
+                Step::Sleep(duration) => thread::sleep(*duration),
+            }
+        }
+        writer.shutdown(Shutdown::Write)?;
+
+        // 继续接收REPLCONF ACK等命令，直到对方关闭连接或超时
+        loop {
+            match read_command(&mut reader) {
+                Ok(Some(args)) => received.push(args),
+                Ok(None) => return Ok(received),
+                Err(err) if matches!(err.kind(), ErrorKind::WouldBlock | ErrorKind::TimedOut) => {
+                    return Ok(received);
+                }
+                Err(err) => return Err(err),
+            }
+        }
+    }
+
+    fn check_password(&self, password: &[u8]) -> bool {
+        match &self.password {
+            Some(expected) => expected.as_bytes() == password,
+            None => true,
+        }
+    }
+
+    /// 发送RDB，设置了`with_disconnect_in_rdb`时只发送一部分并断开连接，返回false
+    fn send_rdb(&self, writer: &mut TcpStream, diskless: bool) -> Result<bool> {
+        // Redis在生成RDB期间会发送换行，防止replica超时
+        self.write(writer, b"\n")?;
+        if diskless {
+            self.write(writer, b"$EOF:")?;
+            self.write(writer, EOF_MARK)?;
+            self.write(writer, b"\r\n")?;
+        } else {
+            self.write(writer, format!("${}\r\n", self.rdb.len()).as_bytes())?;
+        }
+        if let Some(len) = self.rdb_limit {
+            self.write(writer, &self.rdb[..len.min(self.rdb.len())])?;
+            writer.shutdown(Shutdown::Both)?;
+            return Ok(false);
+        }
+        self.write(writer, &self.rdb)?;
+        if diskless {
+            self.write(writer, EOF_MARK)?;
+        }
+        Ok(true)
+    }
+
+    fn write(&self, writer: &mut TcpStream, data: &[u8]) -> Result<()> {
+        match self.pace {
+            Some((chunk, delay)) => {
+                for data in data.chunks(chunk) {
+                    writer.write_all(data)?;
+                    thread::sleep(delay);
+                }
+                Ok(())
+            }
+            None => writer.write_all(data),
+        }
+    }
+}
+
+/// 运行中的[`FakeMaster`]
+///
+/// [`FakeMaster`]: struct.FakeMaster.html
+pub struct FakeMasterHandle {
+    port: u16,
+    password: Option<String>,
+    thread: JoinHandle<Result<Vec<Vec<Vec<u8>>>>>,
+}
+
+impl FakeMasterHandle {
+    /// 监听的端口
+    pub fn port(&self) -> u16 {
+        self.port
+    }
+
+    /// 连接此master的`Config`，其余配置项使用默认值
+    pub fn config(&self) -> Config {
+        Config {
+            host: String::from("127.0.0.1"),
+            port: self.port,
+            password: self.password.clone().unwrap_or_default(),
+            ..Config::default()
+        }
+    }
+
+    /// 等待连接处理完毕，返回接收到的所有命令
+    pub fn join(self) -> Result<Vec<Vec<Vec<u8>>>> {
+        match self.thread.join() {
+            Ok(result) => result,
+            Err(_) => Err(Error::other("fake master panicked")),
+        }
+    }
+}
+
+fn accept(listener: &TcpListener, timeout: Duration) -> Result<TcpStream> {
+    listener.set_nonblocking(true)?;
+    let deadline = Instant::now() + timeout;
+    loop {
+        match listener.accept() {
+            Ok((conn, _)) => {
+                conn.set_nonblocking(false)?;
+                return Ok(conn);
+            }
+            Err(err) if err.kind() == ErrorKind::WouldBlock && Instant::now() < deadline => {
+                thread::sleep(Duration::from_millis(10));
+            }
+            Err(err) => return Err(err),
+        }
+    }
+}
+
+/// 读取一条命令，对方关闭连接时返回None
+fn read_command<R: Read>(reader: &mut R) -> Result<Option<Vec<Vec<u8>>>> {
+    match reader.decode_resp() {
+        Ok(Resp::Array(array)) => {
+            let mut args = Vec::with_capacity(array.len());
+            for arg in array {
+                match arg {
+                    Resp::BulkBytes(bytes) => args.push(bytes),
+                    resp => {
+                        return Err(Error::new(
+                            ErrorKind::InvalidData,
+                            format!("Unexpected argument: {:?}", resp),
+                        ));
+                    }
+                }
+            }
+            if args.is_empty() {
+                return Err(Error::new(ErrorKind::InvalidData, "Empty command"));
+            }
+            Ok(Some(args))
+        }
+        Ok(resp) => Err(Error::new(
+            ErrorKind::InvalidData,
+            format!("Unexpected command: {:?}", resp),
+        )),
+        Err(err) if matches!(err.kind(), ErrorKind::UnexpectedEof | ErrorKind::ConnectionReset) => Ok(None),
+        Err(err) => Err(err),
+    }
+}
+
+fn auth_reply(authenticated: bool, has_password: bool) -> Resp {
+    if !has_password {
+        Resp::Error(String::from(
+            "ERR AUTH <password> called without any password configured for the default user. \
+             Are you sure your configuration is correct?",
+        ))
+    } else if authenticated {
+        Resp::String(String::from("OK"))
+    } else {
+        Resp::Error(String::from(
+            "WRONGPASS invalid username-password pair or user is disabled.",
+        ))
+    }
+}
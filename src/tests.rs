@@ -1,13 +1,11 @@
 #[cfg(test)]
 mod rdb_tests {
     use std::any::Any;
-    use std::cell::RefCell;
     use std::collections::HashMap;
     use std::fs::File;
     use std::io::Read;
-    use std::rc::Rc;
-    use std::sync::Arc;
     use std::sync::atomic::AtomicBool;
+    use std::sync::{Arc, Mutex};
 
     use num_bigint::Sign;
     use num_traits::ToPrimitive;
@@ -15,6 +13,37 @@ mod rdb_tests {
     use crate::rdb::{DefaultRDBParser, EvictType, ExpireType, ID, Module, Object, RDBDecode};
     use crate::{Event, EventHandler, ModuleParser, RDBParser};
 
+    #[test]
+    fn test_function() {
+        let code = b"#!lua name=mylib";
+        let mut rdb = b"REDIS0010".to_vec();
+        rdb.push(245);
+        rdb.push(code.len() as u8);
+        rdb.extend_from_slice(code);
+        rdb.push(255);
+        rdb.extend_from_slice(&[0; 8]);
+
+        struct TestRdbHandler {
+            functions: Vec<Vec<u8>>,
+        }
+
+        impl EventHandler for TestRdbHandler {
+            fn handle(&mut self, data: Event) {
+                if let Event::RDB(Object::Function(code)) = data {
+                    self.functions.push(code);
+                }
+            }
+        }
+        let mut handler = TestRdbHandler { functions: Vec::new() };
+        let mut rdb_parser = DefaultRDBParser {
+            running: Arc::new(AtomicBool::new(true)),
+            module_parser: None,
+            metrics: None,
+        };
+        rdb_parser.parse(&mut rdb.as_slice(), 0, &mut handler).unwrap();
+        assert_eq!(vec![code.to_vec()], handler.functions);
+    }
+
     #[test]
     fn test_zipmap_not_compress() {
         let mut file = File::open("tests/rdb/zipmap_that_doesnt_compress_1.rdb").expect("file not found");
@@ -41,7 +70,7 @@ mod rdb_tests {
                         }
                         _ => {}
                     },
-                    Event::AOF(_) => {}
+                    Event::AOF(_) | Event::Transaction(_) => {}
                 }
             }
         }
@@ -50,6 +79,7 @@ mod rdb_tests {
         let mut rdb_parser = DefaultRDBParser {
             running: Arc::new(AtomicBool::new(true)),
             module_parser: None,
+            metrics: None,
         };
         rdb_parser.parse(&mut file, 0, &mut handler).unwrap();
     }
@@ -81,7 +111,7 @@ mod rdb_tests {
                         }
                         _ => {}
                     },
-                    Event::AOF(_) => {}
+                    Event::AOF(_) | Event::Transaction(_) => {}
                 }
             }
         }
@@ -91,6 +121,7 @@ mod rdb_tests {
         let mut rdb_parser = DefaultRDBParser {
             running: Arc::new(AtomicBool::new(true)),
             module_parser: None,
+            metrics: None,
         };
         rdb_parser.parse(&mut file, 0, &mut handler).unwrap();
     }
@@ -134,7 +165,7 @@ mod rdb_tests {
                         }
                         _ => {}
                     },
-                    Event::AOF(_) => {}
+                    Event::AOF(_) | Event::Transaction(_) => {}
                 }
             }
         }
@@ -144,6 +175,7 @@ mod rdb_tests {
         let mut rdb_parser = DefaultRDBParser {
             running: Arc::new(AtomicBool::new(true)),
             module_parser: None,
+            metrics: None,
         };
         rdb_parser.parse(&mut file, 0, &mut handler).unwrap();
     }
@@ -178,7 +210,7 @@ mod rdb_tests {
                         }
                         _ => {}
                     },
-                    Event::AOF(_) => {}
+                    Event::AOF(_) | Event::Transaction(_) => {}
                 }
             }
         }
@@ -188,6 +220,7 @@ mod rdb_tests {
         let mut rdb_parser = DefaultRDBParser {
             running: Arc::new(AtomicBool::new(true)),
             module_parser: None,
+            metrics: None,
         };
         rdb_parser.parse(&mut file, 0, &mut handler).unwrap();
     }
@@ -230,7 +263,7 @@ mod rdb_tests {
                         }
                         _ => {}
                     },
-                    Event::AOF(_) => {}
+                    Event::AOF(_) | Event::Transaction(_) => {}
                 }
             }
         }
@@ -240,6 +273,7 @@ mod rdb_tests {
         let mut rdb_parser = DefaultRDBParser {
             running: Arc::new(AtomicBool::new(true)),
             module_parser: None,
+            metrics: None,
         };
         rdb_parser.parse(&mut file, 0, &mut handler).unwrap();
     }
@@ -292,7 +326,7 @@ mod rdb_tests {
                         }
                         _ => {}
                     },
-                    Event::AOF(_) => {}
+                    Event::AOF(_) | Event::Transaction(_) => {}
                 }
             }
         }
@@ -302,6 +336,7 @@ mod rdb_tests {
         let mut rdb_parser = DefaultRDBParser {
             running: Arc::new(AtomicBool::new(true)),
             module_parser: None,
+            metrics: None,
         };
         rdb_parser.parse(&mut file, 0, &mut handler).unwrap();
     }
@@ -344,7 +379,7 @@ mod rdb_tests {
                         }
                         _ => {}
                     },
-                    Event::AOF(_) => {}
+                    Event::AOF(_) | Event::Transaction(_) => {}
                 }
             }
         }
@@ -354,6 +389,7 @@ mod rdb_tests {
         let mut rdb_parser = DefaultRDBParser {
             running: Arc::new(AtomicBool::new(true)),
             module_parser: None,
+            metrics: None,
         };
         rdb_parser.parse(&mut file, 0, &mut handler).unwrap();
     }
@@ -396,7 +432,7 @@ mod rdb_tests {
                         }
                         _ => {}
                     },
-                    Event::AOF(_) => {}
+                    Event::AOF(_) | Event::Transaction(_) => {}
                 }
             }
         }
@@ -405,6 +441,7 @@ mod rdb_tests {
         let mut rdb_parser = DefaultRDBParser {
             running: Arc::new(AtomicBool::new(true)),
             module_parser: None,
+            metrics: None,
         };
         rdb_parser.parse(&mut file, 0, &mut handler).unwrap();
     }
@@ -463,7 +500,7 @@ mod rdb_tests {
     fn test_module() {
         let mut file = File::open("tests/rdb/module.rdb").expect("file not found");
 
-        let parser = Rc::new(RefCell::new(HelloModuleParser {}));
+        let parser = Arc::new(Mutex::new(HelloModuleParser {}));
 
         struct TestRdbHandler {}
 
@@ -493,6 +530,7 @@ mod rdb_tests {
         let mut rdb_parser = DefaultRDBParser {
             running: Arc::new(AtomicBool::new(true)),
             module_parser: Some(parser),
+            metrics: None,
         };
         rdb_parser.parse(&mut file, 0, &mut handler).unwrap();
     }
@@ -501,7 +539,7 @@ mod rdb_tests {
     fn test_module2() {
         let mut file = File::open("tests/rdb/dump-module-2.rdb").expect("file not found");
 
-        let parser = Rc::new(RefCell::new(HelloModuleParser {}));
+        let parser = Arc::new(Mutex::new(HelloModuleParser {}));
 
         struct TestRdbHandler {}
 
@@ -533,6 +571,7 @@ mod rdb_tests {
         let mut rdb_parser = DefaultRDBParser {
             running: Arc::new(AtomicBool::new(true)),
             module_parser: Some(parser),
+            metrics: None,
         };
         rdb_parser.parse(&mut file, 0, &mut handler).unwrap();
     }
@@ -552,6 +591,7 @@ mod rdb_tests {
         let mut rdb_parser = DefaultRDBParser {
             running: Arc::new(AtomicBool::new(true)),
             module_parser: None,
+            metrics: None,
         };
         rdb_parser.parse(&mut file, 0, &mut handler).unwrap();
     }
@@ -692,6 +732,7 @@ mod rdb_tests {
         let mut rdb_parser = DefaultRDBParser {
             running: Arc::new(AtomicBool::new(true)),
             module_parser: None,
+            metrics: None,
         };
         rdb_parser.parse(&mut file, 0, &mut handler).unwrap();
     }
@@ -711,6 +752,7 @@ mod rdb_tests {
         let mut rdb_parser = DefaultRDBParser {
             running: Arc::new(AtomicBool::new(true)),
             module_parser: None,
+            metrics: None,
         };
         rdb_parser.parse(&mut file, 0, &mut handler).unwrap();
     }
@@ -720,6 +762,9 @@ mod rdb_tests {
 mod aof_tests {
     use std::fs::File;
 
+    use crate::aof::{AofReader, AofWriter, FsyncPolicy, Manifest, MultiPartReader, WriterConfig};
+    use std::io::Cursor;
+
     use crate::cmd::Command;
     use crate::resp::{Resp, RespDecode};
     use crate::{Event, EventHandler, cmd};
@@ -734,7 +779,7 @@ mod aof_tests {
         impl EventHandler for TestCmdHandler {
             fn handle(&mut self, cmd: Event) {
                 match cmd {
-                    Event::RDB(_) => {}
+                    Event::RDB(_) | Event::Transaction(_) => {}
                     Event::AOF(cmd) => match cmd {
                         Command::HMSET(hmset) => {
                             let key = String::from_utf8_lossy(hmset.key);
@@ -810,7 +855,7 @@ mod aof_tests {
         impl EventHandler for TestCmdHandler {
             fn handle(&mut self, cmd: Event) {
                 match cmd {
-                    Event::RDB(_) => {}
+                    Event::RDB(_) | Event::Transaction(_) => {}
                     Event::AOF(cmd) => {
                         if let Command::SET(set) = cmd {
                             let key = String::from_utf8_lossy(set.key);
@@ -951,7 +996,7 @@ mod aof_tests {
         impl EventHandler for TestCmdHandler {
             fn handle(&mut self, cmd: Event) {
                 match cmd {
-                    Event::RDB(_) => {}
+                    Event::RDB(_) | Event::Transaction(_) => {}
                     Event::AOF(cmd) => match cmd {
                         Command::XADD(xadd) => {
                             let key = String::from_utf8_lossy(xadd.key);
@@ -1018,11 +1063,240 @@ mod aof_tests {
             }
         }
     }
+
+    #[test]
+    fn test_aof_reader() {
+        struct TestCmdHandler {
+            commands: Vec<String>,
+        }
+
+        impl EventHandler for TestCmdHandler {
+            fn handle(&mut self, cmd: Event) {
+                if let Event::AOF(cmd) = cmd {
+                    let name = format!("{:?}", cmd);
+                    self.commands.push(name.split('(').next().unwrap().to_string());
+                }
+            }
+        }
+
+        let mut handler = TestCmdHandler { commands: Vec::new() };
+        let mut reader = AofReader::open("tests/aof/appendonly.aof").unwrap();
+        let result = reader.read(&mut handler).unwrap();
+        assert_eq!(6, result.commands);
+        assert_eq!(310, result.valid_len);
+        assert!(!result.truncated);
+
+        let select = b"*2\r\n$6\r\nSELECT\r\n$1\r\n1\r\n";
+        let set = b"*3\r\n$3\r\nSET\r\n$1\r\na\r\n$1\r\nb\r\n";
+        let multi = b"*1\r\n$5\r\nMULTI\r\n";
+        let incr = b"*2\r\n$4\r\nINCR\r\n$1\r\nx\r\n";
+        let exec = b"*1\r\n$4\r\nEXEC\r\n";
+        let mut data = b"#TS:1588842682\r\n".to_vec();
+        data.extend_from_slice(select);
+        data.extend_from_slice(multi);
+        data.extend_from_slice(incr);
+        data.extend_from_slice(exec);
+        let valid_len = data.len() as u64;
+        // 未完成的事务，以及不完整的命令
+        data.extend_from_slice(multi);
+        data.extend_from_slice(set);
+        data.extend_from_slice(&incr[..10]);
+
+        let mut handler = TestCmdHandler { commands: Vec::new() };
+        let mut reader = AofReader::new(Cursor::new(data));
+        let result = reader.read(&mut handler).unwrap();
+        assert_eq!(4, result.commands);
+        assert_eq!(valid_len, result.valid_len);
+        assert!(result.truncated);
+        assert_eq!(1, reader.db());
+        assert_eq!(vec!["SELECT", "MULTI", "INCR", "EXEC"], handler.commands);
+    }
+
+    #[test]
+    fn test_multi_part_aof() {
+        let manifest = Manifest::parse(
+            "file appendonly.aof.1.base.rdb seq 1 type b\n\
+             file \"append only.aof.2.incr.aof\" seq 2 type i startoffset 10\n\
+             file appendonly.aof.1.incr.aof seq 1 type i\n",
+        )
+        .unwrap();
+        assert_eq!("appendonly.aof.1.base.rdb", manifest.base().unwrap().name);
+        let incrs: Vec<&str> = manifest.incrs().iter().map(|entry| entry.name.as_str()).collect();
+        assert_eq!(vec!["appendonly.aof.1.incr.aof", "append only.aof.2.incr.aof"], incrs);
+        assert!(Manifest::parse("file appendonly.aof.1.base.rdb seq 1").is_err());
+
+        let tempdir = tempdir::TempDir::new("redis-event-appendonlydir").unwrap();
+        let dir = tempdir.path();
+        std::fs::write(
+            dir.join("appendonly.aof.manifest"),
+            "file appendonly.aof.1.base.rdb seq 1 type b\n\
+             file appendonly.aof.1.incr.aof seq 1 type i\n\
+             file appendonly.aof.2.incr.aof seq 2 type i\n",
+        )
+        .unwrap();
+        std::fs::copy("tests/rdb/dictionary.rdb", dir.join("appendonly.aof.1.base.rdb")).unwrap();
+        std::fs::copy("tests/aof/appendonly.aof", dir.join("appendonly.aof.1.incr.aof")).unwrap();
+        let mut incr = b"*3\r\n$3\r\nSET\r\n$1\r\na\r\n$1\r\nb\r\n".to_vec();
+        incr.extend_from_slice(b"*3\r\n$3\r\nSET");
+        std::fs::write(dir.join("appendonly.aof.2.incr.aof"), &incr).unwrap();
+
+        struct TestCmdHandler {
+            rdb: usize,
+            aof: usize,
+        }
+
+        impl EventHandler for TestCmdHandler {
+            fn handle(&mut self, event: Event) {
+                match event {
+                    Event::RDB(_) => self.rdb += 1,
+                    Event::AOF(_) => self.aof += 1,
+                    Event::Transaction(_) => {}
+                }
+            }
+        }
+
+        // 重写期间残留的临时manifest不影响读取
+        std::fs::write(
+            dir.join("temp-appendonly.aof.manifest"),
+            "file temp-rewrite.aof seq 3 type b\n",
+        )
+        .unwrap();
+
+        let mut handler = TestCmdHandler { rdb: 0, aof: 0 };
+        let mut reader = MultiPartReader::open(dir).unwrap();
+        assert_eq!(3, reader.manifest().entries.len());
+        let result = reader.read(&mut handler).unwrap();
+        assert!(handler.rdb > 0);
+        // RDB中的SELECTDB也会产生一个SELECT事件
+        assert_eq!(8, handler.aof);
+        assert_eq!(7, result.commands);
+        assert_eq!(27, result.valid_len);
+        assert!(result.truncated);
+
+        // 只有最后一个文件允许不完整
+        std::fs::write(dir.join("appendonly.aof.1.incr.aof"), &incr).unwrap();
+        assert!(MultiPartReader::open(dir).unwrap().read(&mut handler).is_err());
+    }
+
+    #[test]
+    fn test_aof_writer() {
+        let tempdir = tempdir::TempDir::new("redis-event-aof-writer").unwrap();
+        let dir = tempdir.path();
+        let config = WriterConfig {
+            dir: dir.to_string_lossy().to_string(),
+            file_name: String::from("replication"),
+            max_file_size: Some(64),
+            max_file_age: None,
+            fsync: FsyncPolicy::Always,
+        };
+        let frame = b"*3\r\n$3\r\nSET\r\n$1\r\na\r\n$1\r\nb\r\n";
+        let mut writer = AofWriter::new(config.clone()).unwrap();
+        writer.append("id", 10, frame).unwrap();
+        writer.append("id", 10 + frame.len() as i64, frame).unwrap();
+        // 超过max_file_size，写入新的文件
+        writer.append("id", 10 + 2 * frame.len() as i64, frame).unwrap();
+        // offset不连续，写入offset标记
+        writer.append("id", 100, frame).unwrap();
+        drop(writer);
+
+        let first = std::fs::read(dir.join("replication.1.aof")).unwrap();
+        let mut expected = b"#OFFSET:id 10\r\n".to_vec();
+        expected.extend_from_slice(frame);
+        expected.extend_from_slice(frame);
+        assert_eq!(expected, first);
+
+        let second = std::fs::read(dir.join("replication.2.aof")).unwrap();
+        let mut expected = b"#OFFSET:id 64\r\n".to_vec();
+        expected.extend_from_slice(frame);
+        expected.extend_from_slice(b"#OFFSET:id 100\r\n");
+        expected.extend_from_slice(frame);
+        assert_eq!(expected, second);
+
+        // 已存在的文件不会被覆盖
+        let mut writer = AofWriter::new(config.clone()).unwrap();
+        writer.append("id", 0, frame).unwrap();
+        assert_eq!(Some(dir.join("replication.3.aof")), writer.current_path());
+        drop(writer);
+
+        // EverySec时每条命令都会立即flush，fsync由tick完成
+        let config = WriterConfig {
+            max_file_size: None,
+            fsync: FsyncPolicy::EverySec,
+            ..config
+        };
+        let mut writer = AofWriter::new(config).unwrap();
+        writer.append("id", 0, frame).unwrap();
+        let path = writer.current_path().unwrap();
+        let mut expected = b"#OFFSET:id 0\r\n".to_vec();
+        expected.extend_from_slice(frame);
+        assert_eq!(expected, std::fs::read(&path).unwrap());
+        writer.tick().unwrap();
+        drop(writer);
+    }
 }
 
 #[cfg(test)]
 mod other_tests {
-    use crate::rdb::ID;
+    use crate::aof::{AofWriter, FsyncPolicy, WriterConfig};
+    use crate::cmd::{Buffered, Command, TransactionBuffer, parse_transaction};
+    use crate::config::Config;
+    use crate::filter::{DataType, Filter, FilteredHandler, glob_match};
+    use crate::handler::{FanOutHandler, Middleware, MiddlewareChain};
+    use crate::io::copy_until;
+    use crate::lag::{LagTracker, parse_master_offset};
+    use crate::listener::{self, NextStep, is_getack, psync_next_step};
+    use crate::metrics::{MeteredHandler, Metrics};
+    use crate::rdb::{DefaultRDBParser, ID, KeyValue, Meta, Module, Object};
+    use crate::testing::{FakeMaster, Fault};
+    use crate::{Event, EventHandler, NoOpEventHandler, RDBParser, RedisListener};
+    use std::any::Any;
+    use std::io::{BufReader, Cursor, ErrorKind, Read, sink};
+    use std::sync::atomic::AtomicBool;
+    use std::sync::{Arc, Mutex};
+    use std::thread;
+    use std::time::{Duration, Instant};
+
+    /// 由字符串构造命令的各个参数
+    fn cmd(args: &[&str]) -> Vec<Vec<u8>> {
+        args.iter().map(|arg| arg.as_bytes().to_vec()).collect()
+    }
+
+    struct TestModule(i64);
+
+    impl Module for TestModule {
+        fn as_any(&self) -> &dyn Any {
+            self
+        }
+    }
+
+    /// 记录事件的类型及key
+    #[derive(Default)]
+    struct TestRecordHandler {
+        events: Vec<String>,
+    }
+
+    impl EventHandler for TestRecordHandler {
+        fn handle(&mut self, event: Event) {
+            let event = match event {
+                Event::RDB(Object::BOR) => String::from("BOR"),
+                Event::RDB(Object::EOR) => String::from("EOR"),
+                Event::RDB(Object::String(kv)) => format!("string:{}", String::from_utf8_lossy(kv.key)),
+                Event::RDB(Object::Hash(hash)) => format!("hash:{}", String::from_utf8_lossy(hash.key)),
+                Event::RDB(Object::Module(_, module, _)) => match module.as_any().downcast_ref::<TestModule>() {
+                    Some(module) => format!("module:{}", module.0),
+                    None => String::from("module"),
+                },
+                Event::RDB(_) => String::from("rdb"),
+                Event::AOF(cmd) => format!(
+                    "{}:{}",
+                    cmd.name(),
+                    String::from_utf8_lossy(&cmd.keys().join(&b","[..]))
+                ),
+                Event::Transaction(cmds) => format!("transaction:{}", cmds.len()),
+            };
+            self.events.push(event);
+        }
+    }
 
     #[test]
     fn test_id_cmp() {
@@ -1040,4 +1314,772 @@ mod other_tests {
         id1.seq = 0;
         assert_eq!(id1 > id2, true);
     }
+
+    #[test]
+    fn test_is_getack() {
+        let getack = vec![b"replconf".to_vec(), b"GETACK".to_vec(), b"*".to_vec()];
+        assert!(is_getack(&getack));
+
+        let ack = vec![b"REPLCONF".to_vec(), b"ACK".to_vec(), b"0".to_vec()];
+        assert!(!is_getack(&ack));
+        assert!(!is_getack(&[b"REPLCONF".to_vec()]));
+    }
+
+    #[test]
+    fn test_copy_until_skip() {
+        let mark = [b'a'; 40];
+        let mut data = vec![b'x'; 100];
+        data.extend_from_slice(&mark);
+        data.extend_from_slice(b"*1\r\n$4\r\nPING\r\n");
+        // 较小的缓冲区，使mark跨越多次读取
+        let mut input = BufReader::with_capacity(16, Cursor::new(data));
+        copy_until(&mut input, &mark, &mut sink()).unwrap();
+        let mut rest = String::new();
+        input.read_to_string(&mut rest).unwrap();
+        assert_eq!("*1\r\n$4\r\nPING\r\n", rest);
+
+        let mut input = BufReader::new(Cursor::new(vec![b'a'; 39]));
+        let err = copy_until(&mut input, &mark, &mut sink()).unwrap_err();
+        assert_eq!(ErrorKind::UnexpectedEof, err.kind());
+    }
+
+    #[test]
+    fn test_copy_until() {
+        let mark = [b'a'; 40];
+        let mut data = b"REDIS0009".repeat(10);
+        data.extend_from_slice(&mark);
+        data.extend_from_slice(b"+PING\r\n");
+        let mut input = BufReader::with_capacity(16, Cursor::new(data));
+        let mut output = Vec::new();
+        assert_eq!(90, copy_until(&mut input, &mark, &mut output).unwrap());
+        assert_eq!(b"REDIS0009".repeat(10), output);
+        let mut rest = String::new();
+        input.read_to_string(&mut rest).unwrap();
+        assert_eq!("+PING\r\n", rest);
+    }
+
+    #[test]
+    fn test_transaction_buffer() {
+        struct TestCmdHandler {
+            commands: usize,
+        }
+
+        impl EventHandler for TestCmdHandler {
+            fn handle(&mut self, event: Event) {
+                if let Event::Transaction(commands) = event {
+                    assert!(matches!(commands[0], Command::INCR(_)));
+                    self.commands += commands.len();
+                }
+            }
+        }
+
+        let mut buffer = TransactionBuffer::default();
+        assert!(matches!(
+            buffer.push(cmd(&["SET", "a", "b"]), 10),
+            Buffered::Command(_, 10)
+        ));
+        assert!(matches!(buffer.push(cmd(&["multi"]), 5), Buffered::Pending));
+        assert!(matches!(buffer.push(cmd(&["INCR", "x"]), 7), Buffered::Pending));
+        assert!(matches!(buffer.push(cmd(&["SET", "a", "b"]), 10), Buffered::Pending));
+        assert_eq!(22, buffer.pending_size());
+        match buffer.push(cmd(&["EXEC"]), 5) {
+            Buffered::Transaction(commands, size) => {
+                assert_eq!(2, commands.len());
+                assert_eq!(27, size);
+                let mut handler = TestCmdHandler { commands: 0 };
+                parse_transaction(&commands, &mut handler);
+                assert_eq!(2, handler.commands);
+            }
+            _ => panic!("expect transaction"),
+        }
+        assert_eq!(0, buffer.pending_size());
+        // 不在事务中的EXEC按普通命令处理
+        assert!(matches!(buffer.push(cmd(&["EXEC"]), 5), Buffered::Command(_, 5)));
+
+        // 未结束的事务中又出现MULTI，之前缓冲的命令逐条交出，大小不丢失
+        assert!(matches!(buffer.push(cmd(&["MULTI"]), 5), Buffered::Pending));
+        assert!(matches!(buffer.push(cmd(&["INCR", "x"]), 7), Buffered::Pending));
+        match buffer.push(cmd(&["MULTI"]), 5) {
+            Buffered::Flushed(commands, size) => {
+                assert_eq!(vec![cmd(&["INCR", "x"])], commands);
+                assert_eq!(12, size);
+            }
+            _ => panic!("expect flushed commands"),
+        }
+        assert_eq!(5, buffer.pending_size());
+        match buffer.push(cmd(&["EXEC"]), 5) {
+            Buffered::Transaction(commands, size) => assert_eq!((0, 10), (commands.len(), size)),
+            _ => panic!("expect transaction"),
+        }
+    }
+
+    #[test]
+    fn test_replication_lag() {
+        let info = b"# Replication\r\nrole:master\r\nconnected_slaves:1\r\nmaster_repl_offset:1024\r\n";
+        assert_eq!(Some(1024), parse_master_offset(info));
+        assert_eq!(None, parse_master_offset(b"# Replication\r\nrole:master\r\n"));
+
+        let start = Instant::now();
+        let mut tracker = LagTracker::default();
+        let lag = tracker.update(start, 100, 100);
+        assert_eq!((0, Duration::ZERO), (lag.bytes, lag.time));
+
+        tracker.update(start + Duration::from_secs(1), 200, 100);
+        tracker.update(start + Duration::from_secs(2), 300, 150);
+        // offset越过150是在第1秒
+        let lag = tracker.update(start + Duration::from_secs(3), 300, 150);
+        assert_eq!((150, Duration::from_secs(2)), (lag.bytes, lag.time));
+        let lag = tracker.update(start + Duration::from_secs(4), 300, 250);
+        assert_eq!((50, Duration::from_secs(2)), (lag.bytes, lag.time));
+        let lag = tracker.update(start + Duration::from_secs(5), 300, 300);
+        assert_eq!((0, Duration::ZERO), (lag.bytes, lag.time));
+
+        // Redis重启之后offset变小
+        let lag = tracker.update(start + Duration::from_secs(6), 50, 0);
+        assert_eq!((50, Duration::ZERO), (lag.bytes, lag.time));
+    }
+
+    #[test]
+    fn test_metrics() {
+        let rdb = std::fs::read("tests/rdb/hash_as_ziplist.rdb").unwrap();
+        let metrics = Metrics::default();
+        let mut rdb_parser = DefaultRDBParser {
+            running: Arc::new(AtomicBool::new(true)),
+            module_parser: None,
+            metrics: Some(metrics.clone()),
+        };
+        let mut handler = NoOpEventHandler {};
+        let mut handler = MeteredHandler::new(&mut handler, &metrics);
+        rdb_parser
+            .parse(&mut rdb.as_slice(), rdb.len() as i64, &mut handler)
+            .unwrap();
+
+        crate::cmd::parse(cmd(&["SET", "a", "b"]), &mut handler);
+        crate::cmd::parse(cmd(&["set", "c", "d"]), &mut handler);
+        crate::cmd::parse(cmd(&["FOO", "bar"]), &mut handler);
+        parse_transaction(&[cmd(&["INCR", "x"]), cmd(&["SET", "y", "z"])], &mut handler);
+
+        let snapshot = metrics.snapshot();
+        assert_eq!(Some(rdb.len() as u64), snapshot.rdb_length);
+        assert_eq!(rdb.len() as u64, snapshot.rdb_parsed);
+        assert_eq!(Some(&1), snapshot.objects.get("hash"));
+        assert_eq!(Some(&3), snapshot.commands.get("SET"));
+        assert_eq!(Some(&1), snapshot.commands.get("FOO"));
+        assert_eq!(1, snapshot.transactions);
+        assert_eq!(0, snapshot.reconnects);
+
+        let text = snapshot.to_prometheus();
+        assert!(text.contains("# TYPE redis_event_commands_total counter\n"));
+        assert!(text.contains("redis_event_commands_total{command=\"SET\"} 3\n"));
+        assert!(text.contains("redis_event_rdb_objects_total{type=\"hash\"} 1\n"));
+        assert!(text.contains(&format!("redis_event_rdb_parsed_bytes {}\n", rdb.len())));
+
+        // 元素较多的List会分为多个批次交给EventHandler，但只计为一个key
+        let rdb = std::fs::read("tests/rdb/linkedlist.rdb").unwrap();
+        let metrics = Metrics::default();
+        let mut rdb_parser = DefaultRDBParser {
+            running: Arc::new(AtomicBool::new(true)),
+            module_parser: None,
+            metrics: Some(metrics.clone()),
+        };
+        struct BatchCounter(usize);
+
+        impl EventHandler for BatchCounter {
+            fn handle(&mut self, event: Event) {
+                if let Event::RDB(Object::List(_)) = event {
+                    self.0 += 1;
+                }
+            }
+        }
+
+        let mut counter = BatchCounter(0);
+        let mut handler = MeteredHandler::new(&mut counter, &metrics);
+        rdb_parser
+            .parse(&mut rdb.as_slice(), rdb.len() as i64, &mut handler)
+            .unwrap();
+        assert!(counter.0 > 1);
+        assert_eq!(Some(&1), metrics.snapshot().objects.get("list"));
+    }
+
+    #[test]
+    fn test_config_from_url() {
+        let conf =
+            Config::from_url("redis://user:p%40ss@[::1]:6380/0?repl_id=abc&repl_offset=100&read_timeout=1.5").unwrap();
+        assert_eq!("::1", conf.host);
+        assert_eq!(6380, conf.port);
+        assert_eq!("user", conf.username);
+        assert_eq!("p@ss", conf.password);
+        assert_eq!("abc", conf.repl_id);
+        assert_eq!(100, conf.repl_offset);
+        assert_eq!(Some(Duration::from_millis(1500)), conf.read_timeout);
+        assert_eq!(None, conf.write_timeout);
+        assert!(conf.is_aof);
+
+        let conf = Config::from_url("redis://localhost?protocol=resp3&aof=false").unwrap();
+        assert_eq!(("localhost", 6379), (conf.host.as_str(), conf.port));
+        assert_eq!(("?", -1), (conf.repl_id.as_str(), conf.repl_offset));
+        assert!(conf.is_resp3 && !conf.is_aof);
+
+        let conf = Config::from_url("unix:///tmp/redis.sock?password=secret").unwrap();
+        assert_eq!(Some("/tmp/redis.sock"), conf.unix_socket_path.as_deref());
+        assert_eq!("secret", conf.password);
+
+        #[cfg(any(feature = "tls", feature = "tls-rustls"))]
+        {
+            let conf = Config::from_url("rediss://:pass@host:6380/?insecure=true").unwrap();
+            assert!(conf.is_tls_enabled && conf.is_tls_insecure);
+        }
+        #[cfg(not(any(feature = "tls", feature = "tls-rustls")))]
+        assert!(Config::from_url("rediss://host").is_err());
+
+        for url in [
+            "localhost:6379",
+            "http://localhost",
+            "redis://host:port",
+            "redis://host/?foo=bar",
+        ] {
+            assert_eq!(ErrorKind::InvalidInput, Config::from_url(url).unwrap_err().kind());
+        }
+    }
+
+    #[test]
+    fn test_build_validate() {
+        let build = |config: Option<Config>| {
+            let mut builder = listener::Builder::new();
+            if let Some(config) = config {
+                builder.with_config(config);
+            }
+            builder.with_control_flag(Arc::new(AtomicBool::new(true)));
+            builder.build().map(|_| ())
+        };
+        assert!(build(Some(Config::default())).is_ok());
+        assert!(build(None).is_err());
+
+        let invalid = [
+            Config {
+                host: String::new(),
+                ..Config::default()
+            },
+            Config {
+                port: 0,
+                ..Config::default()
+            },
+            Config {
+                repl_offset: 100,
+                ..Config::default()
+            },
+            Config {
+                repl_id: String::from("8de1787ba490483314a4d30f1c628bc5025eb761"),
+                ..Config::default()
+            },
+            Config {
+                heartbeat_interval: Duration::ZERO,
+                ..Config::default()
+            },
+        ];
+        for config in invalid {
+            assert_eq!(ErrorKind::InvalidInput, build(Some(config)).unwrap_err().kind());
+        }
+        // unix socket不需要host及port
+        let config = Config {
+            host: String::new(),
+            port: 0,
+            unix_socket_path: Some(String::from("/tmp/redis.sock")),
+            ..Config::default()
+        };
+        assert!(build(Some(config)).is_ok());
+
+        // 两个TLS feature同时开启时，不能混用两种实现的配置项
+        #[cfg(all(feature = "tls", feature = "tls-rustls"))]
+        {
+            use crate::config::TlsBackend;
+
+            let tls_config = |backend: Option<TlsBackend>, native: bool, rustls: bool| Config {
+                is_tls_enabled: true,
+                identity_passwd: native.then(|| String::from("secret")),
+                tls_server_name: rustls.then(|| String::from("redis.local")),
+                tls_backend: backend,
+                ..Config::default()
+            };
+            assert!(build(Some(tls_config(Some(TlsBackend::Rustls), false, true))).is_ok());
+            assert!(build(Some(tls_config(Some(TlsBackend::Native), true, false))).is_ok());
+            assert!(build(Some(tls_config(None, false, true))).is_ok());
+            for config in [
+                tls_config(Some(TlsBackend::Native), false, true),
+                tls_config(Some(TlsBackend::Rustls), true, false),
+                tls_config(None, true, true),
+            ] {
+                assert_eq!(ErrorKind::InvalidInput, build(Some(config)).unwrap_err().kind());
+            }
+            let conf = Config::from_url("rediss://host/?tls_backend=native").unwrap();
+            assert_eq!(Some(TlsBackend::Native), conf.tls_backend);
+            assert!(Config::from_url("rediss://host/?tls_backend=openssl").is_err());
+        }
+
+        let mut config = Config::default();
+        assert!(psync_next_step("FULLRESYNC 8de1787ba490483314a4d30f1c628bc5025eb761", &mut config).is_err());
+        assert!(psync_next_step("FULLRESYNC abc xyz", &mut config).is_err());
+        assert!(matches!(psync_next_step("FULLRESYNC abc 10", &mut config), Ok(Some(_))));
+        assert_eq!(("abc", 10), (config.repl_id.as_str(), config.repl_offset));
+        let resp = "ERR unknown command 'PSYNC', with args beginning with: '?' '-1' ";
+        assert!(matches!(
+            psync_next_step(resp, &mut config),
+            Ok(Some(NextStep::ChangeMode))
+        ));
+        let resp = "LOADING Redis is loading the dataset in memory";
+        assert!(matches!(psync_next_step(resp, &mut config), Ok(Some(NextStep::Wait))));
+    }
+
+    #[test]
+    fn test_fan_out_and_middleware() {
+        // 为String类型的key加上前缀
+        struct TestRenameMiddleware;
+
+        impl Middleware for TestRenameMiddleware {
+            fn handle(&mut self, event: Event, next: &mut dyn EventHandler) {
+                match event {
+                    Event::RDB(Object::String(kv)) => {
+                        let key = [b"new:", kv.key].concat();
+                        next.handle(Event::RDB(Object::String(KeyValue { key: &key, ..kv })));
+                    }
+                    event => next.handle(event),
+                }
+            }
+        }
+
+        // 丢弃DEL命令
+        struct TestDropMiddleware;
+
+        impl Middleware for TestDropMiddleware {
+            fn handle(&mut self, event: Event, next: &mut dyn EventHandler) {
+                if !matches!(event, Event::AOF(Command::DEL(_))) {
+                    next.handle(event);
+                }
+            }
+        }
+
+        let first = Arc::new(Mutex::new(TestRecordHandler::default()));
+        let second = Arc::new(Mutex::new(TestRecordHandler::default()));
+        let mut fan_out = FanOutHandler::new();
+        fan_out.with_handler(first.clone());
+        fan_out.with_handler(second.clone());
+        let mut chain = MiddlewareChain::new(Arc::new(Mutex::new(fan_out)));
+        chain.with_middleware(Box::new(TestRenameMiddleware));
+        chain.with_middleware(Box::new(TestDropMiddleware));
+
+        let meta = Meta {
+            db: 0,
+            expire: None,
+            evict: None,
+        };
+        let kv = KeyValue {
+            key: b"k",
+            value: b"v",
+            meta: &meta,
+        };
+        chain.handle(Event::RDB(Object::String(kv)));
+        let module = Box::new(TestModule(7));
+        chain.handle(Event::RDB(Object::Module(b"test".to_vec(), module, &meta)));
+        crate::cmd::parse(cmd(&["DEL", "k"]), &mut chain);
+        crate::cmd::parse(cmd(&["SET", "k", "v"]), &mut chain);
+
+        let expected = vec!["string:new:k", "module:7", "SET:k"];
+        assert_eq!(expected, first.lock().unwrap().events);
+        assert_eq!(expected, second.lock().unwrap().events);
+    }
+
+    #[test]
+    fn test_glob_match() {
+        assert!(glob_match(b"*", b""));
+        assert!(glob_match(b"user:*", b"user:1"));
+        assert!(!glob_match(b"user:*", b"order:1"));
+        assert!(glob_match(b"h?llo", b"hello"));
+        assert!(!glob_match(b"h?llo", b"hllo"));
+        assert!(glob_match(b"h[ae]llo", b"hallo"));
+        assert!(!glob_match(b"h[^e]llo", b"hello"));
+        assert!(glob_match(b"h[a-b]llo", b"hbllo"));
+        assert!(glob_match(b"*:*:end", b"a:b:c:end"));
+        assert!(glob_match(b"a\\*", b"a*"));
+        assert!(!glob_match(b"a\\*", b"ab"));
+    }
+
+    #[test]
+    fn test_filter() {
+        let mut filter = Filter::new();
+        filter.with_db(1);
+        filter.with_key_pattern("user:*");
+        filter.with_type(DataType::String);
+        filter.with_command("select");
+        filter.with_command("set");
+        filter.with_command("del");
+        let mut recorder = TestRecordHandler::default();
+        let mut handler = FilteredHandler::new(&mut recorder, &mut filter);
+
+        for (db, key) in [(1, "user:1"), (0, "user:2"), (1, "order:1")] {
+            let meta = Meta {
+                db,
+                expire: None,
+                evict: None,
+            };
+            let kv = KeyValue {
+                key: key.as_bytes(),
+                value: b"v",
+                meta: &meta,
+            };
+            handler.handle(Event::RDB(Object::String(kv)));
+        }
+
+        crate::cmd::parse(cmd(&["SET", "user:1", "v"]), &mut handler);
+        crate::cmd::parse(cmd(&["SELECT", "1"]), &mut handler);
+        crate::cmd::parse(cmd(&["SET", "user:1", "v"]), &mut handler);
+        crate::cmd::parse(cmd(&["SET", "order:1", "v"]), &mut handler);
+        crate::cmd::parse(cmd(&["DEL", "order:1", "user:2"]), &mut handler);
+        crate::cmd::parse(cmd(&["LPUSH", "user:1", "v"]), &mut handler);
+
+        parse_transaction(
+            &[cmd(&["SET", "user:3", "v"]), cmd(&["UNLINK", "user:3"])],
+            &mut handler,
+        );
+        parse_transaction(&[cmd(&["SET", "order:3", "v"])], &mut handler);
+
+        let expected = vec![
+            "string:user:1",
+            "SELECT:",
+            "SET:user:1",
+            "DEL:order:1,user:2",
+            "transaction:1",
+        ];
+        assert_eq!(expected, recorder.events);
+
+        // 未能解析的命令将第一个参数视为key
+        let mut filter = Filter::new();
+        filter.with_key_pattern("user:*");
+        let mut recorder = TestRecordHandler::default();
+        let mut handler = FilteredHandler::new(&mut recorder, &mut filter);
+        crate::cmd::parse(cmd(&["FOO", "user:1"]), &mut handler);
+        crate::cmd::parse(cmd(&["FOO", "order:1"]), &mut handler);
+        crate::cmd::parse(cmd(&["BAR"]), &mut handler);
+        assert_eq!(vec!["FOO:", "BAR:"], recorder.events);
+    }
+
+    fn run_listener(config: Config) -> (std::io::Result<()>, Vec<String>) {
+        let handler = Arc::new(Mutex::new(TestRecordHandler::default()));
+        let mut builder = listener::Builder::new();
+        builder.with_config(config);
+        builder.with_control_flag(Arc::new(AtomicBool::new(true)));
+        builder.with_event_handler(handler.clone());
+        let result = builder.build().and_then(|mut listener| listener.start());
+        let events = std::mem::take(&mut handler.lock().unwrap().events);
+        (result, events)
+    }
+
+    /// 将事件转交给`TestRecordHandler`的`AsyncEventHandler`
+    #[cfg(feature = "async")]
+    struct AsyncRecordHandler(Arc<Mutex<TestRecordHandler>>);
+
+    #[cfg(feature = "async")]
+    impl crate::aio::AsyncEventHandler for AsyncRecordHandler {
+        async fn handle(&mut self, event: Event<'_>) {
+            self.0.lock().unwrap().handle(event);
+        }
+    }
+
+    #[cfg(feature = "async")]
+    async fn run_async_listener(config: Config, filter: Option<Filter>) -> (std::io::Result<()>, Vec<String>) {
+        let handler = Arc::new(Mutex::new(TestRecordHandler::default()));
+        let mut builder = crate::aio::Builder::new();
+        builder.with_config(config);
+        builder.with_control_flag(Arc::new(AtomicBool::new(true)));
+        builder.with_event_handler(AsyncRecordHandler(handler.clone()));
+        if let Some(filter) = filter {
+            builder.with_filter(filter);
+        }
+        let result = match builder.build() {
+            Ok(mut listener) => listener.start().await,
+            Err(error) => Err(error),
+        };
+        let events = std::mem::take(&mut handler.lock().unwrap().events);
+        (result, events)
+    }
+
+    #[cfg(feature = "async")]
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_async_filter() {
+        let mut master = FakeMaster::new();
+        master.with_rdb_file("tests/rdb/hash_as_ziplist.rdb").unwrap();
+        master.with_command(&["SET", "a", "1"]);
+        master.with_command(&["SET", "b", "2"]);
+        let handle = master.spawn().unwrap();
+
+        let mut filter = Filter::new();
+        filter.with_key_pattern("a");
+        let (result, events) = run_async_listener(handle.config(), Some(filter)).await;
+        assert_eq!(ErrorKind::UnexpectedEof, result.unwrap_err().kind());
+        assert_eq!(vec!["BOR", "SELECT:", "EOR", "SET:a"], events);
+        tokio::task::spawn_blocking(move || handle.join())
+            .await
+            .unwrap()
+            .unwrap();
+    }
+
+    #[test]
+    fn test_fake_master() {
+        for diskless in [false, true] {
+            let mut master = FakeMaster::new();
+            master.with_password("pass");
+            master.with_rdb_file("tests/rdb/hash_as_ziplist.rdb").unwrap();
+            master.with_diskless(diskless);
+            master.with_command(&["SET", "a", "1"]);
+            master.with_command(&["INCR", "b"]);
+            let handle = master.spawn().unwrap();
+
+            let (result, events) = run_listener(handle.config());
+            assert_eq!(ErrorKind::UnexpectedEof, result.unwrap_err().kind());
+            assert_eq!(
+                vec![
+                    "BOR",
+                    "SELECT:",
+                    "hash:zipmap_compresses_easily",
+                    "EOR",
+                    "SET:a",
+                    "INCR:b"
+                ],
+                events
+            );
+
+            let received = handle.join().unwrap();
+            let names: Vec<&[u8]> = received.iter().map(|args| args[0].as_slice()).collect();
+            assert_eq!(&[b"AUTH".as_slice(), b"PING", b"REPLCONF"], &names[..3]);
+            assert!(received.contains(&vec![b"REPLCONF".to_vec(), b"capa".to_vec(), b"eof".to_vec()]));
+            assert!(received.contains(&vec![b"PSYNC".to_vec(), b"?".to_vec(), b"-1".to_vec()]));
+        }
+    }
+
+    #[test]
+    fn test_getack_with_heartbeat() {
+        // 心跳与GETACK的回复同时写入时，master收到的每一条命令都应是完整的
+        let mut master = FakeMaster::new();
+        for _ in 0..200 {
+            master.with_command(&["REPLCONF", "GETACK", "*"]);
+        }
+        let handle = master.spawn().unwrap();
+        let mut config = handle.config();
+        config.heartbeat_interval = Duration::from_millis(1);
+        let (result, _) = run_listener(config);
+        assert_eq!(ErrorKind::UnexpectedEof, result.unwrap_err().kind());
+
+        let received = handle.join().unwrap();
+        let psync = received.iter().position(|args| args[0] == b"PSYNC").unwrap();
+        let acks = &received[psync + 1..];
+        assert!(acks.len() >= 200);
+        for args in acks {
+            assert_eq!(3, args.len());
+            assert_eq!(
+                (b"REPLCONF".as_slice(), b"ACK".as_slice()),
+                (args[0].as_slice(), args[1].as_slice())
+            );
+            assert!(String::from_utf8_lossy(&args[2]).parse::<i64>().is_ok());
+        }
+    }
+
+    #[test]
+    fn test_heartbeat_with_slow_handler() {
+        // 事件处理缓慢时，心跳依然按照间隔发送
+        struct SlowHandler;
+
+        impl EventHandler for SlowHandler {
+            fn handle(&mut self, _: Event) {
+                std::thread::sleep(Duration::from_millis(50));
+            }
+        }
+
+        let mut master = FakeMaster::new();
+        for i in 0..5 {
+            master.with_command(&["SET", &format!("k{}", i), "v"]);
+        }
+        let handle = master.spawn().unwrap();
+        let mut config = handle.config();
+        config.heartbeat_interval = Duration::from_millis(10);
+        let mut builder = listener::Builder::new();
+        builder.with_config(config);
+        builder.with_control_flag(Arc::new(AtomicBool::new(true)));
+        builder.with_event_handler(Arc::new(Mutex::new(SlowHandler)));
+        let result = builder.build().and_then(|mut listener| listener.start());
+        assert_eq!(ErrorKind::UnexpectedEof, result.unwrap_err().kind());
+
+        let received = handle.join().unwrap();
+        let psync = received.iter().position(|args| args[0] == b"PSYNC").unwrap();
+        let acks = received[psync + 1..]
+            .iter()
+            .filter(|args| args[0] == b"REPLCONF" && args[1] == b"ACK")
+            .count();
+        assert!(acks >= 5, "only {} acks received", acks);
+    }
+
+    #[test]
+    fn test_aof_writer_in_transaction() {
+        // MULTI与EXEC之间的命令需写入同一个文件
+        let tempdir = tempdir::TempDir::new("redis-event-aof-writer-multi").unwrap();
+        let dir = tempdir.path();
+        let mut master = FakeMaster::new();
+        master.with_command(&["MULTI"]);
+        for i in 0..5 {
+            master.with_command(&["SET", &format!("k{}", i), "v"]);
+        }
+        master.with_command(&["EXEC"]);
+        master.with_command(&["SET", "a", "b"]);
+        let handle = master.spawn().unwrap();
+        let config = WriterConfig {
+            dir: dir.to_string_lossy().to_string(),
+            file_name: String::from("replication"),
+            max_file_size: Some(16),
+            max_file_age: None,
+            fsync: FsyncPolicy::No,
+        };
+        let writer = Arc::new(Mutex::new(AofWriter::new(config).unwrap()));
+        let mut builder = listener::Builder::new();
+        builder.with_config(handle.config());
+        builder.with_control_flag(Arc::new(AtomicBool::new(true)));
+        builder.with_event_handler(Arc::new(Mutex::new(NoOpEventHandler {})));
+        builder.with_aof_writer(Arc::clone(&writer));
+        let result = builder.build().and_then(|mut listener| listener.start());
+        assert_eq!(ErrorKind::UnexpectedEof, result.unwrap_err().kind());
+        handle.join().unwrap();
+        drop(writer);
+
+        let first = String::from_utf8(std::fs::read(dir.join("replication.1.aof")).unwrap()).unwrap();
+        assert!(first.contains("MULTI"));
+        assert!(first.contains("EXEC"));
+        assert_eq!(5, first.matches("\r\nSET\r\n").count());
+        let second = String::from_utf8(std::fs::read(dir.join("replication.2.aof")).unwrap()).unwrap();
+        assert_eq!(1, second.matches("\r\nSET\r\n").count());
+    }
+
+    #[cfg(feature = "async")]
+    #[test]
+    fn test_min_frame_len() {
+        use crate::aio::min_frame_len;
+
+        let b = b"*2\r\n$6\r\nSELECT\r\n$1\r\n0\r\n";
+        assert_eq!(b.len(), min_frame_len(b));
+        assert_eq!(b.len(), min_frame_len(&[&b[..], b"+OK\r\n"].concat()));
+        // bulk string不完整时，根据长度得出所需的字节数
+        assert_eq!(16, min_frame_len(&b[..10]));
+        assert_eq!(b.len(), min_frame_len(&b[..b.len() - 1]));
+        // 长度本身不完整时，至少还需要1个字节
+        assert_eq!(3, min_frame_len(&b[..2]));
+        assert_eq!(1, min_frame_len(b""));
+        assert_eq!(3, min_frame_len(b"\n\n"));
+        assert_eq!(1024 * 1024 + 16, min_frame_len(b"*1\r\n$1048576\r\nabc"));
+
+        let resp3 = b"%1\r\n+key\r\n:1\r\n|1\r\n+ttl\r\n:3600\r\n~2\r\n+a\r\n_\r\n";
+        let map = b"%1\r\n+key\r\n:1\r\n".len();
+        assert_eq!(map, min_frame_len(resp3));
+        assert_eq!(resp3.len() - map, min_frame_len(&resp3[map..]));
+        assert_eq!(b"$-1\r\n".len(), min_frame_len(b"$-1\r\n"));
+        // 格式有误时交由decode_resp返回错误
+        assert_eq!(0, min_frame_len(b"@1\r\n"));
+        assert_eq!(0, min_frame_len(b"*abc\r\n"));
+    }
+
+    #[test]
+    fn test_fake_master_wrong_password() {
+        let mut master = FakeMaster::new();
+        master.with_password("pass");
+        let handle = master.spawn().unwrap();
+        let mut config = handle.config();
+        config.password = String::from("wrong");
+        config.is_resp3 = true;
+
+        let (result, events) = run_listener(config);
+        assert!(result.is_err());
+        assert!(events.is_empty());
+        assert_eq!(1, handle.join().unwrap().len());
+    }
+
+    #[test]
+    fn test_faults() {
+        // 各个场景的Listener在各自的线程中运行，线程panic时join返回Err
+        let threads: Vec<_> = Fault::ALL
+            .iter()
+            .map(|&fault| {
+                thread::spawn(move || {
+                    let mut master = FakeMaster::new();
+                    master.with_rdb_file("tests/rdb/hash_as_ziplist.rdb").unwrap();
+                    master.with_command(&["SET", "a", "1"]);
+                    master.with_fault(fault);
+                    let handle = master.spawn().unwrap();
+                    let (result, events) = run_listener(handle.config());
+                    (fault, result, events, handle.join().unwrap())
+                })
+            })
+            .collect();
+
+        for thread in threads {
+            let (fault, result, events, received) = thread.join().unwrap();
+            check_fault(fault, result, events, received);
+        }
+    }
+
+    #[cfg(feature = "async")]
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_async_faults() {
+        let tasks: Vec<_> = Fault::ALL
+            .iter()
+            .map(|&fault| {
+                tokio::spawn(async move {
+                    let mut master = FakeMaster::new();
+                    master.with_rdb_file("tests/rdb/hash_as_ziplist.rdb").unwrap();
+                    master.with_command(&["SET", "a", "1"]);
+                    master.with_fault(fault);
+                    let handle = master.spawn().unwrap();
+                    let (result, events) = run_async_listener(handle.config(), None).await;
+                    // join会阻塞，不能占用tokio的工作线程
+                    let received = tokio::task::spawn_blocking(move || handle.join()).await.unwrap();
+                    (fault, result, events, received.unwrap())
+                })
+            })
+            .collect();
+        for task in tasks {
+            let (fault, result, events, received) = task.await.unwrap();
+            check_fault(fault, result, events, received);
+        }
+    }
+
+    /// 检查Listener在`fault`场景下的表现，Listener及AsyncListener的表现应一致
+    fn check_fault(fault: Fault, result: std::io::Result<()>, events: Vec<String>, received: Vec<Vec<Vec<u8>>>) {
+        let complete = vec!["BOR", "SELECT:", "hash:zipmap_compresses_easily", "EOR", "SET:a"];
+        let kind = result.unwrap_err().kind();
+        match fault {
+            Fault::DisconnectInRdb => {
+                assert_eq!(ErrorKind::UnexpectedEof, kind);
+                assert!(!events.contains(&String::from("EOR")));
+            }
+            Fault::Loading | Fault::NoMasterLink | Fault::SlowWrite => {
+                // 命令发送完毕之后master断开连接
+                assert_eq!(ErrorKind::UnexpectedEof, kind, "{:?}", fault);
+                assert_eq!(complete, events, "{:?}", fault);
+            }
+            Fault::PsyncUnsupported => {
+                assert_eq!(ErrorKind::UnexpectedEof, kind);
+                assert_eq!(complete, events);
+                assert!(received.iter().any(|args| args[0] == b"SYNC"));
+            }
+            Fault::Garbage => {
+                assert_eq!(ErrorKind::InvalidData, kind);
+                assert_eq!(complete, events);
+            }
+            Fault::MalformedCommand => {
+                // 缺少参数的命令作为Command::Other交给EventHandler，不会panic
+                assert_eq!(ErrorKind::UnexpectedEof, kind);
+                let mut expected = complete.clone();
+                expected.extend(["SET:", "SCRIPT:", "HSET:", "SELECT:"]);
+                assert_eq!(expected, events);
+            }
+        }
+        if matches!(fault, Fault::Loading | Fault::NoMasterLink) {
+            let psync = received.iter().filter(|args| args[0] == b"PSYNC").count();
+            assert_eq!(2, psync, "{:?}", fault);
+        }
+    }
 }
@@ -0,0 +1,212 @@
+/*!
+TLS连接的实现，支持native-tls(`tls` feature)以及rustls(`tls-rustls` feature)
+
+两个feature同时开启时，使用`Config::tls_backend`所指定的实现，
+未指定时若设置了`identity`(PKCS#12)则使用native-tls，否则使用rustls
+*/
+use std::io::{Read, Result, Write};
+use std::net::TcpStream;
+
+use crate::config::Config;
+
+/// 已建立的TLS连接，屏蔽了底层TLS库的差异
+pub(crate) enum TlsStream {
+    #[cfg(feature = "tls")]
+    Native(native_tls::TlsStream<TcpStream>),
+    #[cfg(feature = "tls-rustls")]
+    Rustls(Box<rustls::StreamOwned<rustls::ClientConnection, TcpStream>>),
+}
+
+impl TlsStream {
+    /// 获取底层的TcpStream
+    pub(crate) fn get_ref(&self) -> &TcpStream {
+        match self {
+            #[cfg(feature = "tls")]
+            TlsStream::Native(stream) => stream.get_ref(),
+            #[cfg(feature = "tls-rustls")]
+            TlsStream::Rustls(stream) => stream.get_ref(),
+        }
+    }
+}
+
+impl Read for TlsStream {
+    fn read(&mut self, buf: &mut [u8]) -> Result<usize> {
+        match self {
+            #[cfg(feature = "tls")]
+            TlsStream::Native(stream) => stream.read(buf),
+            #[cfg(feature = "tls-rustls")]
+            TlsStream::Rustls(stream) => stream.read(buf),
+        }
+    }
+}
+
+impl Write for TlsStream {
+    fn write(&mut self, buf: &[u8]) -> Result<usize> {
+        match self {
+            #[cfg(feature = "tls")]
+            TlsStream::Native(stream) => stream.write(buf),
+            #[cfg(feature = "tls-rustls")]
+            TlsStream::Rustls(stream) => stream.write(buf),
+        }
+    }
+
+    fn flush(&mut self) -> Result<()> {
+        match self {
+            #[cfg(feature = "tls")]
+            TlsStream::Native(stream) => stream.flush(),
+            #[cfg(feature = "tls-rustls")]
+            TlsStream::Rustls(stream) => stream.flush(),
+        }
+    }
+}
+
+/// 在已建立的TCP连接上进行TLS握手
+pub(crate) fn connect(config: &Config, stream: TcpStream) -> Result<TlsStream> {
+    #[cfg(all(feature = "tls", feature = "tls-rustls"))]
+    {
+        use crate::config::TlsBackend;
+
+        let backend = config.tls_backend.unwrap_or(if config.identity.is_some() {
+            TlsBackend::Native
+        } else {
+            TlsBackend::Rustls
+        });
+        if backend == TlsBackend::Native {
+            return native_connect(config, stream);
+        }
+    }
+    #[cfg(feature = "tls-rustls")]
+    {
+        rustls_connect(config, stream)
+    }
+    #[cfg(not(feature = "tls-rustls"))]
+    {
+        native_connect(config, stream)
+    }
+}
+
+#[cfg(feature = "tls")]
+fn native_connect(config: &Config, stream: TcpStream) -> Result<TlsStream> {
+    use native_tls::{Identity, TlsConnector};
+
+    let mut builder = TlsConnector::builder();
+    builder.danger_accept_invalid_hostnames(config.is_tls_insecure);
+    builder.danger_accept_invalid_certs(config.is_tls_insecure);
+
+    if let Some(id) = &config.identity {
+        let buff = std::fs::read(id)?;
+        let identity_passwd = match &config.identity_passwd {
+            None => "",
+            Some(passwd) => passwd.as_str(),
+        };
+        let identity = Identity::from_pkcs12(&buff, identity_passwd).map_err(invalid_input)?;
+        builder.identity(identity);
+    }
+
+    let connector = builder.build().map_err(invalid_input)?;
+    let tls_stream = connector
+        .connect(&config.host, stream)
+        .map_err(|err| std::io::Error::new(std::io::ErrorKind::ConnectionAborted, err.to_string()))?;
+    Ok(TlsStream::Native(tls_stream))
+}
+
+#[cfg(feature = "tls-rustls")]
+fn rustls_connect(config: &Config, stream: TcpStream) -> Result<TlsStream> {
+    use std::io::{Error, ErrorKind};
+    use std::sync::Arc;
+
+    use rustls::pki_types::pem::PemObject;
+    use rustls::pki_types::{CertificateDer, PrivateKeyDer, ServerName};
+    use rustls::{ClientConfig, ClientConnection, RootCertStore, StreamOwned};
+
+    let provider = Arc::new(rustls::crypto::ring::default_provider());
+    let builder = ClientConfig::builder_with_provider(Arc::clone(&provider))
+        .with_safe_default_protocol_versions()
+        .map_err(invalid_input)?;
+
+    let builder = if config.is_tls_insecure {
+        builder
+            .dangerous()
+            .with_custom_certificate_verifier(Arc::new(insecure::NoVerifier(provider)))
+    } else {
+        let mut roots = RootCertStore::empty();
+        match &config.tls_ca_cert {
+            Some(path) => {
+                for cert in CertificateDer::pem_file_iter(path).map_err(invalid_input)? {
+                    roots.add(cert.map_err(invalid_input)?).map_err(invalid_input)?;
+                }
+            }
+            None => roots.extend(webpki_roots::TLS_SERVER_ROOTS.iter().cloned()),
+        }
+        builder.with_root_certificates(roots)
+    };
+
+    let client_config = match (&config.tls_cert, &config.tls_key) {
+        (Some(cert), Some(key)) => {
+            let certs = CertificateDer::pem_file_iter(cert)
+                .and_then(|certs| certs.collect::<std::result::Result<Vec<_>, _>>())
+                .map_err(invalid_input)?;
+            let key = PrivateKeyDer::from_pem_file(key).map_err(invalid_input)?;
+            builder.with_client_auth_cert(certs, key).map_err(invalid_input)?
+        }
+        (None, None) => builder.with_no_client_auth(),
+        _ => {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                "tls_cert and tls_key must be set together",
+            ));
+        }
+    };
+
+    let server_name = config.tls_server_name.as_ref().unwrap_or(&config.host);
+    let server_name = ServerName::try_from(server_name.clone()).map_err(invalid_input)?;
+    let conn = ClientConnection::new(Arc::new(client_config), server_name).map_err(invalid_input)?;
+    let mut tls_stream = StreamOwned::new(conn, stream);
+    // 完成握手，证书相关的错误在此处返回
+    while tls_stream.conn.is_handshaking() {
+        tls_stream.conn.complete_io(&mut tls_stream.sock)?;
+    }
+    Ok(TlsStream::Rustls(Box::new(tls_stream)))
+}
+
+fn invalid_input<E: std::error::Error + Send + Sync + 'static>(err: E) -> std::io::Error {
+    std::io::Error::new(std::io::ErrorKind::InvalidInput, err)
+}
+
+/// 不校验证书以及域名，对应`is_tls_insecure`
+#[cfg(feature = "tls-rustls")]
+mod insecure {
+    use std::sync::Arc;
+
+    use rustls::client::danger::{HandshakeSignatureValid, ServerCertVerified, ServerCertVerifier};
+    use rustls::crypto::{CryptoProvider, verify_tls12_signature, verify_tls13_signature};
+    use rustls::pki_types::{CertificateDer, ServerName, UnixTime};
+    use rustls::{DigitallySignedStruct, Error, SignatureScheme};
+
+    #[derive(Debug)]
+    pub(super) struct NoVerifier(pub(super) Arc<CryptoProvider>);
+
+    impl ServerCertVerifier for NoVerifier {
+        fn verify_server_cert(
+            &self, _: &CertificateDer<'_>, _: &[CertificateDer<'_>], _: &ServerName<'_>, _: &[u8], _: UnixTime,
+        ) -> Result<ServerCertVerified, Error> {
+            Ok(ServerCertVerified::assertion())
+        }
+
+        fn verify_tls12_signature(
+            &self, message: &[u8], cert: &CertificateDer<'_>, dss: &DigitallySignedStruct,
+        ) -> Result<HandshakeSignatureValid, Error> {
+            verify_tls12_signature(message, cert, dss, &self.0.signature_verification_algorithms)
+        }
+
+        fn verify_tls13_signature(
+            &self, message: &[u8], cert: &CertificateDer<'_>, dss: &DigitallySignedStruct,
+        ) -> Result<HandshakeSignatureValid, Error> {
+            verify_tls13_signature(message, cert, dss, &self.0.signature_verification_algorithms)
+        }
+
+        fn supported_verify_schemes(&self) -> Vec<SignatureScheme> {
+            self.0.signature_verification_algorithms.supported_schemes()
+        }
+    }
+}
@@ -1,11 +1,9 @@
 use core::panic;
 use std::borrow::BorrowMut;
-use std::cell::RefCell;
 use std::collections::HashMap;
 use std::env;
 use std::ops::{Deref, DerefMut};
 use std::process::Command;
-use std::rc::Rc;
 use std::sync::atomic::AtomicBool;
 use std::sync::{Arc, Mutex};
 use std::thread;
@@ -50,12 +48,12 @@ fn test_hash() {
                     }
                     _ => {}
                 },
-                Event::AOF(_) => {}
+                Event::AOF(_) | Event::Transaction(_) => {}
             }
         }
     }
 
-    start_redis_test("dictionary.rdb", 10000, Rc::new(RefCell::new(TestRdbHandler {})));
+    start_redis_test("dictionary.rdb", 10000, Arc::new(Mutex::new(TestRdbHandler {})));
 }
 
 #[test]
@@ -82,12 +80,12 @@ fn test_hash_1() {
                     }
                     _ => {}
                 },
-                Event::AOF(_) => {}
+                Event::AOF(_) | Event::Transaction(_) => {}
             }
         }
     }
 
-    start_redis_test("hash_as_ziplist.rdb", 10001, Rc::new(RefCell::new(TestRdbHandler {})));
+    start_redis_test("hash_as_ziplist.rdb", 10001, Arc::new(Mutex::new(TestRdbHandler {})));
 }
 
 #[test]
@@ -112,7 +110,7 @@ fn test_string() {
                     }
                     _ => {}
                 },
-                Event::AOF(_) => {}
+                Event::AOF(_) | Event::Transaction(_) => {}
             }
         }
     }
@@ -120,7 +118,7 @@ fn test_string() {
     start_redis_test(
         "easily_compressible_string_key.rdb",
         10002,
-        Rc::new(RefCell::new(TestRdbHandler {})),
+        Arc::new(Mutex::new(TestRdbHandler {})),
     );
 }
 
@@ -151,7 +149,7 @@ fn test_integer() {
                     }
                     _ => {}
                 },
-                Event::AOF(_) => {}
+                Event::AOF(_) | Event::Transaction(_) => {}
             }
         }
     }
@@ -159,7 +157,7 @@ fn test_integer() {
     start_redis_test(
         "integer_keys.rdb",
         10003,
-        Rc::new(RefCell::new(TestRdbHandler { map: HashMap::new() })),
+        Arc::new(Mutex::new(TestRdbHandler { map: HashMap::new() })),
     );
 }
 
@@ -191,14 +189,14 @@ fn test_intset16() {
                     }
                     _ => {}
                 },
-                Event::AOF(_) => {}
+                Event::AOF(_) | Event::Transaction(_) => {}
             }
         }
     }
     start_redis_test(
         "intset_16.rdb",
         10004,
-        Rc::new(RefCell::new(TestRdbHandler { map: HashMap::new() })),
+        Arc::new(Mutex::new(TestRdbHandler { map: HashMap::new() })),
     );
 }
 
@@ -230,14 +228,14 @@ fn test_intset32() {
                     }
                     _ => {}
                 },
-                Event::AOF(_) => {}
+                Event::AOF(_) | Event::Transaction(_) => {}
             }
         }
     }
     start_redis_test(
         "intset_32.rdb",
         10005,
-        Rc::new(RefCell::new(TestRdbHandler { map: HashMap::new() })),
+        Arc::new(Mutex::new(TestRdbHandler { map: HashMap::new() })),
     );
 }
 
@@ -269,14 +267,14 @@ fn test_intset64() {
                     }
                     _ => {}
                 },
-                Event::AOF(_) => {}
+                Event::AOF(_) | Event::Transaction(_) => {}
             }
         }
     }
     start_redis_test(
         "intset_64.rdb",
         10006,
-        Rc::new(RefCell::new(TestRdbHandler { map: HashMap::new() })),
+        Arc::new(Mutex::new(TestRdbHandler { map: HashMap::new() })),
     );
 }
 
@@ -302,11 +300,11 @@ fn test_keys_with_expiry() {
                     }
                     _ => {}
                 },
-                Event::AOF(_) => {}
+                Event::AOF(_) | Event::Transaction(_) => {}
             }
         }
     }
-    start_redis_test("keys_with_expiry.rdb", 10007, Rc::new(RefCell::new(TestRdbHandler {})));
+    start_redis_test("keys_with_expiry.rdb", 10007, Arc::new(Mutex::new(TestRdbHandler {})));
 }
 
 #[test]
@@ -340,14 +338,14 @@ fn test_linked_list() {
                     }
                     _ => {}
                 },
-                Event::AOF(_) => {}
+                Event::AOF(_) | Event::Transaction(_) => {}
             }
         }
     }
     start_redis_test(
         "linkedlist.rdb",
         10008,
-        Rc::new(RefCell::new(TestRdbHandler { list: vec![] })),
+        Arc::new(Mutex::new(TestRdbHandler { list: vec![] })),
     );
 }
 
@@ -374,15 +372,11 @@ fn test_multiple_database() {
                     }
                     _ => {}
                 },
-                Event::AOF(_) => {}
+                Event::AOF(_) | Event::Transaction(_) => {}
             }
         }
     }
-    start_redis_test(
-        "multiple_databases.rdb",
-        10009,
-        Rc::new(RefCell::new(TestRdbHandler {})),
-    );
+    start_redis_test("multiple_databases.rdb", 10009, Arc::new(Mutex::new(TestRdbHandler {})));
 }
 
 #[test]
@@ -413,14 +407,14 @@ fn test_regular_set() {
                     }
                     _ => {}
                 },
-                Event::AOF(_) => {}
+                Event::AOF(_) | Event::Transaction(_) => {}
             }
         }
     }
     start_redis_test(
         "regular_set.rdb",
         11110,
-        Rc::new(RefCell::new(TestRdbHandler { map: HashMap::new() })),
+        Arc::new(Mutex::new(TestRdbHandler { map: HashMap::new() })),
     );
 }
 
@@ -462,14 +456,14 @@ fn test_regular_sorted_set() {
                     }
                     _ => {}
                 },
-                Event::AOF(_) => {}
+                Event::AOF(_) | Event::Transaction(_) => {}
             }
         }
     }
     start_redis_test(
         "regular_sorted_set.rdb",
         10011,
-        Rc::new(RefCell::new(TestRdbHandler { map: HashMap::new() })),
+        Arc::new(Mutex::new(TestRdbHandler { map: HashMap::new() })),
     );
 }
 
@@ -500,14 +494,14 @@ fn test_zipmap_big_values() {
                     }
                     _ => {}
                 },
-                Event::AOF(_) => {}
+                Event::AOF(_) | Event::Transaction(_) => {}
             }
         }
     }
     start_redis_test(
         "zipmap_with_big_values.rdb",
         10012,
-        Rc::new(RefCell::new(TestRdbHandler { map: HashMap::new() })),
+        Arc::new(Mutex::new(TestRdbHandler { map: HashMap::new() })),
     );
 }
 
@@ -537,14 +531,14 @@ fn test_zipmap_compress() {
                     }
                     _ => {}
                 },
-                Event::AOF(_) => {}
+                Event::AOF(_) | Event::Transaction(_) => {}
             }
         }
     }
     start_redis_test(
         "zipmap_that_compresses_easily.rdb",
         10013,
-        Rc::new(RefCell::new(TestRdbHandler { map: HashMap::new() })),
+        Arc::new(Mutex::new(TestRdbHandler { map: HashMap::new() })),
     );
 }
 
@@ -573,14 +567,14 @@ fn test_zipmap_not_compress() {
                     }
                     _ => {}
                 },
-                Event::AOF(_) => {}
+                Event::AOF(_) | Event::Transaction(_) => {}
             }
         }
     }
     start_redis_test(
         "zipmap_that_doesnt_compress.rdb",
         10014,
-        Rc::new(RefCell::new(TestRdbHandler { map: HashMap::new() })),
+        Arc::new(Mutex::new(TestRdbHandler { map: HashMap::new() })),
     );
 }
 
@@ -613,14 +607,14 @@ fn test_ziplist() {
                     }
                     _ => {}
                 },
-                Event::AOF(_) => {}
+                Event::AOF(_) | Event::Transaction(_) => {}
             }
         }
     }
     start_redis_test(
         "ziplist_that_compresses_easily.rdb",
         10015,
-        Rc::new(RefCell::new(TestRdbHandler { list: vec![] })),
+        Arc::new(Mutex::new(TestRdbHandler { list: vec![] })),
     );
 }
 
@@ -698,6 +692,7 @@ fn test_aof() {
                         _ => {}
                     }
                 }
+                Event::Transaction(_) => {}
             }
         }
     }
@@ -706,39 +701,58 @@ fn test_aof() {
 
     let rc = cmd_count.clone();
 
-    let t = thread::spawn(move || {
-        let cmd_handler = TestCmdHandler { pid, count: rc };
+    let cmd_handler = TestCmdHandler { pid, count: rc };
 
-        let ip = String::from("127.0.0.1");
-        let conf = Config {
-            is_discard_rdb: false,
-            is_aof: true,
-            host: ip,
-            port,
-            password: String::from("123456"),
-            repl_id: String::from("?"),
-            repl_offset: -1,
-            read_timeout: None,
-            write_timeout: None,
-            #[cfg(feature = "tls")]
-            is_tls_enabled: false,
-            #[cfg(feature = "tls")]
-            is_tls_insecure: false,
-            #[cfg(feature = "tls")]
-            identity: None,
-            username: "".to_string(),
-            #[cfg(feature = "tls")]
-            identity_passwd: None,
-        };
-        let running = Arc::new(AtomicBool::new(true));
+    let ip = String::from("127.0.0.1");
+    let conf = Config {
+        is_discard_rdb: false,
+        is_aof: true,
+        host: ip,
+        port,
+        unix_socket_path: None,
+        password: String::from("123456"),
+        repl_id: String::from("?"),
+        repl_offset: -1,
+        read_timeout: None,
+        write_timeout: None,
+        is_resp3: false,
+        is_forward_getack: false,
+        is_group_transaction: false,
+        heartbeat_interval: Duration::from_secs(1),
+        rdb_spool_dir: None,
+        rdb_backup_path: None,
+        rdb_filter_only: None,
+        #[cfg(any(feature = "tls", feature = "tls-rustls"))]
+        is_tls_enabled: false,
+        #[cfg(any(feature = "tls", feature = "tls-rustls"))]
+        is_tls_insecure: false,
+        #[cfg(feature = "tls")]
+        identity: None,
+        username: "".to_string(),
+        #[cfg(feature = "tls")]
+        identity_passwd: None,
+        #[cfg(feature = "tls-rustls")]
+        tls_cert: None,
+        #[cfg(feature = "tls-rustls")]
+        tls_key: None,
+        #[cfg(feature = "tls-rustls")]
+        tls_ca_cert: None,
+        #[cfg(feature = "tls-rustls")]
+        tls_server_name: None,
+        #[cfg(all(feature = "tls", feature = "tls-rustls"))]
+        tls_backend: None,
+    };
+    let running = Arc::new(AtomicBool::new(true));
 
-        let mut builder = listener::Builder::new();
-        builder.with_config(conf);
-        builder.with_control_flag(running);
-        builder.with_event_handler(Rc::new(RefCell::new(cmd_handler)));
+    let mut builder = listener::Builder::new();
+    builder.with_config(conf);
+    builder.with_control_flag(running);
+    builder.with_event_handler(Arc::new(Mutex::new(cmd_handler)));
 
-        let mut redis_listener = builder.build();
+    // 在当前线程中构建，再交由其他线程运行
+    let mut redis_listener = builder.build().unwrap();
 
+    let t = thread::spawn(move || {
         if let Err(_) = redis_listener.start() {
             println!("redis-server closed");
         }
@@ -822,29 +836,47 @@ fn test_tls() {
         is_aof: false,
         host: host.to_string(),
         port: *port,
+        unix_socket_path: None,
         password: String::new(),
         repl_id: String::from("?"),
         repl_offset: -1,
         read_timeout: None,
         write_timeout: None,
-        #[cfg(feature = "tls")]
+        is_resp3: false,
+        is_forward_getack: false,
+        is_group_transaction: false,
+        heartbeat_interval: Duration::from_secs(1),
+        rdb_spool_dir: None,
+        rdb_backup_path: None,
+        rdb_filter_only: None,
+        #[cfg(any(feature = "tls", feature = "tls-rustls"))]
         is_tls_enabled: true,
-        #[cfg(feature = "tls")]
+        #[cfg(any(feature = "tls", feature = "tls-rustls"))]
         is_tls_insecure: true,
         #[cfg(feature = "tls")]
         identity: None,
         username: "".to_string(),
         #[cfg(feature = "tls")]
         identity_passwd: None,
+        #[cfg(feature = "tls-rustls")]
+        tls_cert: None,
+        #[cfg(feature = "tls-rustls")]
+        tls_key: None,
+        #[cfg(feature = "tls-rustls")]
+        tls_ca_cert: None,
+        #[cfg(feature = "tls-rustls")]
+        tls_server_name: None,
+        #[cfg(all(feature = "tls", feature = "tls-rustls"))]
+        tls_backend: None,
     };
     let running = Arc::new(AtomicBool::new(true));
 
     let mut builder = listener::Builder::new();
     builder.with_config(conf);
     builder.with_control_flag(running);
-    builder.with_event_handler(Rc::new(RefCell::new(NoOpEventHandler {})));
+    builder.with_event_handler(Arc::new(Mutex::new(NoOpEventHandler {})));
 
-    let mut redis_listener = builder.build();
+    let mut redis_listener = builder.build().unwrap();
     println!("connect to redis-server");
     if let Err(err) = redis_listener.start() {
         println!("error: {}", err);
@@ -854,7 +886,261 @@ fn test_tls() {
     context.stop_server();
 }
 
-fn start_redis_test(rdb: &str, port: u16, rdb_handler: Rc<RefCell<dyn EventHandler>>) {
+#[test]
+#[serial]
+fn test_shutdown() {
+    let port = 10018;
+    let pid = start_redis_server("dictionary.rdb", port);
+    // wait redis to start
+    sleep(Duration::from_secs(2));
+
+    let conf = Config {
+        is_discard_rdb: false,
+        is_aof: true,
+        host: String::from("127.0.0.1"),
+        port,
+        unix_socket_path: None,
+        password: String::new(),
+        repl_id: String::from("?"),
+        repl_offset: -1,
+        read_timeout: None,
+        write_timeout: None,
+        is_resp3: false,
+        is_forward_getack: false,
+        is_group_transaction: false,
+        heartbeat_interval: Duration::from_secs(1),
+        rdb_spool_dir: None,
+        rdb_backup_path: None,
+        rdb_filter_only: None,
+        #[cfg(any(feature = "tls", feature = "tls-rustls"))]
+        is_tls_enabled: false,
+        #[cfg(any(feature = "tls", feature = "tls-rustls"))]
+        is_tls_insecure: false,
+        #[cfg(feature = "tls")]
+        identity: None,
+        username: "".to_string(),
+        #[cfg(feature = "tls")]
+        identity_passwd: None,
+        #[cfg(feature = "tls-rustls")]
+        tls_cert: None,
+        #[cfg(feature = "tls-rustls")]
+        tls_key: None,
+        #[cfg(feature = "tls-rustls")]
+        tls_ca_cert: None,
+        #[cfg(feature = "tls-rustls")]
+        tls_server_name: None,
+        #[cfg(all(feature = "tls", feature = "tls-rustls"))]
+        tls_backend: None,
+    };
+    let mut builder = listener::Builder::new();
+    builder.with_config(conf);
+    builder.with_control_flag(Arc::new(AtomicBool::new(true)));
+    let mut redis_listener = builder.build().unwrap();
+    let handle = redis_listener.shutdown_handle();
+
+    let t = thread::spawn(move || {
+        let result = redis_listener.start();
+        (result, redis_listener.config.repl_offset)
+    });
+    // 没有任何写入，listener阻塞在读取上
+    sleep(Duration::from_secs(3));
+    let start = std::time::Instant::now();
+    handle.shutdown();
+    let (result, offset) = t.join().unwrap();
+    shutdown_redis(pid);
+
+    assert!(result.is_ok());
+    assert!(offset >= 0);
+    assert!(start.elapsed() < Duration::from_secs(1));
+}
+
+#[cfg(unix)]
+#[test]
+#[serial]
+fn test_unix_socket() {
+    let socket_path = env::temp_dir().join("redis-event-10019.sock");
+    let pid = Command::new("redis-server")
+        .arg("--port")
+        .arg("0")
+        .arg("--unixsocket")
+        .arg(&socket_path)
+        .arg("--daemonize")
+        .arg("no")
+        .arg("--dbfilename")
+        .arg("dictionary.rdb")
+        .arg("--dir")
+        .arg("./tests/rdb")
+        .arg("--loglevel")
+        .arg("warning")
+        .spawn()
+        .expect("failed to start redis-server")
+        .id();
+    // wait redis to start
+    sleep(Duration::from_secs(2));
+
+    struct TestRdbHandler {
+        count: Arc<Mutex<i32>>,
+    }
+
+    impl EventHandler for TestRdbHandler {
+        fn handle(&mut self, data: Event) {
+            if let Event::RDB(Object::Hash(hash)) = data {
+                assert_eq!(b"force_dictionary", hash.key);
+                *self.count.lock().unwrap() += 1;
+            }
+        }
+    }
+
+    let conf = Config {
+        is_discard_rdb: false,
+        is_aof: false,
+        host: String::new(),
+        port: 0,
+        unix_socket_path: Some(socket_path.to_string_lossy().to_string()),
+        username: "".to_string(),
+        password: String::new(),
+        repl_id: String::from("?"),
+        repl_offset: -1,
+        read_timeout: None,
+        write_timeout: None,
+        is_resp3: false,
+        is_forward_getack: false,
+        is_group_transaction: false,
+        heartbeat_interval: Duration::from_secs(1),
+        rdb_spool_dir: None,
+        rdb_backup_path: None,
+        rdb_filter_only: None,
+        #[cfg(any(feature = "tls", feature = "tls-rustls"))]
+        is_tls_enabled: false,
+        #[cfg(any(feature = "tls", feature = "tls-rustls"))]
+        is_tls_insecure: false,
+        #[cfg(feature = "tls")]
+        identity: None,
+        #[cfg(feature = "tls")]
+        identity_passwd: None,
+        #[cfg(feature = "tls-rustls")]
+        tls_cert: None,
+        #[cfg(feature = "tls-rustls")]
+        tls_key: None,
+        #[cfg(feature = "tls-rustls")]
+        tls_ca_cert: None,
+        #[cfg(feature = "tls-rustls")]
+        tls_server_name: None,
+        #[cfg(all(feature = "tls", feature = "tls-rustls"))]
+        tls_backend: None,
+    };
+    let count = Arc::new(Mutex::new(0));
+    let mut builder = listener::Builder::new();
+    builder.with_config(conf);
+    builder.with_control_flag(Arc::new(AtomicBool::new(true)));
+    builder.with_event_handler(Arc::new(Mutex::new(TestRdbHandler { count: count.clone() })));
+    let mut redis_listener = builder.build().unwrap();
+    let result = redis_listener.start();
+    shutdown_redis(pid);
+
+    result.expect("listen on unix socket failed");
+    assert_eq!(1, *count.lock().unwrap());
+}
+
+#[cfg(feature = "async")]
+#[test]
+#[serial]
+fn test_aof_async() {
+    use redis_event::aio::{self, AsyncEventHandler};
+
+    let port = 10017;
+    let pid = start_redis_server("dictionary.rdb", port);
+    // wait redis to start
+    sleep(Duration::from_secs(2));
+
+    struct TestCmdHandler {
+        running: Arc<AtomicBool>,
+        rdb_count: i32,
+    }
+
+    impl AsyncEventHandler for TestCmdHandler {
+        async fn handle(&mut self, event: Event<'_>) {
+            match event {
+                Event::RDB(_) => self.rdb_count += 1,
+                Event::AOF(cmd) => {
+                    if let cmd::Command::SET(set) = cmd {
+                        assert!(self.rdb_count > 0);
+                        assert_eq!(b"aa", set.key);
+                        assert_eq!(b"bb", set.value);
+                        self.running.store(false, std::sync::atomic::Ordering::SeqCst);
+                    }
+                }
+                Event::Transaction(_) => {}
+            }
+        }
+    }
+
+    let t = thread::spawn(move || {
+        let writer = thread::spawn(move || {
+            sleep(Duration::from_secs(3));
+            let uri = format!("redis://127.0.0.1:{}", port);
+            let client = redis::Client::open(uri.as_str()).unwrap();
+            let mut conn = client.get_connection().unwrap();
+            let _: () = conn.set("aa", "bb").unwrap();
+        });
+
+        let runtime = tokio::runtime::Builder::new_multi_thread()
+            .enable_all()
+            .build()
+            .unwrap();
+        let running = Arc::new(AtomicBool::new(true));
+        let conf = Config {
+            is_discard_rdb: false,
+            is_aof: true,
+            host: String::from("127.0.0.1"),
+            port,
+            unix_socket_path: None,
+            password: String::new(),
+            repl_id: String::from("?"),
+            repl_offset: -1,
+            read_timeout: None,
+            write_timeout: None,
+            is_resp3: false,
+            is_forward_getack: false,
+            is_group_transaction: false,
+            heartbeat_interval: Duration::from_secs(1),
+            rdb_spool_dir: None,
+            rdb_backup_path: None,
+            rdb_filter_only: None,
+            #[cfg(any(feature = "tls", feature = "tls-rustls"))]
+            is_tls_enabled: false,
+            #[cfg(any(feature = "tls", feature = "tls-rustls"))]
+            is_tls_insecure: false,
+            #[cfg(feature = "tls")]
+            identity: None,
+            username: "".to_string(),
+            #[cfg(feature = "tls")]
+            identity_passwd: None,
+            #[cfg(feature = "tls-rustls")]
+            tls_cert: None,
+            #[cfg(feature = "tls-rustls")]
+            tls_key: None,
+            #[cfg(feature = "tls-rustls")]
+            tls_ca_cert: None,
+            #[cfg(feature = "tls-rustls")]
+            tls_server_name: None,
+            #[cfg(all(feature = "tls", feature = "tls-rustls"))]
+            tls_backend: None,
+        };
+        let mut builder = aio::Builder::new();
+        builder.with_config(conf);
+        builder.with_control_flag(running.clone());
+        builder.with_event_handler(TestCmdHandler { running, rdb_count: 0 });
+        let mut redis_listener = builder.build().unwrap();
+        runtime.block_on(redis_listener.start()).unwrap();
+        writer.join().unwrap();
+    });
+    let result = t.join();
+    shutdown_redis(pid);
+    result.expect("async listener failed");
+}
+
+fn start_redis_test(rdb: &str, port: u16, rdb_handler: Arc<Mutex<dyn EventHandler + Send>>) {
     let pid = start_redis_server(rdb, port);
     // wait redis to start
     sleep(Duration::from_secs(2));
@@ -865,20 +1151,38 @@ fn start_redis_test(rdb: &str, port: u16, rdb_handler: Rc<RefCell<dyn EventHandl
         is_aof: false,
         host: ip,
         port: port,
+        unix_socket_path: None,
         username: "".to_string(),
         password: String::new(),
         repl_id: String::from("?"),
         repl_offset: -1,
         read_timeout: None,
         write_timeout: None,
-        #[cfg(feature = "tls")]
+        is_resp3: false,
+        is_forward_getack: false,
+        is_group_transaction: false,
+        heartbeat_interval: Duration::from_secs(1),
+        rdb_spool_dir: None,
+        rdb_backup_path: None,
+        rdb_filter_only: None,
+        #[cfg(any(feature = "tls", feature = "tls-rustls"))]
         is_tls_enabled: false,
-        #[cfg(feature = "tls")]
+        #[cfg(any(feature = "tls", feature = "tls-rustls"))]
         is_tls_insecure: false,
         #[cfg(feature = "tls")]
         identity: None,
         #[cfg(feature = "tls")]
         identity_passwd: None,
+        #[cfg(feature = "tls-rustls")]
+        tls_cert: None,
+        #[cfg(feature = "tls-rustls")]
+        tls_key: None,
+        #[cfg(feature = "tls-rustls")]
+        tls_ca_cert: None,
+        #[cfg(feature = "tls-rustls")]
+        tls_server_name: None,
+        #[cfg(all(feature = "tls", feature = "tls-rustls"))]
+        tls_backend: None,
     };
     let running = Arc::new(AtomicBool::new(true));
 
@@ -887,7 +1191,7 @@ fn start_redis_test(rdb: &str, port: u16, rdb_handler: Rc<RefCell<dyn EventHandl
     builder.with_control_flag(running);
     builder.with_event_handler(rdb_handler);
 
-    let mut redis_listener = builder.build();
+    let mut redis_listener = builder.build().unwrap();
 
     if let Err(error) = redis_listener.start() {
         eprintln!("error: {}", error);